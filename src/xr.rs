@@ -1,8 +1,14 @@
 use crate::store::upgrade_store::{UpgradeModule, UpgradeStore};
+use crate::xr_lab_grid::nanopoly::core::nanopoly_object::{
+    BciInterface, EnergeticProfile, NanopolyObject,
+};
+use crate::xr_lab_grid::nanopoly::generators;
+use crate::xr_lab_grid::nanopoly::lifecycle::LifecycleError;
 use crate::xr_lab_grid::nanopoly::nanopolygon::{
-    BiophysicalMetadata, BioAffinityTarget, Nanopolygon, SurfaceCharge, VertexNm, Edge,
+    BiophysicalMetadata, BioAffinityTarget, SurfaceChemistry, SurfaceLigand,
 };
 use crate::xr_lab_grid::nanopoly::nanoswarm::{Nanoswarm, NanoswarmMember};
+use crate::xr_lab_grid::nanopoly::transform::{Quaternion, RigidTransform};
 
 pub struct XrSession {
     pub swarm: Nanoswarm,
@@ -20,7 +26,10 @@ impl XrSession {
             delta_energy_d: 0.05,
             delta_dw: 0.10,
             allowed_targets: vec![BioAffinityTarget::NeuralMembrane],
-            max_allowed_charge: SurfaceCharge::Neutral,
+            // Anionic through neutral; cationic surfaces disrupt membranes.
+            allowed_zeta_mv: (-60.0, 10.0),
+            required_chemistries: vec![SurfaceChemistry::Peg],
+            forbidden_chemistries: vec![SurfaceChemistry::Amine, SurfaceChemistry::Alkyl],
         });
 
         let swarm = Nanoswarm::new(session_id);
@@ -28,43 +37,60 @@ impl XrSession {
         Self { swarm, store }
     }
 
-    pub fn spawn_nanopolygon_member(&mut self) {
-        let vertices = vec![
-            VertexNm { x_nm: 0.0, y_nm: 0.0, z_nm: 0.0 },
-            VertexNm { x_nm: 50.0, y_nm: 0.0, z_nm: 0.0 },
-            VertexNm { x_nm: 25.0, y_nm: 43.3, z_nm: 0.0 },
-        ];
-
-        let edges = vec![
-            Edge { start_index: 0, end_index: 1 },
-            Edge { start_index: 1, end_index: 2 },
-            Edge { start_index: 2, end_index: 0 },
-        ];
-
+    pub fn spawn_nanopolygon_member(&mut self) -> Result<(), LifecycleError> {
         let bio = BiophysicalMetadata {
             target: BioAffinityTarget::NeuralMembrane,
-            surface_charge: SurfaceCharge::Neutral,
+            zeta_potential_mv: -5.0,
             hydrophobicity_index: 0.4,
-            elastic_modulus_kpa: 5.0,
+            // Within the native range of neural tissue, so the stiffness gate passes.
+            elastic_modulus_kpa: 0.5,
+            ligands: vec![SurfaceLigand {
+                chemistry: SurfaceChemistry::Peg,
+                density_per_nm2: 0.5,
+            }],
         };
 
-        let poly = Nanopolygon::new("poly_tri_01", vertices, edges, bio);
+        // Ids stay unique, even after removals, so members can be addressed by id.
+        let mut n = self.swarm.members.len() + 1;
+        while self.swarm.member_index(&format!("poly_tri_{:02}", n)).is_some() {
+            n += 1;
+        }
+        let id = format!("poly_tri_{:02}", n);
 
-        let member = NanoswarmMember {
+        // Equilateral triangle with 50 nm sides.
+        let poly =
+            generators::polygon_tile(&id, 3, 50.0 / 3.0_f64.sqrt(), bio).map_err(|errors| {
+                LifecycleError::InvalidGeometry {
+                    id: id.clone(),
+                    errors,
+                }
+            })?;
+
+        // Spawn side by side along +x so repeated spawns stay clear of each other.
+        let offset_nm = 60.0 * self.swarm.members.len() as f64;
+        let object = NanopolyObject::from_parts(
+            &id,
             poly,
-            basal_glucose_uW: 100.0,
-        };
+            EnergeticProfile::constant(100.0),
+            BciInterface::none(),
+            // Members carry the swarm's host governance.
+            self.swarm.governance.clone(),
+        );
+        let member = NanoswarmMember::new(
+            object,
+            RigidTransform::new(Quaternion::identity(), [offset_nm, 0.0, 0.0]),
+        );
 
-        self.swarm.add_member(member);
+        self.swarm.add_member(member)
     }
 
-    pub fn evaluate_swarm(&self) -> crate::store::metrics::ResponseMetric {
+    pub fn evaluate_swarm(&self) -> crate::xr_lab_grid::nanopoly::swarm_policy::PolicyVerdict {
         self.swarm.check_policy()
     }
 
     pub fn evaluate_first_member_upgrade(&self) -> Option<crate::store::upgrade_store::UpgradeDecision> {
         let member = self.swarm.members.first()?;
         let module = self.store.inventory.first()?;
-        Some(self.store.evaluate_upgrade(&member.poly, module))
+        Some(self.store.evaluate_upgrade(member.poly(), module))
     }
 }
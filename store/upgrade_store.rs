@@ -1,6 +1,8 @@
 use crate::store::metrics::ResponseMetric;
+use crate::xr_lab_grid::nanopoly::charge;
+use crate::xr_lab_grid::nanopoly::stiffness::StiffnessMismatch;
 use crate::xr_lab_grid::nanopoly::nanopolygon::{
-    Nanopolygon, SurfaceCharge, BioAffinityTarget,
+    Nanopolygon, BioAffinityTarget, BiophysicalMetadata, SurfaceChemistry,
 };
 
 #[derive(Clone, Debug)]
@@ -11,7 +13,30 @@ pub struct UpgradeModule {
     pub delta_energy_d: f32,
     pub delta_dw: f32,
     pub allowed_targets: Vec<BioAffinityTarget>,
-    pub max_allowed_charge: SurfaceCharge,
+    /// Inclusive zeta potential range the module tolerates, in mV.
+    pub allowed_zeta_mv: (f32, f32),
+    /// Surface chemistries the particle must carry.
+    pub required_chemistries: Vec<SurfaceChemistry>,
+    /// Surface chemistries the particle must not carry.
+    pub forbidden_chemistries: Vec<SurfaceChemistry>,
+}
+
+impl UpgradeModule {
+    /// Required chemistries the particle lacks and forbidden ones it carries,
+    /// as note fragments; empty when the coating is acceptable.
+    pub fn chemistry_violations(&self, bio: &BiophysicalMetadata) -> Vec<String> {
+        let missing = self
+            .required_chemistries
+            .iter()
+            .filter(|c| !bio.has_chemistry(**c))
+            .map(|c| format!("missing required {:?}", c));
+        let present = self
+            .forbidden_chemistries
+            .iter()
+            .filter(|c| bio.has_chemistry(**c))
+            .map(|c| format!("carries forbidden {:?}", c));
+        missing.chain(present).collect()
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -40,30 +65,61 @@ impl UpgradeStore {
         poly: &Nanopolygon,
         module: &UpgradeModule,
     ) -> UpgradeDecision {
-        let target_ok = module
-            .allowed_targets
-            .iter()
-            .any(|t| *t as i32 == poly.bio.target as i32);
+        let target_ok = module.allowed_targets.contains(&poly.bio.target);
 
-        let charge_ok = match (&poly.bio.surface_charge, &module.max_allowed_charge) {
-            (SurfaceCharge::Positive, SurfaceCharge::Neutral) => false,
-            (SurfaceCharge::Positive, SurfaceCharge::Negative) => false,
-            _ => true,
-        };
+        let charge_ok =
+            charge::zeta_in_range(poly.bio.zeta_potential_mv, module.allowed_zeta_mv);
 
-        let allowed = target_ok && charge_ok;
+        // Elastic mismatch with the target tissue drives inflammation and scarring.
+        let stiffness = poly.stiffness_analysis();
+
+        let chemistry_violations = module.chemistry_violations(&poly.bio);
+
+        let allowed = target_ok
+            && charge_ok
+            && stiffness.is_tolerated()
+            && chemistry_violations.is_empty();
 
         let d = module.delta_energy_d;
-        let dw = module.delta_dw;
+        let dw = module.delta_dw + 0.1 * stiffness.penalty();
         let k = if allowed { 0.92 } else { 0.78 };
 
-        let notes = if allowed {
-            "Upgrade within nanopolygon biophysical and cortical constraints."
+        let mut notes = if allowed {
+            "Upgrade within nanopolygon biophysical and cortical constraints.".to_string()
         } else {
-            "Upgrade violates nanopolygon biophysical or cortical constraints."
+            "Upgrade violates nanopolygon biophysical or cortical constraints.".to_string()
         };
-
-        let metric = ResponseMetric::new(k, d, dw, notes);
+        if !charge_ok {
+            notes.push_str(&format!(
+                " Zeta potential {:.1} mV is outside the module range {:.1} to {:.1} mV.",
+                poly.bio.zeta_potential_mv, module.allowed_zeta_mv.0, module.allowed_zeta_mv.1
+            ));
+        }
+        if !chemistry_violations.is_empty() {
+            notes.push_str(&format!(
+                " Surface chemistry: {}.",
+                chemistry_violations.join(", ")
+            ));
+        }
+        let coating = poly.bio.coating();
+        if coating.strongest_target != poly.bio.target {
+            notes.push_str(&format!(
+                " Coating binds {:?} ({:.2}) more strongly than the declared {:?} ({:.2}).",
+                coating.strongest_target,
+                coating.affinity(&coating.strongest_target),
+                poly.bio.target,
+                coating.affinity(&poly.bio.target)
+            ));
+        }
+        if stiffness.mismatch != StiffnessMismatch::Matched {
+            notes.push(' ');
+            notes.push_str(&stiffness.describe());
+        }
+        // Shape is charged once, through the hazard flags: each lowers
+        // confidence, raises psych-risk and adds its note.
+        let metric = poly
+            .hazard_report()
+            .apply(&ResponseMetric::new(k, d, dw, &notes));
 
         UpgradeDecision {
             poly_id: poly.id.clone(),
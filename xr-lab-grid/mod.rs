@@ -1 +1,2 @@
 pub mod nanopoly;
+pub mod xrgrid;
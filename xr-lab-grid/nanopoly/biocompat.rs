@@ -0,0 +1,486 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::charge::ChargeThresholds;
+use super::nanopolygon::{BioAffinityTarget, Nanopolygon, SurfaceCharge};
+use super::stiffness;
+use crate::store::metrics::ResponseMetric;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BiocompatFactor {
+    SurfaceCharge,
+    Hydrophobicity,
+    Stiffness,
+    Geometry,
+}
+
+impl fmt::Display for BiocompatFactor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            BiocompatFactor::SurfaceCharge => "surface charge",
+            BiocompatFactor::Hydrophobicity => "hydrophobicity",
+            BiocompatFactor::Stiffness => "stiffness",
+            BiocompatFactor::Geometry => "geometry",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Relative weight of each factor in the combined penalty.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FactorWeights {
+    pub surface_charge: f32,
+    pub hydrophobicity: f32,
+    pub stiffness: f32,
+    pub geometry: f32,
+}
+
+/// Reference values for one tissue. Penalties are 0.0 inside the preferred
+/// ranges and reach 1.0 at the tolerance (or a decade off, for stiffness).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TissueReference {
+    pub target: BioAffinityTarget,
+    /// Penalty for a negative, neutral and positive surface, in that order.
+    pub charge_penalty: [f32; 3],
+    pub hydrophobicity_range: (f32, f32),
+    /// Distance outside the range at which the hydrophobicity penalty saturates.
+    pub hydrophobicity_tolerance: f32,
+    /// Native tissue modulus range in kPa.
+    pub modulus_range_kpa: (f32, f32),
+    pub weights: FactorWeights,
+    /// How well characterised the tissue response is; becomes K.
+    pub confidence: f32,
+    /// Host energy demand at full penalty; D scales linearly up to it.
+    pub demand_scale: f32,
+    /// Psych-risk at full penalty; high for neural tissue, low for matrix.
+    pub dw_sensitivity: f32,
+}
+
+impl TissueReference {
+    fn charge_penalty(&self, charge: &SurfaceCharge) -> f32 {
+        match charge {
+            SurfaceCharge::Negative => self.charge_penalty[0],
+            SurfaceCharge::Neutral => self.charge_penalty[1],
+            SurfaceCharge::Positive => self.charge_penalty[2],
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FactorScore {
+    pub factor: BiocompatFactor,
+    /// 0.0 (ideal) to 1.0 (worst).
+    pub penalty: f32,
+    pub weight: f32,
+    /// Share of the combined penalty, penalty * weight / total weight.
+    pub contribution: f32,
+}
+
+#[derive(Clone, Debug)]
+pub struct BiocompatScore {
+    pub poly_id: String,
+    pub target: BioAffinityTarget,
+    pub metric: ResponseMetric,
+    /// Combined weighted penalty in [0, 1].
+    pub penalty: f32,
+    pub factors: Vec<FactorScore>,
+    pub dominant: BiocompatFactor,
+}
+
+impl BiocompatScore {
+    pub fn factor(&self, factor: BiocompatFactor) -> Option<&FactorScore> {
+        self.factors.iter().find(|f| f.factor == factor)
+    }
+}
+
+/// Scores a polygon's biophysics and geometry against per-tissue reference tables.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BiocompatEngine {
+    pub references: Vec<TissueReference>,
+    /// Zeta potential cut-offs used to pick each table's charge penalty.
+    pub charge_thresholds: ChargeThresholds,
+}
+
+impl Default for BiocompatEngine {
+    fn default() -> Self {
+        Self {
+            references: BioAffinityTarget::ALL
+                .iter()
+                .map(default_reference)
+                .collect(),
+            charge_thresholds: ChargeThresholds::default(),
+        }
+    }
+}
+
+impl BiocompatEngine {
+    pub fn new(references: Vec<TissueReference>) -> Self {
+        Self {
+            references,
+            charge_thresholds: ChargeThresholds::default(),
+        }
+    }
+
+    pub fn reference(&self, target: &BioAffinityTarget) -> Option<&TissueReference> {
+        self.references.iter().find(|r| &r.target == target)
+    }
+
+    /// Replace the table for `reference.target`, or add it.
+    pub fn set_reference(&mut self, reference: TissueReference) {
+        self.references.retain(|r| r.target != reference.target);
+        self.references.push(reference);
+    }
+
+    /// Score against the polygon's declared target.
+    pub fn score(&self, poly: &Nanopolygon) -> Option<BiocompatScore> {
+        self.score_for(poly, &poly.bio.target)
+    }
+
+    /// Score against every tissue with a reference table, in table order.
+    pub fn score_all(&self, poly: &Nanopolygon) -> Vec<BiocompatScore> {
+        let charge = poly.bio.surface_charge_with(&self.charge_thresholds);
+        let geometric_risk = poly.geometric_descriptors().toxicity_risk();
+        self.references
+            .iter()
+            .map(|r| score_with(poly, r, &charge, geometric_risk))
+            .collect()
+    }
+
+    pub fn score_for(
+        &self,
+        poly: &Nanopolygon,
+        target: &BioAffinityTarget,
+    ) -> Option<BiocompatScore> {
+        let reference = self.reference(target)?;
+        let charge = poly.bio.surface_charge_with(&self.charge_thresholds);
+        let geometric_risk = poly.geometric_descriptors().toxicity_risk();
+        Some(score_with(poly, reference, &charge, geometric_risk))
+    }
+}
+
+impl Nanopolygon {
+    /// Score against the declared target with the built-in tables.
+    pub fn biocompatibility(&self) -> Option<BiocompatScore> {
+        BiocompatEngine::default().score(self)
+    }
+}
+
+fn score_with(
+    poly: &Nanopolygon,
+    reference: &TissueReference,
+    charge: &SurfaceCharge,
+    geometric_risk: f32,
+) -> BiocompatScore {
+    let bio = &poly.bio;
+    let w = &reference.weights;
+    let raw = [
+        (
+            BiocompatFactor::SurfaceCharge,
+            reference.charge_penalty(charge),
+            w.surface_charge,
+        ),
+        (
+            BiocompatFactor::Hydrophobicity,
+            range_penalty(
+                bio.effective_hydrophobicity(),
+                reference.hydrophobicity_range,
+                reference.hydrophobicity_tolerance,
+            ),
+            w.hydrophobicity,
+        ),
+        (
+            BiocompatFactor::Stiffness,
+            stiffness_penalty(bio.elastic_modulus_kpa, reference.modulus_range_kpa),
+            w.stiffness,
+        ),
+        (BiocompatFactor::Geometry, geometric_risk, w.geometry),
+    ];
+
+    let total_weight: f32 = raw.iter().map(|(_, _, w)| w.max(0.0)).sum();
+    let factors: Vec<FactorScore> = raw
+        .iter()
+        .map(|&(factor, penalty, weight)| {
+            let penalty = penalty.clamp(0.0, 1.0);
+            let weight = weight.max(0.0);
+            FactorScore {
+                factor,
+                penalty,
+                weight,
+                contribution: if total_weight > 0.0 {
+                    penalty * weight / total_weight
+                } else {
+                    0.0
+                },
+            }
+        })
+        .collect();
+    let penalty: f32 = factors.iter().map(|f| f.contribution).sum();
+
+    // Ties go to the earlier factor, so the explanation is stable.
+    let dominant = factors
+        .iter()
+        .fold(None::<&FactorScore>, |best, f| match best {
+            Some(b) if b.contribution >= f.contribution => Some(b),
+            _ => Some(f),
+        })
+        .map_or(BiocompatFactor::Geometry, |f| f.factor);
+
+    let notes = explain(reference, &factors, penalty, dominant);
+    let metric = ResponseMetric::new(
+        reference.confidence,
+        reference.demand_scale * penalty,
+        reference.dw_sensitivity * penalty,
+        &notes,
+    );
+
+    BiocompatScore {
+        poly_id: poly.id.clone(),
+        target: reference.target.clone(),
+        metric,
+        penalty,
+        factors,
+        dominant,
+    }
+}
+
+fn explain(
+    reference: &TissueReference,
+    factors: &[FactorScore],
+    penalty: f32,
+    dominant: BiocompatFactor,
+) -> String {
+    if penalty <= 0.0 {
+        return format!(
+            "{:?}: all factors within reference ranges.",
+            reference.target
+        );
+    }
+    let share = factors
+        .iter()
+        .find(|f| f.factor == dominant)
+        .map_or(0.0, |f| f.contribution / penalty);
+    let detail: Vec<String> = factors
+        .iter()
+        .map(|f| format!("{} {:.2}", f.factor, f.penalty))
+        .collect();
+    format!(
+        "{:?}: penalty {:.2}, dominated by {} ({:.0}% of total); {}.",
+        reference.target,
+        penalty,
+        dominant,
+        100.0 * share,
+        detail.join(", ")
+    )
+}
+
+fn range_penalty(value: f32, (lo, hi): (f32, f32), tolerance: f32) -> f32 {
+    let outside = if value < lo {
+        lo - value
+    } else if value > hi {
+        value - hi
+    } else {
+        0.0
+    };
+    if tolerance > 0.0 {
+        (outside / tolerance).clamp(0.0, 1.0)
+    } else if outside > 0.0 {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+// Decades of mismatch against the nearest end of the native range.
+fn stiffness_penalty(modulus_kpa: f32, range_kpa: (f32, f32)) -> f32 {
+    stiffness::mismatch_decades(modulus_kpa, range_kpa).clamp(0.0, 1.0)
+}
+
+/// Built-in tables. Moduli come from `BioAffinityTarget::modulus_range_kpa`;
+/// cationic surfaces are penalised hardest on membranes.
+pub fn default_reference(target: &BioAffinityTarget) -> TissueReference {
+    let weights = |charge, hydro, stiff, geometry| FactorWeights {
+        surface_charge: charge,
+        hydrophobicity: hydro,
+        stiffness: stiff,
+        geometry,
+    };
+    match target {
+        BioAffinityTarget::NeuralMembrane => TissueReference {
+            target: target.clone(),
+            charge_penalty: [0.25, 0.05, 0.85],
+            hydrophobicity_range: (0.2, 0.5),
+            hydrophobicity_tolerance: 0.3,
+            modulus_range_kpa: target.modulus_range_kpa(),
+            weights: weights(0.30, 0.20, 0.25, 0.25),
+            confidence: 0.88,
+            demand_scale: 0.6,
+            dw_sensitivity: 0.8,
+        },
+        BioAffinityTarget::GlialCell => TissueReference {
+            target: target.clone(),
+            charge_penalty: [0.2, 0.05, 0.75],
+            hydrophobicity_range: (0.2, 0.55),
+            hydrophobicity_tolerance: 0.3,
+            modulus_range_kpa: target.modulus_range_kpa(),
+            weights: weights(0.25, 0.20, 0.30, 0.25),
+            confidence: 0.85,
+            demand_scale: 0.55,
+            dw_sensitivity: 0.6,
+        },
+        BioAffinityTarget::EndothelialCell => TissueReference {
+            target: target.clone(),
+            charge_penalty: [0.1, 0.05, 0.6],
+            hydrophobicity_range: (0.1, 0.45),
+            hydrophobicity_tolerance: 0.35,
+            modulus_range_kpa: target.modulus_range_kpa(),
+            weights: weights(0.30, 0.25, 0.20, 0.25),
+            confidence: 0.85,
+            demand_scale: 0.5,
+            dw_sensitivity: 0.3,
+        },
+        BioAffinityTarget::MuscleFiber => TissueReference {
+            target: target.clone(),
+            charge_penalty: [0.15, 0.05, 0.5],
+            hydrophobicity_range: (0.2, 0.6),
+            hydrophobicity_tolerance: 0.35,
+            modulus_range_kpa: target.modulus_range_kpa(),
+            weights: weights(0.20, 0.20, 0.35, 0.25),
+            confidence: 0.82,
+            demand_scale: 0.5,
+            dw_sensitivity: 0.2,
+        },
+        BioAffinityTarget::ExtracellularMatrix => TissueReference {
+            target: target.clone(),
+            charge_penalty: [0.1, 0.1, 0.4],
+            hydrophobicity_range: (0.1, 0.7),
+            hydrophobicity_tolerance: 0.4,
+            modulus_range_kpa: target.modulus_range_kpa(),
+            weights: weights(0.20, 0.20, 0.30, 0.30),
+            confidence: 0.75,
+            demand_scale: 0.4,
+            dw_sensitivity: 0.1,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xr_lab_grid::nanopoly::generators;
+    use crate::xr_lab_grid::nanopoly::nanopolygon::BiophysicalMetadata;
+
+    fn sphere(zeta_mv: f32, hydrophobicity: f32, modulus_kpa: f32) -> Nanopolygon {
+        let bio = BiophysicalMetadata {
+            target: BioAffinityTarget::NeuralMembrane,
+            zeta_potential_mv: zeta_mv,
+            hydrophobicity_index: hydrophobicity,
+            elastic_modulus_kpa: modulus_kpa,
+            ligands: Vec::new(),
+        };
+        generators::icosphere("p", 100.0, 2, bio).unwrap()
+    }
+
+    // Neural table with geometry weighted out, so penalties are exact.
+    fn neural_without_geometry() -> BiocompatEngine {
+        let mut reference = default_reference(&BioAffinityTarget::NeuralMembrane);
+        reference.weights = FactorWeights {
+            surface_charge: 1.0,
+            hydrophobicity: 1.0,
+            stiffness: 1.0,
+            geometry: 0.0,
+        };
+        BiocompatEngine::new(vec![reference])
+    }
+
+    #[test]
+    fn range_penalty_is_linear_to_the_tolerance() {
+        assert_eq!(range_penalty(0.3, (0.2, 0.5), 0.3), 0.0);
+        assert!((range_penalty(0.65, (0.2, 0.5), 0.3) - 0.5).abs() < 1e-6);
+        assert!((range_penalty(0.05, (0.2, 0.5), 0.3) - 0.5).abs() < 1e-6);
+        assert_eq!(range_penalty(0.9, (0.2, 0.5), 0.3), 1.0);
+        assert_eq!(range_penalty(0.51, (0.2, 0.5), 0.0), 1.0);
+        assert_eq!(range_penalty(0.5, (0.2, 0.5), 0.0), 0.0);
+    }
+
+    #[test]
+    fn weighted_penalty_known_answer() {
+        // Cationic (0.85), 0.15 above the hydrophobicity range (0.5), and one
+        // decade stiffer than neural membrane (1.0).
+        let poly = sphere(30.0, 0.65, 10.0);
+        let score = neural_without_geometry().score(&poly).unwrap();
+        let penalties: Vec<f32> = score.factors.iter().map(|f| f.penalty).collect();
+        assert!((penalties[0] - 0.85).abs() < 1e-6);
+        assert!((penalties[1] - 0.5).abs() < 1e-5);
+        assert!((penalties[2] - 1.0).abs() < 1e-6);
+        assert_eq!(
+            score
+                .factor(BiocompatFactor::Geometry)
+                .unwrap()
+                .contribution,
+            0.0
+        );
+
+        let expected = (0.85 + 0.5 + 1.0) / 3.0;
+        assert!((score.penalty - expected).abs() < 1e-5);
+        assert_eq!(score.dominant, BiocompatFactor::Stiffness);
+        assert!((score.metric.knowledge_factor_k - 0.88).abs() < 1e-6);
+        assert!((score.metric.demand_d - 0.6 * expected).abs() < 1e-5);
+        assert!((score.metric.dracula_wave_dw - 0.8 * expected).abs() < 1e-5);
+        assert!(score
+            .metric
+            .notes
+            .contains("dominated by stiffness (43% of total)"));
+    }
+
+    #[test]
+    fn ties_go_to_the_earlier_factor() {
+        // Anionic (0.25) and 0.075 out of range (0.25); stiffness matched.
+        let poly = sphere(-30.0, 0.125, 0.5);
+        let score = neural_without_geometry().score(&poly).unwrap();
+        assert!((score.factors[0].penalty - score.factors[1].penalty).abs() < 1e-6);
+        assert_eq!(score.dominant, BiocompatFactor::SurfaceCharge);
+    }
+
+    #[test]
+    fn matched_particle_explains_itself() {
+        let poly = sphere(-5.0, 0.3, 0.5);
+        let mut engine = neural_without_geometry();
+        engine.references[0].charge_penalty[1] = 0.0;
+        let score = engine.score(&poly).unwrap();
+        assert_eq!(score.penalty, 0.0);
+        assert_eq!(
+            score.metric.notes,
+            "NeuralMembrane: all factors within reference ranges."
+        );
+
+        // Moving the negative cut-off above -5 mV reclassifies the surface.
+        engine.charge_thresholds.negative_below_mv = -4.0;
+        let score = engine.score(&poly).unwrap();
+        assert!((score.factors[0].penalty - 0.25).abs() < 1e-6);
+    }
+
+    #[test]
+    fn default_tables_cover_every_target() {
+        let engine = BiocompatEngine::default();
+        let poly = sphere(30.0, 0.3, 5.0);
+        let scores = engine.score_all(&poly);
+        let targets: Vec<BioAffinityTarget> = scores.iter().map(|s| s.target.clone()).collect();
+        assert_eq!(targets, BioAffinityTarget::ALL.to_vec());
+        // Cationic surfaces are penalised hardest on neural membranes.
+        let charge: Vec<f32> = scores
+            .iter()
+            .map(|s| s.factor(BiocompatFactor::SurfaceCharge).unwrap().penalty)
+            .collect();
+        assert_eq!(charge, vec![0.85, 0.75, 0.6, 0.5, 0.4]);
+
+        let mut engine = BiocompatEngine::new(Vec::new());
+        assert!(engine.score(&poly).is_none());
+        engine.set_reference(default_reference(&BioAffinityTarget::MuscleFiber));
+        engine.set_reference(default_reference(&BioAffinityTarget::MuscleFiber));
+        assert_eq!(engine.references.len(), 1);
+        assert!(engine
+            .score_for(&poly, &BioAffinityTarget::MuscleFiber)
+            .is_some());
+    }
+}
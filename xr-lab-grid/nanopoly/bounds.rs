@@ -0,0 +1,623 @@
+use serde::{Deserialize, Serialize};
+
+use super::geometry::{self, Vec3};
+use super::nanopolygon::{Face, Nanopolygon};
+use super::topology;
+
+/// Axis-aligned bounding box in nm.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Aabb {
+    pub min_nm: Vec3,
+    pub max_nm: Vec3,
+}
+
+impl Aabb {
+    pub fn from_points(points: &[Vec3]) -> Option<Self> {
+        let first = *points.first()?;
+        let mut min = first;
+        let mut max = first;
+        for p in &points[1..] {
+            for k in 0..3 {
+                min[k] = min[k].min(p[k]);
+                max[k] = max[k].max(p[k]);
+            }
+        }
+        Some(Self {
+            min_nm: min,
+            max_nm: max,
+        })
+    }
+
+    pub fn size_nm(&self) -> Vec3 {
+        geometry::sub(self.max_nm, self.min_nm)
+    }
+
+    pub fn center_nm(&self) -> Vec3 {
+        geometry::scale(geometry::add(self.min_nm, self.max_nm), 0.5)
+    }
+
+    pub fn contains(&self, p: Vec3) -> bool {
+        (0..3).all(|k| p[k] >= self.min_nm[k] && p[k] <= self.max_nm[k])
+    }
+
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        (0..3).all(|k| self.min_nm[k] <= other.max_nm[k] && other.min_nm[k] <= self.max_nm[k])
+    }
+
+    pub fn union(&self, other: &Aabb) -> Self {
+        let mut out = *self;
+        for k in 0..3 {
+            out.min_nm[k] = out.min_nm[k].min(other.min_nm[k]);
+            out.max_nm[k] = out.max_nm[k].max(other.max_nm[k]);
+        }
+        out
+    }
+
+    /// Gap between two boxes in nm; 0.0 when they touch or overlap.
+    pub fn distance_to(&self, other: &Aabb) -> f64 {
+        let mut gap = [0.0_f64; 3];
+        for (k, g) in gap.iter_mut().enumerate() {
+            *g = (other.min_nm[k] - self.max_nm[k])
+                .max(self.min_nm[k] - other.max_nm[k])
+                .max(0.0);
+        }
+        geometry::norm(gap)
+    }
+
+    pub fn expanded(&self, margin_nm: f64) -> Self {
+        Self {
+            min_nm: geometry::sub(self.min_nm, [margin_nm; 3]),
+            max_nm: geometry::add(self.max_nm, [margin_nm; 3]),
+        }
+    }
+}
+
+/// Oriented bounding box from the principal axes of the surface.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Obb {
+    pub center_nm: Vec3,
+    /// Orthonormal axes, largest spread first.
+    pub axes: [Vec3; 3],
+    pub half_extents_nm: Vec3,
+}
+
+impl Obb {
+    pub fn contains(&self, p: Vec3) -> bool {
+        let d = geometry::sub(p, self.center_nm);
+        (0..3).all(|k| geometry::dot(d, self.axes[k]).abs() <= self.half_extents_nm[k])
+    }
+
+    pub fn volume_nm3(&self) -> f64 {
+        8.0 * self.half_extents_nm[0] * self.half_extents_nm[1] * self.half_extents_nm[2]
+    }
+}
+
+/// Volume-integral properties of a closed mesh, per unit density.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MassProperties {
+    pub volume_nm3: f64,
+    pub center_of_mass_nm: Vec3,
+    /// Inertia tensor about the center of mass, row-major, in nm^5
+    /// (multiply by density for mass units).
+    pub inertia_nm5: [[f64; 3]; 3],
+}
+
+impl MassProperties {
+    pub fn mass_kg(&self, density_kg_m3: f64) -> f64 {
+        self.volume_nm3 * 1e-27 * density_kg_m3
+    }
+
+    /// Inertia tensor in kg·m² for a uniform density.
+    pub fn inertia_kg_m2(&self, density_kg_m3: f64) -> [[f64; 3]; 3] {
+        let k = density_kg_m3 * 1e-45;
+        self.inertia_nm5.map(|row| row.map(|v| v * k))
+    }
+}
+
+impl Nanopolygon {
+    pub fn aabb(&self) -> Option<Aabb> {
+        Aabb::from_points(&self.points())
+    }
+
+    /// Mean vertex position.
+    pub fn vertex_centroid(&self) -> Option<Vec3> {
+        let points = self.points();
+        if points.is_empty() {
+            return None;
+        }
+        let sum = points
+            .iter()
+            .fold([0.0; 3], |acc, &p| geometry::add(acc, p));
+        Some(geometry::scale(sum, 1.0 / points.len() as f64))
+    }
+
+    /// Area-weighted surface centroid; falls back to the vertex centroid.
+    pub fn surface_centroid(&self) -> Option<Vec3> {
+        let points = self.points();
+        let mut weighted = [0.0_f64; 3];
+        let mut total = 0.0_f64;
+        for t in self.triangulate() {
+            let (a, b, c) = (points[t[0]], points[t[1]], points[t[2]]);
+            let area = geometry::triangle_area(a, b, c);
+            let centre = geometry::scale(geometry::add(geometry::add(a, b), c), 1.0 / 3.0);
+            weighted = geometry::add(weighted, geometry::scale(centre, area));
+            total += area;
+        }
+        if total > 0.0 {
+            Some(geometry::scale(weighted, 1.0 / total))
+        } else {
+            self.vertex_centroid()
+        }
+    }
+
+    /// Oriented box along the principal axes of the area-weighted surface
+    /// covariance (vertex covariance when there is no surface).
+    pub fn obb(&self) -> Option<Obb> {
+        let points = self.points();
+        let center = self.surface_centroid()?;
+
+        let mut cov = [[0.0_f64; 3]; 3];
+        let triangles = self.triangulate();
+        if triangles.is_empty() {
+            for p in &points {
+                accumulate_outer(&mut cov, geometry::sub(*p, center), 1.0);
+            }
+        } else {
+            // Exact second moment of each triangle: A/12 * (sum d_i d_i^T + s s^T).
+            for t in &triangles {
+                let area = geometry::triangle_area(points[t[0]], points[t[1]], points[t[2]]);
+                let mut sum = [0.0_f64; 3];
+                for &i in t {
+                    let d = geometry::sub(points[i], center);
+                    accumulate_outer(&mut cov, d, area / 12.0);
+                    sum = geometry::add(sum, d);
+                }
+                accumulate_outer(&mut cov, sum, area / 12.0);
+            }
+        }
+
+        let axes = principal_axes(cov);
+        let mut lo = [f64::INFINITY; 3];
+        let mut hi = [f64::NEG_INFINITY; 3];
+        for p in &points {
+            let d = geometry::sub(*p, center);
+            for k in 0..3 {
+                let s = geometry::dot(d, axes[k]);
+                lo[k] = lo[k].min(s);
+                hi[k] = hi[k].max(s);
+            }
+        }
+
+        let mut box_center = center;
+        let mut half = [0.0_f64; 3];
+        for k in 0..3 {
+            box_center = geometry::add(box_center, geometry::scale(axes[k], 0.5 * (lo[k] + hi[k])));
+            half[k] = 0.5 * (hi[k] - lo[k]);
+        }
+
+        Some(Obb {
+            center_nm: box_center,
+            axes,
+            half_extents_nm: half,
+        })
+    }
+
+    /// Enclosed volume, center of mass and inertia for closed meshes (None
+    /// otherwise). Uses Eberly's polyhedral mass properties. Triangles are
+    /// oriented consistently first, since 3-cycles recovered from edges
+    /// carry no winding, and each closed shell counts as solid whichever
+    /// way it faces. Non-orientable surfaces yield None.
+    pub fn mass_properties(&self) -> Option<MassProperties> {
+        if !self.is_closed() {
+            return None;
+        }
+        let points = self.points();
+
+        let mut faces: Vec<Face> = self
+            .triangulate()
+            .into_iter()
+            .map(|t| Face {
+                vertex_indices: t.to_vec(),
+            })
+            .collect();
+        topology::orient_faces(&mut faces).ok()?;
+        let shells = topology::face_components(&faces);
+        let shell_count = shells.iter().map(|&s| s + 1).max().unwrap_or(0);
+
+        // 1, x, y, z, x^2, y^2, z^2, xy, yz, zx; summed per shell.
+        let mut per_shell = vec![[0.0_f64; 10]; shell_count];
+        for (face, &shell) in faces.iter().zip(&shells) {
+            let t = &face.vertex_indices;
+            let integral = &mut per_shell[shell];
+            let (p0, p1, p2) = (points[t[0]], points[t[1]], points[t[2]]);
+            let e1 = geometry::sub(p1, p0);
+            let e2 = geometry::sub(p2, p0);
+            let d = geometry::cross(e1, e2);
+
+            let (f1x, f2x, f3x, g0x, g1x, g2x) = subexpressions(p0[0], p1[0], p2[0]);
+            let (_, f2y, f3y, g0y, g1y, g2y) = subexpressions(p0[1], p1[1], p2[1]);
+            let (_, f2z, f3z, g0z, g1z, g2z) = subexpressions(p0[2], p1[2], p2[2]);
+
+            integral[0] += d[0] * f1x;
+            integral[1] += d[0] * f2x;
+            integral[2] += d[1] * f2y;
+            integral[3] += d[2] * f2z;
+            integral[4] += d[0] * f3x;
+            integral[5] += d[1] * f3y;
+            integral[6] += d[2] * f3z;
+            integral[7] += d[0] * (p0[1] * g0x + p1[1] * g1x + p2[1] * g2x);
+            integral[8] += d[1] * (p0[2] * g0y + p1[2] * g1y + p2[2] * g2y);
+            integral[9] += d[2] * (p0[0] * g0z + p1[0] * g1z + p2[0] * g2z);
+        }
+
+        let mult = [
+            1.0 / 6.0,
+            1.0 / 24.0,
+            1.0 / 24.0,
+            1.0 / 24.0,
+            1.0 / 60.0,
+            1.0 / 60.0,
+            1.0 / 60.0,
+            1.0 / 120.0,
+            1.0 / 120.0,
+            1.0 / 120.0,
+        ];
+        // An inward-facing shell flips the sign of every one of its integrals.
+        let mut integral = [0.0_f64; 10];
+        for shell in &per_shell {
+            let sign = if shell[0] < 0.0 { -1.0 } else { 1.0 };
+            for (k, v) in integral.iter_mut().enumerate() {
+                *v += sign * shell[k] * mult[k];
+            }
+        }
+
+        let volume = integral[0];
+        if volume <= 0.0 {
+            return None;
+        }
+        let c = [
+            integral[1] / volume,
+            integral[2] / volume,
+            integral[3] / volume,
+        ];
+
+        let ixx = integral[5] + integral[6] - volume * (c[1] * c[1] + c[2] * c[2]);
+        let iyy = integral[4] + integral[6] - volume * (c[2] * c[2] + c[0] * c[0]);
+        let izz = integral[4] + integral[5] - volume * (c[0] * c[0] + c[1] * c[1]);
+        let ixy = -(integral[7] - volume * c[0] * c[1]);
+        let iyz = -(integral[8] - volume * c[1] * c[2]);
+        let izx = -(integral[9] - volume * c[2] * c[0]);
+
+        Some(MassProperties {
+            volume_nm3: volume,
+            center_of_mass_nm: c,
+            inertia_nm5: [[ixx, ixy, izx], [ixy, iyy, iyz], [izx, iyz, izz]],
+        })
+    }
+
+    pub fn volume_nm3(&self) -> Option<f64> {
+        self.mass_properties().map(|m| m.volume_nm3)
+    }
+}
+
+fn subexpressions(w0: f64, w1: f64, w2: f64) -> (f64, f64, f64, f64, f64, f64) {
+    let temp0 = w0 + w1;
+    let f1 = temp0 + w2;
+    let temp1 = w0 * w0;
+    let temp2 = temp1 + w1 * temp0;
+    let f2 = temp2 + w2 * f1;
+    let f3 = w0 * temp1 + w1 * temp2 + w2 * f2;
+    let g0 = f2 + w0 * (f1 + w0);
+    let g1 = f2 + w1 * (f1 + w1);
+    let g2 = f2 + w2 * (f1 + w2);
+    (f1, f2, f3, g0, g1, g2)
+}
+
+fn accumulate_outer(m: &mut [[f64; 3]; 3], d: Vec3, weight: f64) {
+    for i in 0..3 {
+        for j in 0..3 {
+            m[i][j] += weight * d[i] * d[j];
+        }
+    }
+}
+
+/// Eigenvectors of a symmetric 3x3 matrix (cyclic Jacobi), largest eigenvalue first.
+pub fn principal_axes(m: [[f64; 3]; 3]) -> [Vec3; 3] {
+    let mut a = m;
+    let mut v = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+
+    for _ in 0..50 {
+        let off = a[0][1].abs() + a[0][2].abs() + a[1][2].abs();
+        if off < 1e-12 * (a[0][0].abs() + a[1][1].abs() + a[2][2].abs()).max(1e-300) {
+            break;
+        }
+        for (p, q) in [(0, 1), (0, 2), (1, 2)] {
+            if a[p][q].abs() < 1e-300 {
+                continue;
+            }
+            let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+            let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+            let c = 1.0 / (t * t + 1.0).sqrt();
+            let s = t * c;
+
+            for row in a.iter_mut() {
+                let akp = row[p];
+                let akq = row[q];
+                row[p] = c * akp - s * akq;
+                row[q] = s * akp + c * akq;
+            }
+            let (row_p, row_q) = (a[p], a[q]);
+            for k in 0..3 {
+                a[p][k] = c * row_p[k] - s * row_q[k];
+                a[q][k] = s * row_p[k] + c * row_q[k];
+            }
+            for row in v.iter_mut() {
+                let vkp = row[p];
+                let vkq = row[q];
+                row[p] = c * vkp - s * vkq;
+                row[q] = s * vkp + c * vkq;
+            }
+        }
+    }
+
+    let mut order = [0usize, 1, 2];
+    order.sort_by(|&i, &j| a[j][j].total_cmp(&a[i][i]));
+    let column = |k: usize| [v[0][k], v[1][k], v[2][k]];
+    let x = column(order[0]);
+    let y = column(order[1]);
+    // Right-handed frame regardless of the solver's sign choices.
+    let z = geometry::cross(x, y);
+    [x, y, z]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xr_lab_grid::nanopoly::nanopolygon::{
+        BioAffinityTarget, BiophysicalMetadata, Edge, VertexNm,
+    };
+    use crate::xr_lab_grid::nanopoly::transform::{Quaternion, RigidTransform};
+
+    fn bio() -> BiophysicalMetadata {
+        BiophysicalMetadata {
+            target: BioAffinityTarget::ExtracellularMatrix,
+            zeta_potential_mv: -10.0,
+            hydrophobicity_index: 0.3,
+            elastic_modulus_kpa: 1.0,
+            ligands: Vec::new(),
+        }
+    }
+
+    // Unit right-angle tetrahedron with its corner at (1, 1, 1).
+    fn tetra_vertices() -> Vec<VertexNm> {
+        [
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0],
+        ]
+        .iter()
+        .map(|p| VertexNm {
+            x_nm: p[0] + 1.0,
+            y_nm: p[1] + 1.0,
+            z_nm: p[2] + 1.0,
+        })
+        .collect()
+    }
+
+    fn assert_tetra(m: &MassProperties) {
+        assert!((m.volume_nm3 - 1.0 / 6.0).abs() < 1e-12);
+        for k in 0..3 {
+            assert!((m.center_of_mass_nm[k] - 1.25).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn tetrahedron_from_faces() {
+        let faces = [[0, 2, 1], [0, 1, 3], [0, 3, 2], [1, 2, 3]]
+            .iter()
+            .map(|f| Face {
+                vertex_indices: f.to_vec(),
+            })
+            .collect();
+        let poly = Nanopolygon::from_faces("tet", tetra_vertices(), faces, bio());
+        assert_tetra(&poly.mass_properties().unwrap());
+    }
+
+    #[test]
+    fn tetrahedron_from_edges_matches_faces() {
+        let edges = [(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)]
+            .iter()
+            .map(|&(a, b)| Edge {
+                start_index: a,
+                end_index: b,
+            })
+            .collect();
+        let poly = Nanopolygon::new("tet", tetra_vertices(), edges, bio());
+        assert!(poly.is_closed());
+        assert_tetra(&poly.mass_properties().unwrap());
+    }
+
+    #[test]
+    fn inverted_and_mixed_winding_agree() {
+        let outward = [[0, 2, 1], [0, 1, 3], [0, 3, 2], [1, 2, 3]];
+        for flips in 0..16u32 {
+            let faces = outward
+                .iter()
+                .enumerate()
+                .map(|(i, f)| {
+                    let mut ring = f.to_vec();
+                    if flips & (1 << i) != 0 {
+                        ring.reverse();
+                    }
+                    Face {
+                        vertex_indices: ring,
+                    }
+                })
+                .collect();
+            let poly = Nanopolygon::from_faces("tet", tetra_vertices(), faces, bio());
+            assert_tetra(&poly.mass_properties().unwrap());
+        }
+    }
+
+    #[test]
+    fn cube_inertia_and_open_surface() {
+        let s = 2.0;
+        let vertices: Vec<VertexNm> = (0..8)
+            .map(|i| VertexNm {
+                x_nm: s * (i & 1) as f64,
+                y_nm: s * ((i >> 1) & 1) as f64,
+                z_nm: s * ((i >> 2) & 1) as f64,
+            })
+            .collect();
+        let quads = [
+            [0, 2, 3, 1],
+            [4, 5, 7, 6],
+            [0, 1, 5, 4],
+            [2, 6, 7, 3],
+            [0, 4, 6, 2],
+            [1, 3, 7, 5],
+        ];
+        let faces: Vec<Face> = quads
+            .iter()
+            .map(|f| Face {
+                vertex_indices: f.to_vec(),
+            })
+            .collect();
+        let cube = Nanopolygon::from_faces("cube", vertices.clone(), faces.clone(), bio());
+        let m = cube.mass_properties().unwrap();
+        assert!((m.volume_nm3 - 8.0).abs() < 1e-9);
+        // Solid cube about its centre: I = m s^2 / 6 on the diagonal.
+        for k in 0..3 {
+            assert!((m.center_of_mass_nm[k] - 1.0).abs() < 1e-12);
+            assert!((m.inertia_nm5[k][k] - 8.0 * 4.0 / 6.0).abs() < 1e-9);
+        }
+        assert!(m.inertia_nm5[0][1].abs() < 1e-9);
+
+        let open = Nanopolygon::from_faces("open", vertices, faces[..5].to_vec(), bio());
+        assert!(open.mass_properties().is_none());
+    }
+
+    // Closed box with one corner at the origin and outward quads.
+    fn cuboid(size: Vec3) -> Nanopolygon {
+        let vertices = (0..8)
+            .map(|i| VertexNm {
+                x_nm: size[0] * (i & 1) as f64,
+                y_nm: size[1] * ((i >> 1) & 1) as f64,
+                z_nm: size[2] * ((i >> 2) & 1) as f64,
+            })
+            .collect();
+        let faces = [
+            [0, 2, 3, 1],
+            [4, 5, 7, 6],
+            [0, 1, 5, 4],
+            [2, 6, 7, 3],
+            [0, 4, 6, 2],
+            [1, 3, 7, 5],
+        ]
+        .iter()
+        .map(|f| Face {
+            vertex_indices: f.to_vec(),
+        })
+        .collect();
+        Nanopolygon::from_faces("box", vertices, faces, bio())
+    }
+
+    #[test]
+    fn aabb_gaps_unions_and_margins() {
+        let a = Aabb::from_points(&[[0.0, 0.0, 0.0], [2.0, 1.0, 1.0]]).unwrap();
+        let b = Aabb::from_points(&[[5.0, 5.0, 1.0], [6.0, 6.0, 3.0]]).unwrap();
+        // Gaps of 3 and 4 along x and y, touching in z.
+        assert_eq!(a.distance_to(&b), 5.0);
+        assert_eq!(b.distance_to(&a), 5.0);
+        assert!(!a.intersects(&b));
+
+        let u = a.union(&b);
+        assert_eq!(u.min_nm, [0.0, 0.0, 0.0]);
+        assert_eq!(u.max_nm, [6.0, 6.0, 3.0]);
+        assert_eq!(u.center_nm(), [3.0, 3.0, 1.5]);
+        assert!(u.contains([6.0, 0.0, 3.0]));
+        assert!(!u.contains([6.1, 0.0, 0.0]));
+
+        // Touching boxes intersect and have no gap.
+        let grown = a.expanded(4.0);
+        assert_eq!(grown.size_nm(), [10.0, 9.0, 9.0]);
+        assert_eq!(grown.max_nm[1], b.min_nm[1]);
+        assert!(grown.intersects(&b));
+        assert_eq!(grown.distance_to(&b), 0.0);
+
+        assert!(Aabb::from_points(&[]).is_none());
+    }
+
+    #[test]
+    fn obb_follows_a_rotated_slab() {
+        let mut slab = cuboid([8.0, 2.0, 1.0]);
+        let turn = Quaternion::from_axis_angle([1.0, 2.0, 3.0], 0.8);
+        slab.rotate(&turn);
+
+        let obb = slab.obb().unwrap();
+        let half = obb.half_extents_nm;
+        assert!((half[0] - 4.0).abs() < 1e-6);
+        assert!((half[1] - 1.0).abs() < 1e-6);
+        assert!((half[2] - 0.5).abs() < 1e-6);
+        assert!((obb.volume_nm3() - 16.0).abs() < 1e-5);
+        // The long axis is the rotated x axis.
+        let long = turn.rotate([1.0, 0.0, 0.0]);
+        assert!((geometry::dot(obb.axes[0], long).abs() - 1.0).abs() < 1e-9);
+        for v in slab.points() {
+            assert!(obb.contains(geometry::add(
+                obb.center_nm,
+                geometry::scale(geometry::sub(v, obb.center_nm), 0.999)
+            )));
+        }
+
+        // The axis-aligned box of the same slab is looser.
+        let aabb = slab.aabb().unwrap();
+        let size = aabb.size_nm();
+        assert!(size[0] * size[1] * size[2] > 2.0 * obb.volume_nm3());
+    }
+
+    #[test]
+    fn mass_properties_move_with_the_body() {
+        let body = cuboid([4.0, 2.0, 1.0]);
+        let before = body.mass_properties().unwrap();
+        let pose = RigidTransform::new(
+            Quaternion::from_axis_angle([0.0, 1.0, 0.0], 1.2),
+            [10.0, -3.0, 7.0],
+        );
+        let mut moved = body.clone();
+        moved.apply_transform(&pose);
+        let after = moved.mass_properties().unwrap();
+
+        assert!((after.volume_nm3 - 8.0).abs() < 1e-9);
+        let expected = pose.apply(before.center_of_mass_nm);
+        for k in 0..3 {
+            assert!((after.center_of_mass_nm[k] - expected[k]).abs() < 1e-9);
+        }
+        // The trace of the inertia tensor is invariant under rotation.
+        let trace = |m: [[f64; 3]; 3]| m[0][0] + m[1][1] + m[2][2];
+        assert!((trace(after.inertia_nm5) - trace(before.inertia_nm5)).abs() < 1e-9);
+        // Box inertia about x: V (b^2 + c^2) / 12.
+        assert!((before.inertia_nm5[0][0] - 8.0 * 5.0 / 12.0).abs() < 1e-9);
+
+        // 8 nm^3 of water weighs 8e-24 kg.
+        assert!((before.mass_kg(1000.0) - 8.0e-24).abs() < 1e-36);
+        let si = before.inertia_kg_m2(1000.0);
+        assert!((si[0][0] - before.inertia_nm5[0][0] * 1e-42).abs() < 1e-54);
+    }
+
+    #[test]
+    fn principal_axes_sort_by_eigenvalue() {
+        let axes = principal_axes([[1.0, 0.0, 0.0], [0.0, 3.0, 0.0], [0.0, 0.0, 2.0]]);
+        let expected = [[0.0, 1.0, 0.0], [0.0, 0.0, 1.0], [1.0, 0.0, 0.0]];
+        for (axis, e) in axes.iter().zip(expected) {
+            assert!((geometry::dot(*axis, e).abs() - 1.0).abs() < 1e-12);
+        }
+
+        // Eigenvalues 3 and 1 along the diagonals of the xy plane.
+        let axes = principal_axes([[2.0, 1.0, 0.0], [1.0, 2.0, 0.0], [0.0, 0.0, 0.5]]);
+        let diagonal = [0.5f64.sqrt(), 0.5f64.sqrt(), 0.0];
+        assert!((geometry::dot(axes[0], diagonal).abs() - 1.0).abs() < 1e-9);
+        assert!((axes[2][2].abs() - 1.0).abs() < 1e-9);
+    }
+}
@@ -1,5 +1,3 @@
-use crate::nanopoly::species::Species;
-
 #[derive(Clone, Debug)]
 pub enum CanineApplication {
     VeterinaryNeurologySupport,
@@ -26,8 +24,8 @@ pub struct CanineHostBudget {
 
 impl CanineHostBudget {
     pub fn derive(body_mass_kg: f32, age_years: f32, chronic_condition_score: f32) -> Self {
-        let age_factor = (1.0 - (age_years / 20.0).clamp(0.0, 0.8)) as f32;
-        let condition_factor = (1.0 - chronic_condition_score.clamp(0.0, 0.7)) as f32;
+        let age_factor = 1.0 - (age_years / 20.0).clamp(0.0, 0.8);
+        let condition_factor = 1.0 - chronic_condition_score.clamp(0.0, 0.7);
 
         let base_d = 0.35_f32;   // conservative default from your table
         let base_dw = 0.10_f32;  // conservative psych-risk ceiling
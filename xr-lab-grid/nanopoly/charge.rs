@@ -0,0 +1,149 @@
+use serde::{Deserialize, Serialize};
+
+use super::nanopolygon::{BiophysicalMetadata, SurfaceCharge};
+
+/// Zeta potential cut-offs, in mV, separating the three charge classes.
+/// Values strictly between them are neutral.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ChargeThresholds {
+    pub negative_below_mv: f32,
+    pub positive_above_mv: f32,
+}
+
+impl Default for ChargeThresholds {
+    /// |zeta| under 10 mV is the usual cut-off for an effectively neutral particle.
+    fn default() -> Self {
+        Self {
+            negative_below_mv: -10.0,
+            positive_above_mv: 10.0,
+        }
+    }
+}
+
+impl ChargeThresholds {
+    pub fn classify(&self, zeta_mv: f32) -> SurfaceCharge {
+        if zeta_mv <= self.negative_below_mv {
+            SurfaceCharge::Negative
+        } else if zeta_mv >= self.positive_above_mv {
+            SurfaceCharge::Positive
+        } else {
+            SurfaceCharge::Neutral
+        }
+    }
+}
+
+impl SurfaceCharge {
+    /// Typical zeta potential for the class, used when only the class is known.
+    pub fn representative_zeta_mv(&self) -> f32 {
+        match self {
+            SurfaceCharge::Negative => -30.0,
+            SurfaceCharge::Neutral => 0.0,
+            SurfaceCharge::Positive => 30.0,
+        }
+    }
+}
+
+impl BiophysicalMetadata {
+    /// Charge class under the default thresholds.
+    pub fn surface_charge(&self) -> SurfaceCharge {
+        self.surface_charge_with(&ChargeThresholds::default())
+    }
+
+    pub fn surface_charge_with(&self, thresholds: &ChargeThresholds) -> SurfaceCharge {
+        thresholds.classify(self.zeta_potential_mv)
+    }
+}
+
+/// Inclusive zeta potential range in mV; NaN never falls inside.
+pub fn zeta_in_range(zeta_mv: f32, (lo, hi): (f32, f32)) -> bool {
+    zeta_mv >= lo && zeta_mv <= hi
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xr_lab_grid::nanopoly::nanopolygon::BioAffinityTarget;
+
+    #[test]
+    fn classify_switches_at_the_cut_offs() {
+        let t = ChargeThresholds::default();
+        assert_eq!(t.classify(-10.0), SurfaceCharge::Negative);
+        assert_eq!(t.classify(-9.99), SurfaceCharge::Neutral);
+        assert_eq!(t.classify(0.0), SurfaceCharge::Neutral);
+        assert_eq!(t.classify(9.99), SurfaceCharge::Neutral);
+        assert_eq!(t.classify(10.0), SurfaceCharge::Positive);
+        assert_eq!(t.classify(f32::NAN), SurfaceCharge::Neutral);
+
+        let strict = ChargeThresholds {
+            negative_below_mv: -25.0,
+            positive_above_mv: 25.0,
+        };
+        assert_eq!(strict.classify(-20.0), SurfaceCharge::Neutral);
+        assert_eq!(strict.classify(-25.0), SurfaceCharge::Negative);
+    }
+
+    #[test]
+    fn representative_zeta_classifies_back_to_its_class() {
+        let t = ChargeThresholds::default();
+        for class in [
+            SurfaceCharge::Negative,
+            SurfaceCharge::Neutral,
+            SurfaceCharge::Positive,
+        ] {
+            assert_eq!(t.classify(class.representative_zeta_mv()), class);
+        }
+    }
+
+    #[test]
+    fn zeta_range_is_inclusive_and_rejects_nan() {
+        assert!(zeta_in_range(-30.0, (-30.0, -10.0)));
+        assert!(zeta_in_range(-10.0, (-30.0, -10.0)));
+        assert!(!zeta_in_range(-9.0, (-30.0, -10.0)));
+        assert!(!zeta_in_range(f32::NAN, (f32::NEG_INFINITY, f32::INFINITY)));
+    }
+
+    #[test]
+    fn legacy_surface_charge_records_deserialize_to_zeta() {
+        let legacy = r#"{
+            "target": "GlialCell",
+            "surface_charge": "Positive",
+            "hydrophobicity_index": 0.3,
+            "elastic_modulus_kpa": 1.0
+        }"#;
+        let bio: BiophysicalMetadata = serde_json::from_str(legacy).unwrap();
+        assert_eq!(bio.zeta_potential_mv, 30.0);
+        assert_eq!(bio.surface_charge(), SurfaceCharge::Positive);
+        assert_eq!(bio.target, BioAffinityTarget::GlialCell);
+        assert!(bio.ligands.is_empty());
+
+        // A measured zeta wins over the stored class.
+        let both = legacy.replace(
+            r#""surface_charge": "Positive","#,
+            r#""surface_charge": "Positive", "zeta_potential_mv": -12.5,"#,
+        );
+        let bio: BiophysicalMetadata = serde_json::from_str(&both).unwrap();
+        assert_eq!(bio.zeta_potential_mv, -12.5);
+        assert_eq!(bio.surface_charge(), SurfaceCharge::Negative);
+
+        let neither = legacy.replace(r#""surface_charge": "Positive","#, "");
+        let err = serde_json::from_str::<BiophysicalMetadata>(&neither).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("missing field `zeta_potential_mv`"));
+    }
+
+    #[test]
+    fn current_records_round_trip() {
+        let bio = BiophysicalMetadata {
+            target: BioAffinityTarget::EndothelialCell,
+            zeta_potential_mv: -7.5,
+            hydrophobicity_index: 0.4,
+            elastic_modulus_kpa: 2.0,
+            ligands: Vec::new(),
+        };
+        let json = serde_json::to_string(&bio).unwrap();
+        assert!(!json.contains("surface_charge"));
+        let back: BiophysicalMetadata = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, bio);
+    }
+}
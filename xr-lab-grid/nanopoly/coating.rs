@@ -0,0 +1,247 @@
+use serde::{Deserialize, Serialize};
+
+use super::nanopolygon::{BioAffinityTarget, BiophysicalMetadata, SurfaceChemistry};
+
+// Binding propensity of an uncoated core, for every tissue.
+const BARE_AFFINITY: f32 = 0.2;
+
+/// Reference behaviour of one chemistry at full coverage.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ChemistryProperties {
+    /// Hydrophobicity index of a fully covered surface.
+    pub hydrophobicity: f32,
+    /// Density at which the chemistry covers the whole surface.
+    pub saturation_density_per_nm2: f32,
+    /// Binding propensity per tissue in [0, 1], in `BioAffinityTarget::ALL` order.
+    pub affinity: [f32; 5],
+}
+
+impl SurfaceChemistry {
+    /// Literature-typical values: small groups saturate at a few per nm^2,
+    /// polymers near one chain per nm^2 (brush regime), proteins at their
+    /// footprint. Affinities follow the receptor each ligand engages.
+    pub fn properties(&self) -> ChemistryProperties {
+        let props = |hydrophobicity, saturation_density_per_nm2, affinity| ChemistryProperties {
+            hydrophobicity,
+            saturation_density_per_nm2,
+            affinity,
+        };
+        match self {
+            // Stealth coating: suppresses adsorption everywhere.
+            SurfaceChemistry::Peg => props(0.15, 1.0, [0.05, 0.05, 0.05, 0.05, 0.05]),
+            SurfaceChemistry::Carboxyl => props(0.10, 4.0, [0.10, 0.15, 0.15, 0.15, 0.30]),
+            // Cationic groups adhere to anionic membranes.
+            SurfaceChemistry::Amine => props(0.20, 4.0, [0.60, 0.50, 0.50, 0.40, 0.40]),
+            SurfaceChemistry::Hydroxyl => props(0.05, 5.0, [0.10, 0.10, 0.10, 0.10, 0.10]),
+            SurfaceChemistry::Alkyl => props(0.90, 4.0, [0.60, 0.60, 0.50, 0.50, 0.40]),
+            // Integrin binding.
+            SurfaceChemistry::RgdPeptide => props(0.30, 0.1, [0.30, 0.50, 0.90, 0.60, 0.70]),
+            // Transferrin receptor, enriched on brain endothelium.
+            SurfaceChemistry::Transferrin => props(0.30, 0.01, [0.40, 0.40, 0.95, 0.30, 0.10]),
+            // LDL receptor family.
+            SurfaceChemistry::ApolipoproteinE => props(0.40, 0.02, [0.80, 0.70, 0.80, 0.20, 0.10]),
+            // CD44.
+            SurfaceChemistry::Hyaluronan => props(0.05, 0.2, [0.20, 0.50, 0.20, 0.20, 0.80]),
+        }
+    }
+}
+
+/// What the coating does to the surface as a whole.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CoatingProfile {
+    /// Covered share of the surface in [0, 1].
+    pub coverage: f32,
+    pub effective_hydrophobicity: f32,
+    /// Effective binding propensity per tissue, in `BioAffinityTarget::ALL` order.
+    pub affinities: Vec<(BioAffinityTarget, f32)>,
+    /// Tissue the coated particle binds most strongly.
+    pub strongest_target: BioAffinityTarget,
+}
+
+impl CoatingProfile {
+    pub fn from_bio(bio: &BiophysicalMetadata) -> Self {
+        let shares = coverage_shares(bio);
+        let coverage: f32 = shares.iter().map(|(_, s)| s).sum();
+        let bare = 1.0 - coverage;
+
+        let effective_hydrophobicity = bare * bio.hydrophobicity_index
+            + shares
+                .iter()
+                .map(|(p, s)| s * p.hydrophobicity)
+                .sum::<f32>();
+
+        let affinities: Vec<(BioAffinityTarget, f32)> = BioAffinityTarget::ALL
+            .iter()
+            .enumerate()
+            .map(|(i, target)| {
+                let coated: f32 = shares.iter().map(|(p, s)| s * p.affinity[i]).sum();
+                (target.clone(), bare * BARE_AFFINITY + coated)
+            })
+            .collect();
+
+        // Ties go to the declared target.
+        let strongest_target = affinities
+            .iter()
+            .fold(
+                (bio.target.clone(), affinity_of(&affinities, &bio.target)),
+                |best, (t, a)| if *a > best.1 { (t.clone(), *a) } else { best },
+            )
+            .0;
+
+        Self {
+            coverage,
+            effective_hydrophobicity,
+            affinities,
+            strongest_target,
+        }
+    }
+
+    pub fn affinity(&self, target: &BioAffinityTarget) -> f32 {
+        affinity_of(&self.affinities, target)
+    }
+}
+
+impl BiophysicalMetadata {
+    pub fn coating(&self) -> CoatingProfile {
+        CoatingProfile::from_bio(self)
+    }
+
+    /// Hydrophobicity as seen by tissue: bare core blended with the coating.
+    pub fn effective_hydrophobicity(&self) -> f32 {
+        self.coating().effective_hydrophobicity
+    }
+
+    /// Binding propensity toward the declared target.
+    pub fn effective_affinity(&self) -> f32 {
+        self.coating().affinity(&self.target)
+    }
+
+    pub fn has_chemistry(&self, chemistry: SurfaceChemistry) -> bool {
+        self.ligands
+            .iter()
+            .any(|l| l.chemistry == chemistry && l.density_per_nm2 > 0.0)
+    }
+}
+
+// Covered share per ligand. When the ligands together exceed full coverage
+// they share the surface in proportion to their own coverage.
+fn coverage_shares(bio: &BiophysicalMetadata) -> Vec<(ChemistryProperties, f32)> {
+    let raw: Vec<(ChemistryProperties, f32)> = bio
+        .ligands
+        .iter()
+        .filter(|l| l.density_per_nm2 > 0.0)
+        .map(|l| {
+            let props = l.chemistry.properties();
+            let share = (l.density_per_nm2 / props.saturation_density_per_nm2).min(1.0);
+            (props, share)
+        })
+        .collect();
+    let total: f32 = raw.iter().map(|(_, s)| s).sum();
+    if total > 1.0 {
+        raw.into_iter().map(|(p, s)| (p, s / total)).collect()
+    } else {
+        raw
+    }
+}
+
+fn affinity_of(affinities: &[(BioAffinityTarget, f32)], target: &BioAffinityTarget) -> f32 {
+    affinities
+        .iter()
+        .find(|(t, _)| t == target)
+        .map_or(BARE_AFFINITY, |(_, a)| *a)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xr_lab_grid::nanopoly::nanopolygon::SurfaceLigand;
+
+    fn coated(
+        core_hydrophobicity: f32,
+        ligands: &[(SurfaceChemistry, f32)],
+    ) -> BiophysicalMetadata {
+        BiophysicalMetadata {
+            target: BioAffinityTarget::NeuralMembrane,
+            zeta_potential_mv: -20.0,
+            hydrophobicity_index: core_hydrophobicity,
+            elastic_modulus_kpa: 1.0,
+            ligands: ligands
+                .iter()
+                .map(|&(chemistry, density_per_nm2)| SurfaceLigand {
+                    chemistry,
+                    density_per_nm2,
+                })
+                .collect(),
+        }
+    }
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-6
+    }
+
+    #[test]
+    fn bare_core_keeps_its_own_properties() {
+        let profile = coated(0.7, &[]).coating();
+        assert_eq!(profile.coverage, 0.0);
+        assert_eq!(profile.effective_hydrophobicity, 0.7);
+        assert!(profile.affinities.iter().all(|(_, a)| *a == BARE_AFFINITY));
+        // Every tissue ties, so the declared target stays strongest.
+        assert_eq!(profile.strongest_target, BioAffinityTarget::NeuralMembrane);
+    }
+
+    #[test]
+    fn partial_peg_blends_with_the_core() {
+        // Half of PEG's 1.0 /nm^2 saturation density.
+        let bio = coated(0.8, &[(SurfaceChemistry::Peg, 0.5)]);
+        let profile = bio.coating();
+        assert!(close(profile.coverage, 0.5));
+        assert!(close(
+            profile.effective_hydrophobicity,
+            0.5 * 0.8 + 0.5 * 0.15
+        ));
+        for (_, a) in &profile.affinities {
+            assert!(close(*a, 0.5 * 0.2 + 0.5 * 0.05));
+        }
+        assert!(close(bio.effective_hydrophobicity(), 0.475));
+    }
+
+    #[test]
+    fn saturated_transferrin_targets_endothelium() {
+        let bio = coated(0.5, &[(SurfaceChemistry::Transferrin, 0.05)]);
+        let profile = bio.coating();
+        assert_eq!(profile.coverage, 1.0);
+        assert_eq!(profile.strongest_target, BioAffinityTarget::EndothelialCell);
+        assert!(close(
+            profile.affinity(&BioAffinityTarget::EndothelialCell),
+            0.95
+        ));
+        assert!(close(bio.effective_affinity(), 0.4));
+    }
+
+    #[test]
+    fn over_full_coatings_share_the_surface() {
+        // Both ligands alone would cover the surface; each ends up with half.
+        let bio = coated(
+            0.9,
+            &[(SurfaceChemistry::Peg, 2.0), (SurfaceChemistry::Amine, 4.0)],
+        );
+        let profile = bio.coating();
+        assert!(close(profile.coverage, 1.0));
+        assert!(close(
+            profile.effective_hydrophobicity,
+            0.5 * 0.15 + 0.5 * 0.20
+        ));
+        assert!(close(
+            profile.affinity(&BioAffinityTarget::NeuralMembrane),
+            0.5 * 0.05 + 0.5 * 0.60
+        ));
+    }
+
+    #[test]
+    fn zero_density_ligands_are_ignored() {
+        let bio = coated(0.4, &[(SurfaceChemistry::Alkyl, 0.0)]);
+        assert!(!bio.has_chemistry(SurfaceChemistry::Alkyl));
+        assert_eq!(bio.coating().coverage, 0.0);
+        assert_eq!(bio.effective_hydrophobicity(), 0.4);
+    }
+}
@@ -0,0 +1,515 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use super::nanoswarm::Nanoswarm;
+use super::spatial::SwarmIndex;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CommsSettings {
+    /// Members whose surfaces are farther apart than this cannot talk.
+    pub link_range_nm: f64,
+    /// Bandwidth of a link between touching members; it falls linearly to
+    /// zero at the range limit.
+    pub peak_bandwidth_bps: f64,
+    /// Links slower than this are dropped.
+    pub min_bandwidth_bps: f64,
+    /// Id of the member that receives Rollback commands from the host and
+    /// relays them. Left unset, the first member added takes the role. Once
+    /// that member is removed, Rollback reaches no one until a new source is set.
+    pub rollback_source: Option<String>,
+}
+
+impl Default for CommsSettings {
+    fn default() -> Self {
+        Self {
+            link_range_nm: 150.0,
+            peak_bandwidth_bps: 1000.0,
+            min_bandwidth_bps: 1.0,
+            rollback_source: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CommLink {
+    pub a: usize,
+    pub b: usize,
+    pub separation_nm: f64,
+    pub bandwidth_bps: f64,
+}
+
+/// Undirected communication graph over swarm members, by member index.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CommGraph {
+    pub links: Vec<CommLink>,
+    /// `CommsSettings::rollback_source` as a member index; None when unset
+    /// or no longer in the swarm.
+    pub rollback_source: Option<usize>,
+    adjacency: Vec<Vec<usize>>,
+}
+
+impl CommGraph {
+    pub fn build(swarm: &Nanoswarm, settings: &CommsSettings) -> Self {
+        let index = SwarmIndex::build(swarm);
+        let range = settings.link_range_nm.max(0.0);
+        let links = index
+            .candidate_pairs(range)
+            .into_iter()
+            // A quarantined member's interface is cut; it neither talks nor relays.
+            .filter(|&(a, b)| {
+                swarm.members[a].lifecycle.is_active() && swarm.members[b].lifecycle.is_active()
+            })
+            .filter_map(|(a, b)| {
+                let separation_nm = index.separation_nm(a, b)?;
+                if separation_nm > range {
+                    return None;
+                }
+                let bandwidth_bps = if range > 0.0 {
+                    settings.peak_bandwidth_bps * (1.0 - separation_nm / range)
+                } else {
+                    settings.peak_bandwidth_bps
+                };
+                (bandwidth_bps >= settings.min_bandwidth_bps).then_some(CommLink {
+                    a,
+                    b,
+                    separation_nm,
+                    bandwidth_bps,
+                })
+            })
+            .collect();
+        let mut graph = Self::from_links(swarm.members.len(), links);
+        graph.rollback_source = settings
+            .rollback_source
+            .as_deref()
+            .and_then(|id| swarm.member_index(id));
+        graph
+    }
+
+    /// Links to members past `member_count`, or from a member to itself,
+    /// are dropped. The graph has no Rollback source.
+    pub fn from_links(member_count: usize, mut links: Vec<CommLink>) -> Self {
+        links.retain(|l| l.a < member_count && l.b < member_count && l.a != l.b);
+        let mut adjacency = vec![Vec::new(); member_count];
+        for link in &links {
+            adjacency[link.a].push(link.b);
+            adjacency[link.b].push(link.a);
+        }
+        for neighbours in &mut adjacency {
+            neighbours.sort_unstable();
+            neighbours.dedup();
+        }
+        Self {
+            links,
+            rollback_source: None,
+            adjacency,
+        }
+    }
+
+    pub fn member_count(&self) -> usize {
+        self.adjacency.len()
+    }
+
+    pub fn neighbours(&self, member: usize) -> &[usize] {
+        self.adjacency.get(member).map_or(&[], |n| n.as_slice())
+    }
+
+    pub fn link(&self, a: usize, b: usize) -> Option<&CommLink> {
+        self.links
+            .iter()
+            .find(|l| (l.a == a && l.b == b) || (l.a == b && l.b == a))
+    }
+
+    /// Hops from `from` to every member; None where unreachable.
+    pub fn hop_counts(&self, from: usize) -> Vec<Option<usize>> {
+        self.hop_counts_without(from, None)
+    }
+
+    pub fn hops(&self, a: usize, b: usize) -> Option<usize> {
+        self.hop_counts(a).get(b).copied().flatten()
+    }
+
+    /// Connected groups of members, each sorted, ordered by lowest member.
+    pub fn partitions(&self) -> Vec<Vec<usize>> {
+        let mut seen = vec![false; self.member_count()];
+        let mut partitions = Vec::new();
+        for start in 0..self.member_count() {
+            if seen[start] {
+                continue;
+            }
+            let mut group: Vec<usize> = self
+                .hop_counts(start)
+                .iter()
+                .enumerate()
+                .filter_map(|(i, h)| h.map(|_| i))
+                .collect();
+            for &i in &group {
+                seen[i] = true;
+            }
+            group.sort_unstable();
+            partitions.push(group);
+        }
+        partitions
+    }
+
+    /// True for an empty swarm or a single partition.
+    pub fn is_connected(&self) -> bool {
+        self.partitions().len() <= 1
+    }
+
+    /// Members whose loss splits their partition, in ascending order.
+    pub fn articulation_points(&self) -> Vec<usize> {
+        let n = self.member_count();
+        let mut discovered = vec![usize::MAX; n];
+        let mut low = vec![0; n];
+        let mut is_cut = vec![false; n];
+        let mut time = 0;
+
+        for root in 0..n {
+            if discovered[root] != usize::MAX {
+                continue;
+            }
+            discovered[root] = time;
+            low[root] = time;
+            time += 1;
+            let mut root_children = 0;
+            // (member, parent, next neighbour to visit)
+            let mut stack = vec![(root, usize::MAX, 0)];
+            while let Some(&mut (v, parent, ref mut next)) = stack.last_mut() {
+                if let Some(&w) = self.adjacency[v].get(*next) {
+                    *next += 1;
+                    if discovered[w] == usize::MAX {
+                        discovered[w] = time;
+                        low[w] = time;
+                        time += 1;
+                        if v == root {
+                            root_children += 1;
+                        }
+                        stack.push((w, v, 0));
+                    } else if w != parent {
+                        low[v] = low[v].min(discovered[w]);
+                    }
+                } else {
+                    stack.pop();
+                    if parent != usize::MAX {
+                        low[parent] = low[parent].min(low[v]);
+                        if parent != root && low[v] >= discovered[parent] {
+                            is_cut[parent] = true;
+                        }
+                    }
+                }
+            }
+            is_cut[root] = root_children > 1;
+        }
+
+        (0..n).filter(|&i| is_cut[i]).collect()
+    }
+
+    /// Who a Rollback issued at `source` reaches, and which relays it
+    /// depends on.
+    pub fn rollback_reach(&self, source: usize) -> RollbackReach {
+        self.reach_from(Some(source))
+    }
+
+    /// Reach from the graph's own Rollback source; without one, no one is
+    /// reached.
+    pub fn source_reach(&self) -> RollbackReach {
+        self.reach_from(self.rollback_source)
+    }
+
+    fn reach_from(&self, source: Option<usize>) -> RollbackReach {
+        let hops = match source {
+            Some(source) => self.hop_counts(source),
+            None => vec![None; self.member_count()],
+        };
+        let unreachable: Vec<usize> = (0..self.member_count())
+            .filter(|&i| hops[i].is_none())
+            .collect();
+        let max_hops = hops.iter().flatten().copied().max();
+
+        let critical_relays = self
+            .articulation_points()
+            .into_iter()
+            .filter(|&relay| Some(relay) != source && hops[relay].is_some())
+            .filter_map(|relay| {
+                // Reaching the relay at all means there is a source.
+                let without = self.hop_counts_without(source?, Some(relay));
+                let stranded: Vec<usize> = (0..self.member_count())
+                    .filter(|&i| i != relay && hops[i].is_some() && without[i].is_none())
+                    .collect();
+                (!stranded.is_empty()).then_some(CriticalRelay { relay, stranded })
+            })
+            .collect();
+
+        RollbackReach {
+            source,
+            hops,
+            max_hops,
+            unreachable,
+            critical_relays,
+        }
+    }
+
+    // Breadth-first hop counts, treating `removed` as gone.
+    fn hop_counts_without(&self, from: usize, removed: Option<usize>) -> Vec<Option<usize>> {
+        let mut hops = vec![None; self.member_count()];
+        if from >= self.member_count() || Some(from) == removed {
+            return hops;
+        }
+        hops[from] = Some(0);
+        let mut queue = VecDeque::from([from]);
+        while let Some(v) = queue.pop_front() {
+            let next = hops[v].map(|h| h + 1);
+            for &w in &self.adjacency[v] {
+                if hops[w].is_none() && Some(w) != removed {
+                    hops[w] = next;
+                    queue.push_back(w);
+                }
+            }
+        }
+        hops
+    }
+}
+
+/// Relay whose loss would cut members off from Rollback.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CriticalRelay {
+    pub relay: usize,
+    pub stranded: Vec<usize>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RollbackReach {
+    pub source: Option<usize>,
+    /// Hops from the source to each member.
+    pub hops: Vec<Option<usize>>,
+    /// Longest relay chain a Rollback travels.
+    pub max_hops: Option<usize>,
+    /// Members in another partition than the source, or every member when
+    /// there is no source; Rollback cannot reach them.
+    pub unreachable: Vec<usize>,
+    pub critical_relays: Vec<CriticalRelay>,
+}
+
+impl RollbackReach {
+    pub fn all_reachable(&self) -> bool {
+        self.unreachable.is_empty()
+    }
+}
+
+impl Nanoswarm {
+    pub fn comm_graph(&self) -> CommGraph {
+        CommGraph::build(self, &self.comms)
+    }
+
+    /// Rollback reach over active members. Quarantined and retired members
+    /// are already held, so they are not reported as unreachable or stranded.
+    pub fn rollback_reach(&self) -> RollbackReach {
+        let mut reach = self.comm_graph().source_reach();
+        let active = |i: &usize| self.members[*i].lifecycle.is_active();
+        reach.unreachable.retain(active);
+        for critical in &mut reach.critical_relays {
+            critical.stranded.retain(active);
+        }
+        reach.critical_relays.retain(|c| !c.stranded.is_empty());
+        reach
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xr_lab_grid::nanopoly::core::nanopoly_object::{
+        BciInterface, EnergeticProfile, NanopolyObject,
+    };
+    use crate::xr_lab_grid::nanopoly::generators;
+    use crate::xr_lab_grid::nanopoly::nanopolygon::{BioAffinityTarget, BiophysicalMetadata};
+    use crate::xr_lab_grid::nanopoly::nanoswarm::NanoswarmMember;
+    use crate::xr_lab_grid::nanopoly::transform::{Quaternion, RigidTransform};
+
+    fn graph(member_count: usize, pairs: &[(usize, usize)]) -> CommGraph {
+        let links = pairs
+            .iter()
+            .map(|&(a, b)| CommLink {
+                a,
+                b,
+                separation_nm: 10.0,
+                bandwidth_bps: 900.0,
+            })
+            .collect();
+        CommGraph::from_links(member_count, links)
+    }
+
+    fn relays(reach: &RollbackReach) -> Vec<(usize, Vec<usize>)> {
+        reach
+            .critical_relays
+            .iter()
+            .map(|c| (c.relay, c.stranded.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn path_relays_every_inner_member() {
+        let path = graph(4, &[(0, 1), (1, 2), (2, 3)]);
+        assert_eq!(path.hop_counts(0), [Some(0), Some(1), Some(2), Some(3)]);
+        assert_eq!(path.hops(3, 0), Some(3));
+        assert_eq!(path.partitions(), [vec![0, 1, 2, 3]]);
+        assert_eq!(path.articulation_points(), [1, 2]);
+
+        let reach = path.rollback_reach(0);
+        assert_eq!(reach.max_hops, Some(3));
+        assert!(reach.all_reachable());
+        assert_eq!(relays(&reach), [(1, vec![2, 3]), (2, vec![3])]);
+
+        // The source is never its own critical relay.
+        assert_eq!(relays(&path.rollback_reach(1)), [(2, vec![3])]);
+    }
+
+    #[test]
+    fn star_hinges_on_its_centre() {
+        let star = graph(5, &[(0, 1), (0, 2), (0, 3), (0, 4)]);
+        assert_eq!(star.neighbours(0), [1, 2, 3, 4]);
+        assert_eq!(star.articulation_points(), [0]);
+
+        let from_centre = star.rollback_reach(0);
+        assert_eq!(from_centre.max_hops, Some(1));
+        assert!(from_centre.critical_relays.is_empty());
+
+        let from_leaf = star.rollback_reach(3);
+        assert_eq!(from_leaf.max_hops, Some(2));
+        assert_eq!(relays(&from_leaf), [(0, vec![1, 2, 4])]);
+    }
+
+    #[test]
+    fn triangles_sharing_a_vertex_cut_only_there() {
+        let bowtie = graph(5, &[(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 2)]);
+        assert_eq!(bowtie.articulation_points(), [2]);
+        assert_eq!(bowtie.hop_counts(0)[4], Some(2));
+        assert_eq!(relays(&bowtie.rollback_reach(0)), [(2, vec![3, 4])]);
+        assert_eq!(relays(&bowtie.rollback_reach(3)), [(2, vec![0, 1])]);
+
+        // A second bridge between the triangles removes the cut.
+        let bridged = graph(5, &[(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 2), (1, 3)]);
+        assert!(bridged.articulation_points().is_empty());
+        assert!(bridged.rollback_reach(0).critical_relays.is_empty());
+    }
+
+    #[test]
+    fn disconnected_pairs_are_separate_partitions() {
+        let pairs = graph(4, &[(0, 1), (2, 3)]);
+        assert_eq!(pairs.partitions(), [vec![0, 1], vec![2, 3]]);
+        assert!(!pairs.is_connected());
+        assert_eq!(pairs.hop_counts(0), [Some(0), Some(1), None, None]);
+        assert_eq!(pairs.hops(1, 2), None);
+        assert!(pairs.articulation_points().is_empty());
+
+        let reach = pairs.rollback_reach(0);
+        assert_eq!(reach.unreachable, [2, 3]);
+        assert_eq!(reach.max_hops, Some(1));
+        assert!(!reach.all_reachable());
+
+        // Members with no links at all are partitions of one.
+        let lonely = graph(3, &[]);
+        assert_eq!(lonely.partitions(), [vec![0], vec![1], vec![2]]);
+        assert!(graph(0, &[]).is_connected());
+    }
+
+    #[test]
+    fn self_and_out_of_range_links_are_dropped() {
+        let g = graph(3, &[(0, 0), (0, 5), (7, 1), (0, 1), (1, 0)]);
+        assert_eq!(g.links.len(), 2);
+        assert_eq!(g.neighbours(0), [1]);
+        assert_eq!(g.neighbours(1), [0]);
+        assert!(g.neighbours(2).is_empty());
+        assert!(g.neighbours(7).is_empty());
+        assert_eq!(g.link(1, 0).map(|l| l.bandwidth_bps), Some(900.0));
+        assert!(g.link(0, 2).is_none());
+
+        // A source outside the graph reaches no one.
+        let reach = g.rollback_reach(9);
+        assert_eq!(reach.hops, [None, None, None]);
+        assert_eq!(reach.unreachable, [0, 1, 2]);
+        assert_eq!(reach.max_hops, None);
+        assert!(reach.critical_relays.is_empty());
+    }
+
+    // 10 nm spheres; centres 120 nm apart leave a 100 nm gap, inside the
+    // default 150 nm link range.
+    fn swarm(xs: &[f64]) -> Nanoswarm {
+        let bio = BiophysicalMetadata {
+            target: BioAffinityTarget::NeuralMembrane,
+            zeta_potential_mv: -30.0,
+            hydrophobicity_index: 0.25,
+            elastic_modulus_kpa: 0.5,
+            ligands: Vec::new(),
+        };
+        let mut swarm = Nanoswarm::new("did:example:owner");
+        for (i, &x) in xs.iter().enumerate() {
+            let id = format!("m{}", i);
+            let object = NanopolyObject::from_parts(
+                &id,
+                generators::icosphere(&id, 10.0, 1, bio.clone()).unwrap(),
+                EnergeticProfile::constant(100.0),
+                BciInterface::none(),
+                swarm.governance.clone(),
+            );
+            let pose = RigidTransform::new(Quaternion::identity(), [x, 0.0, 0.0]);
+            swarm
+                .admit_member(NanoswarmMember::new(object, pose), 0)
+                .unwrap();
+        }
+        swarm
+    }
+
+    #[test]
+    fn rollback_source_follows_its_member_id() {
+        let mut swarm = swarm(&[0.0, 120.0, 240.0, 360.0]);
+        assert_eq!(swarm.comms.rollback_source.as_deref(), Some("m0"));
+        assert_eq!(swarm.comm_graph().rollback_source, Some(0));
+
+        // Removing an earlier member moves the source's index, not its id.
+        swarm.comms.rollback_source = Some("m2".to_string());
+        swarm.remove_member("m0", 1, "explanted").unwrap();
+        let reach = swarm.rollback_reach();
+        assert_eq!(reach.source, Some(1));
+        assert_eq!(reach.hops, [Some(1), Some(0), Some(1)]);
+
+        // Without its source, Rollback reaches no one ...
+        swarm.remove_member("m2", 2, "explanted").unwrap();
+        assert_eq!(swarm.comms.rollback_source.as_deref(), Some("m2"));
+        let reach = swarm.rollback_reach();
+        assert_eq!(reach.source, None);
+        assert_eq!(reach.unreachable, [0, 1]);
+        // ... and a later admission does not quietly take over.
+        let mut late = swarm.members[0].clone();
+        late.object.set_id("m4");
+        late.pose.translation_nm = [240.0, 0.0, 0.0];
+        swarm.admit_member(late, 3).unwrap();
+        assert_eq!(swarm.rollback_reach().unreachable, [0, 1, 2]);
+
+        swarm.comms.rollback_source = Some("m4".to_string());
+        assert!(swarm.rollback_reach().all_reachable());
+    }
+
+    #[test]
+    fn held_members_are_left_out_of_rollback_reach() {
+        // Chain m0-m1-m2, and m3 on its own.
+        let mut split = swarm(&[0.0, 120.0, 240.0, 5000.0]);
+        let reach = split.rollback_reach();
+        assert_eq!(reach.unreachable, [3]);
+        assert_eq!(relays(&reach), [(1, vec![2])]);
+
+        // Held members are not reported as unreachable or stranded.
+        split.quarantine_member("m3", 1, "review").unwrap();
+        split.retire_member("m2", 1, "end of study").unwrap();
+        let reach = split.rollback_reach();
+        assert!(reach.all_reachable());
+        assert!(reach.critical_relays.is_empty());
+
+        // A quarantined relay no longer relays, which cuts m2 off.
+        let mut chain = swarm(&[0.0, 120.0, 240.0]);
+        chain.quarantine_member("m1", 1, "review").unwrap();
+        assert!(chain.comm_graph().links.is_empty());
+        assert_eq!(chain.rollback_reach().unreachable, [2]);
+    }
+}
@@ -1,13 +1,15 @@
-use crate::core::species::{HostType, SpeciesId};
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug)]
+use super::species::{HostBudgetProfile, HostType, SpeciesId};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ConsentState {
     Locked,
     Active,
     Suspended,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct GovernanceLayer {
     pub owner_did: String,
     pub consent_state: ConsentState,
@@ -21,3 +23,27 @@ pub struct GovernanceLayer {
     pub non_commercial: bool,
     pub no_entertainment: bool,
 }
+
+impl GovernanceLayer {
+    /// Active consent for an adult human owner, with no stake requirement
+    /// and no extra neurorights restrictions.
+    pub fn human(owner_did: &str) -> Self {
+        Self {
+            owner_did: owner_did.to_string(),
+            consent_state: ConsentState::Active,
+            min_citizen_stake: 0,
+            upgrade_revision: 0,
+            host_type: HostType::Human,
+            species: SpeciesId::HomoSapiens,
+            guardian_did: None,
+            veterinary_did: None,
+            non_commercial: false,
+            no_entertainment: false,
+        }
+    }
+
+    /// Default D/DW envelope for the host this layer governs.
+    pub fn host_budget(&self) -> HostBudgetProfile {
+        HostBudgetProfile::default_for(self.species.clone(), self.host_type.clone())
+    }
+}
@@ -0,0 +1,3 @@
+pub mod governance;
+pub mod nanopoly_object;
+pub mod species;
@@ -1,11 +1,97 @@
-#[derive(Clone, Debug)]
+use serde::{Deserialize, Serialize};
+
+use crate::xr_lab_grid::nanopoly::generators;
+use crate::xr_lab_grid::nanopoly::nanopolygon::{
+    BioAffinityTarget, BiophysicalMetadata, Edge, Face, Nanopolygon, VertexNm,
+};
+use crate::xr_lab_grid::nanopoly::shape_descriptor::{self, ShapeDescriptor};
+
+pub use super::governance::{ConsentState, GovernanceLayer};
+
+/// Vertex-cloud polygon record from before objects wrapped a `Nanopolygon`.
+/// The original three fields load as before; connectivity, id and
+/// biophysics were added later and are empty on old records.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct NanoPolygon {
     pub vertices_nm: Vec<[f64; 3]>,     // nanometer coordinates
     pub surface_area_nm2: f64,
-    pub curvature_signature: Vec<f64>,  // compressed shape invariant
+    pub curvature_signature: Vec<f64>,  // compressed shape invariant, see ShapeDescriptor::signature
+    #[serde(default)]
+    pub edges: Vec<Edge>,
+    #[serde(default)]
+    pub faces: Vec<Face>,
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub bio: Option<BiophysicalMetadata>,
+}
+
+impl NanoPolygon {
+    pub fn set_shape_descriptor(&mut self, descriptor: &ShapeDescriptor) {
+        self.curvature_signature = descriptor.signature();
+    }
+
+    /// Rebuild the descriptor for similarity search; None if no signature is stored.
+    pub fn shape_descriptor(&self, scale_nm: f64) -> Option<ShapeDescriptor> {
+        ShapeDescriptor::from_signature(&self.curvature_signature, scale_nm)
+    }
+
+    /// Canonical polygon for this record. The record's own id and metadata
+    /// win; `fallback_id` and `fallback_bio` fill in for old records. Old
+    /// records carry no connectivity, so their surface is rebuilt as the
+    /// convex hull of the vertices.
+    pub fn to_nanopolygon(
+        &self,
+        fallback_id: &str,
+        fallback_bio: BiophysicalMetadata,
+    ) -> Nanopolygon {
+        let id = self.id.as_deref().unwrap_or(fallback_id);
+        let bio = self.bio.clone().unwrap_or(fallback_bio);
+        let vertices: Vec<VertexNm> = self
+            .vertices_nm
+            .iter()
+            .map(|&p| VertexNm::from_vec3(p))
+            .collect();
+
+        if self.edges.is_empty() && self.faces.is_empty() {
+            let faces = generators::convex_hull_faces(&self.vertices_nm)
+                .into_iter()
+                .map(|vertex_indices| Face { vertex_indices })
+                .collect();
+            return Nanopolygon::from_faces(id, vertices, faces, bio);
+        }
+
+        let mut poly = Nanopolygon {
+            id: id.to_string(),
+            vertices,
+            edges: self.edges.clone(),
+            faces: self.faces.clone(),
+            surface_area_nm2: 0.0,
+            mean_curvature: 0.0,
+            bio,
+        };
+        poly.refresh_geometry();
+        poly
+    }
 }
 
-#[derive(Clone, Debug)]
+impl From<&Nanopolygon> for NanoPolygon {
+    /// Record form of a canonical polygon, with its signature at the default bin count.
+    fn from(poly: &Nanopolygon) -> Self {
+        let descriptor = ShapeDescriptor::from_nanopolygon(poly, shape_descriptor::DEFAULT_BINS);
+        Self {
+            vertices_nm: poly.points(),
+            surface_area_nm2: poly.surface_area_nm2,
+            curvature_signature: descriptor.signature(),
+            edges: poly.edges.clone(),
+            faces: poly.faces.clone(),
+            id: Some(poly.id.clone()),
+            bio: Some(poly.bio.clone()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum BioAttachmentMode {
     NeuralSynaptic,
     NeuralGlial,
@@ -14,7 +100,33 @@ pub enum BioAttachmentMode {
     ExtracellularMatrix,
 }
 
-#[derive(Clone, Debug)]
+impl From<BioAffinityTarget> for BioAttachmentMode {
+    fn from(target: BioAffinityTarget) -> Self {
+        match target {
+            BioAffinityTarget::NeuralMembrane => BioAttachmentMode::NeuralSynaptic,
+            BioAffinityTarget::GlialCell => BioAttachmentMode::NeuralGlial,
+            BioAffinityTarget::EndothelialCell => BioAttachmentMode::VascularEndothelial,
+            BioAffinityTarget::MuscleFiber => BioAttachmentMode::MuscularFiber,
+            BioAffinityTarget::ExtracellularMatrix => BioAttachmentMode::ExtracellularMatrix,
+        }
+    }
+}
+
+impl From<BioAttachmentMode> for BioAffinityTarget {
+    fn from(mode: BioAttachmentMode) -> Self {
+        match mode {
+            BioAttachmentMode::NeuralSynaptic => BioAffinityTarget::NeuralMembrane,
+            BioAttachmentMode::NeuralGlial => BioAffinityTarget::GlialCell,
+            BioAttachmentMode::VascularEndothelial => BioAffinityTarget::EndothelialCell,
+            BioAttachmentMode::MuscularFiber => BioAffinityTarget::MuscleFiber,
+            BioAttachmentMode::ExtracellularMatrix => BioAffinityTarget::ExtracellularMatrix,
+        }
+    }
+}
+
+// Field names are part of the stored record format.
+#[allow(non_snake_case)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct EnergeticProfile {
     pub basal_glucose_uW: f64,   // microwatt equivalent
     pub peak_glucose_uW: f64,
@@ -22,34 +134,247 @@ pub struct EnergeticProfile {
     pub hemodynamic_coupling: f64, // 0–1 coupling to blood flow
 }
 
-#[derive(Clone, Debug)]
+impl EnergeticProfile {
+    /// Steady draw with no peaks, turnover or blood-flow coupling.
+    pub fn constant(basal_glucose_uw: f64) -> Self {
+        Self {
+            basal_glucose_uW: basal_glucose_uw,
+            peak_glucose_uW: basal_glucose_uw,
+            protein_turnover_uW: 0.0,
+            hemodynamic_coupling: 0.0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct BciInterface {
     pub input_bands_hz: Vec<(f32, f32)>,  // e.g. (8.0, 12.0) for alpha
     pub output_bands_hz: Vec<(f32, f32)>,
     pub max_bit_rate_bps: f32,
 }
 
-#[derive(Clone, Debug)]
-pub enum ConsentState {
-    Locked,
-    Active,
-    Suspended,
-}
-
-#[derive(Clone, Debug)]
-pub struct GovernanceLayer {
-    pub owner_did: String,
-    pub consent_state: ConsentState,
-    pub min_citizen_stake: u64,      // CITIZEN token threshold
-    pub upgrade_revision: u64,
+impl BciInterface {
+    /// Passive particle with no neural I/O.
+    pub fn none() -> Self {
+        Self {
+            input_bands_hz: Vec::new(),
+            output_bands_hz: Vec::new(),
+            max_bit_rate_bps: 0.0,
+        }
+    }
 }
 
-#[derive(Clone, Debug)]
+/// Canonical nanopolygon object: geometry and biophysics in `polygon`, plus
+/// energy, BCI and governance. The attachment mode is the polygon's
+/// `BioAffinityTarget`, so the two cannot disagree.
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct NanopolyObject {
+    /// Always equal to `polygon.id`; `from_parts` and `set_id` keep them in step.
     pub id: String,
-    pub polygon: NanoPolygon,
-    pub attachment: BioAttachmentMode,
+    pub polygon: Nanopolygon,
     pub energy: EnergeticProfile,
     pub bci: BciInterface,
     pub gov: GovernanceLayer,
 }
+
+impl NanopolyObject {
+    pub fn from_parts(
+        id: &str,
+        mut polygon: Nanopolygon,
+        energy: EnergeticProfile,
+        bci: BciInterface,
+        gov: GovernanceLayer,
+    ) -> Self {
+        polygon.id = id.to_string();
+        Self {
+            id: id.to_string(),
+            polygon,
+            energy,
+            bci,
+            gov,
+        }
+    }
+
+    /// Inverse of `from_parts`.
+    pub fn into_parts(
+        self,
+    ) -> (
+        String,
+        Nanopolygon,
+        EnergeticProfile,
+        BciInterface,
+        GovernanceLayer,
+    ) {
+        (self.id, self.polygon, self.energy, self.bci, self.gov)
+    }
+
+    pub fn set_id(&mut self, id: &str) {
+        self.id = id.to_string();
+        self.polygon.id = id.to_string();
+    }
+
+    pub fn attachment(&self) -> BioAttachmentMode {
+        self.polygon.bio.target.clone().into()
+    }
+
+    pub fn set_attachment(&mut self, mode: BioAttachmentMode) {
+        self.polygon.bio.target = mode.into();
+    }
+
+    /// Curvature signature for similarity search, recomputed from the geometry.
+    pub fn shape_descriptor(&self, bins: usize) -> ShapeDescriptor {
+        ShapeDescriptor::from_nanopolygon(&self.polygon, bins)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xr_lab_grid::nanopoly::generators::PlatonicSolid;
+    use crate::xr_lab_grid::nanopoly::geometry;
+    use crate::xr_lab_grid::nanopoly::nanopolygon::{SurfaceChemistry, SurfaceLigand};
+
+    fn bio(target: BioAffinityTarget) -> BiophysicalMetadata {
+        BiophysicalMetadata {
+            target,
+            zeta_potential_mv: -12.0,
+            hydrophobicity_index: 0.35,
+            elastic_modulus_kpa: 4.0,
+            ligands: vec![SurfaceLigand {
+                chemistry: SurfaceChemistry::Peg,
+                density_per_nm2: 0.4,
+            }],
+        }
+    }
+
+    #[test]
+    fn record_round_trip_keeps_topology_id_and_bio() {
+        let rod = generators::cylinder("rod-7", 3.0, 20.0, 10, bio(BioAffinityTarget::MuscleFiber))
+            .unwrap();
+        let record = NanoPolygon::from(&rod);
+        let back = record.to_nanopolygon("unused", bio(BioAffinityTarget::GlialCell));
+        assert_eq!(back.id, "rod-7");
+        assert_eq!(back.bio, rod.bio);
+        assert_eq!(back.points(), rod.points());
+        assert_eq!(back.edges, rod.edges);
+        assert_eq!(back.faces, rod.faces);
+        assert_eq!(back.surface_area_nm2, rod.surface_area_nm2);
+        assert_eq!(back.mean_curvature, rod.mean_curvature);
+        assert_eq!(NanoPolygon::from(&back), record);
+
+        // JSON keeps everything but the last bit of some coordinates.
+        let json = serde_json::to_string(&record).unwrap();
+        let loaded: NanoPolygon = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            (&loaded.edges, &loaded.faces, &loaded.id, &loaded.bio),
+            (&record.edges, &record.faces, &record.id, &record.bio)
+        );
+        for (a, b) in loaded.vertices_nm.iter().zip(&record.vertices_nm) {
+            assert!(geometry::norm(geometry::sub(*a, *b)) < 1e-12);
+        }
+    }
+
+    #[test]
+    fn wire_round_trip_keeps_edges() {
+        let vertices = vec![
+            VertexNm::from_vec3([0.0, 0.0, 0.0]),
+            VertexNm::from_vec3([4.0, 0.0, 0.0]),
+            VertexNm::from_vec3([4.0, 3.0, 0.0]),
+        ];
+        let edges = vec![
+            Edge {
+                start_index: 0,
+                end_index: 1,
+            },
+            Edge {
+                start_index: 1,
+                end_index: 2,
+            },
+        ];
+        let wire = Nanopolygon::new("w", vertices, edges, bio(BioAffinityTarget::NeuralMembrane));
+        let back = NanoPolygon::from(&wire).to_nanopolygon("x", bio(BioAffinityTarget::GlialCell));
+        assert_eq!(back.edges, wire.edges);
+        assert!(back.faces.is_empty());
+        assert_eq!(back.surface_area_nm2, 0.0);
+    }
+
+    #[test]
+    fn legacy_vertex_cloud_gets_its_hull() {
+        // Cube of side 2 with no connectivity, id or metadata.
+        let legacy = r#"{
+            "vertices_nm": [[-1,-1,-1],[-1,-1,1],[-1,1,-1],[-1,1,1],
+                            [1,-1,-1],[1,-1,1],[1,1,-1],[1,1,1]],
+            "surface_area_nm2": 24.0,
+            "curvature_signature": []
+        }"#;
+        let record: NanoPolygon = serde_json::from_str(legacy).unwrap();
+        assert_eq!((record.id.clone(), record.bio.clone()), (None, None));
+
+        let poly = record.to_nanopolygon("legacy-1", bio(BioAffinityTarget::EndothelialCell));
+        assert_eq!(poly.id, "legacy-1");
+        assert_eq!(poly.bio.target, BioAffinityTarget::EndothelialCell);
+        assert_eq!(poly.faces.len(), 6);
+        assert_eq!(poly.edges.len(), 12);
+        assert!((poly.surface_area_nm2 - record.surface_area_nm2).abs() < 1e-9);
+        assert!(poly.is_closed());
+        assert!(poly.validate().is_ok());
+        assert!(record.shape_descriptor(1.0).is_none());
+    }
+
+    #[test]
+    fn stored_signature_matches_the_geometry() {
+        let cube = generators::platonic(
+            "c",
+            PlatonicSolid::Cube,
+            5.0,
+            bio(BioAffinityTarget::GlialCell),
+        )
+        .unwrap();
+        let record = NanoPolygon::from(&cube);
+        let stored = record.shape_descriptor(1.0).unwrap();
+        let rebuilt = record.to_nanopolygon("c", cube.bio.clone());
+        let fresh = ShapeDescriptor::from_nanopolygon(&rebuilt, shape_descriptor::DEFAULT_BINS);
+        assert_eq!(stored.distance(&fresh), 0.0);
+    }
+
+    #[test]
+    fn object_id_follows_the_polygon() {
+        let poly = generators::platonic(
+            "p",
+            PlatonicSolid::Octahedron,
+            5.0,
+            bio(BioAffinityTarget::GlialCell),
+        )
+        .unwrap();
+        let mut object = NanopolyObject::from_parts(
+            "member-1",
+            poly,
+            EnergeticProfile::constant(50.0),
+            BciInterface::none(),
+            GovernanceLayer::human("did:example:host"),
+        );
+        assert_eq!(object.polygon.id, "member-1");
+        object.set_id("member-2");
+        assert_eq!(
+            (object.id.as_str(), object.polygon.id.as_str()),
+            ("member-2", "member-2")
+        );
+
+        object.set_attachment(BioAttachmentMode::VascularEndothelial);
+        assert_eq!(
+            object.polygon.bio.target,
+            BioAffinityTarget::EndothelialCell
+        );
+        let (id, polygon, energy, _, _) = object.into_parts();
+        assert_eq!(id, polygon.id);
+        assert_eq!(energy, EnergeticProfile::constant(50.0));
+    }
+
+    #[test]
+    fn attachment_modes_map_one_to_one() {
+        for target in BioAffinityTarget::ALL {
+            let mode = BioAttachmentMode::from(target.clone());
+            assert_eq!(BioAffinityTarget::from(mode), target);
+        }
+    }
+}
@@ -1,4 +1,8 @@
-#[derive(Clone, Debug)]
+use serde::{Deserialize, Serialize};
+
+use crate::xr_lab_grid::nanopoly::canine_policy::CanineHostBudget;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum SpeciesId {
     HomoSapiens,
     CanisLupusFamiliaris,
@@ -7,7 +11,7 @@ pub enum SpeciesId {
     Other(String),
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum HostType {
     Human,
     NonHumanCompanion,
@@ -16,7 +20,7 @@ pub enum HostType {
     Other(String),
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct SpeciesProfile {
     pub id: SpeciesId,
     // DEFAULTBIOPHYSEVIDENCE-style safe bands
@@ -29,7 +33,7 @@ pub struct SpeciesProfile {
     pub lifeforce_min_operational: f32,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct HostBudgetProfile {
     pub species: SpeciesId,
     pub host_type: HostType,
@@ -38,4 +42,104 @@ pub struct HostBudgetProfile {
     pub dw_max: f32,
     pub dw_warn: f32,
     pub duty_cycle_max: f32,   // fraction of time in active interface modes
+    pub metabolic_budget_uw: f64, // swarm draw that maps to D = 1
+}
+
+impl HostBudgetProfile {
+    /// Conservative default envelope for a host. Dogs get the canine
+    /// policy's ceilings for a healthy adult of reference mass; other
+    /// non-human ceilings are tighter for smaller or less studied species; wild animals get a further 20% margin and
+    /// a lower duty cycle since nobody can ask them to stop.
+    ///
+    /// The metabolic budget is 1 W for a 70 kg human, scaled to the
+    /// species' typical body mass by Kleiber's law (mass^0.75).
+    pub fn default_for(species: SpeciesId, host_type: HostType) -> Self {
+        // (d_max, dw_max, duty_cycle_max, body_mass_kg)
+        let (d_max, dw_max, duty_cycle_max, body_mass_kg) = match species {
+            SpeciesId::HomoSapiens => (0.80, 0.40, 0.50, 70.0),
+            SpeciesId::CanisLupusFamiliaris => {
+                // No age or condition reduction: the unadjusted canine ceilings.
+                let canine = CanineHostBudget::derive(20.0, 0.0, 0.0);
+                (
+                    canine.max_demand_ceiling,
+                    canine.max_dw_ceiling,
+                    0.25,
+                    canine.body_mass_kg as f64,
+                )
+            }
+            SpeciesId::FelisCatus => (0.30, 0.08, 0.20, 4.0),
+            SpeciesId::CorvusCorax => (0.25, 0.08, 0.15, 1.2),
+            SpeciesId::Other(_) => (0.20, 0.05, 0.10, 1.0),
+        };
+        let (margin, duty_cycle_max) = match host_type {
+            HostType::Wildlife => (0.8, duty_cycle_max * 0.5),
+            _ => (1.0, duty_cycle_max),
+        };
+        let d_max = d_max * margin;
+        let dw_max = dw_max * margin;
+        Self {
+            species,
+            host_type,
+            d_max,
+            d_warn: 0.8 * d_max,
+            dw_max,
+            dw_warn: 0.8 * dw_max,
+            duty_cycle_max,
+            metabolic_budget_uw: 1_000_000.0 * (body_mass_kg / 70.0_f64).powf(0.75),
+        }
+    }
+
+    pub fn human() -> Self {
+        Self::default_for(SpeciesId::HomoSapiens, HostType::Human)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canine_budget_comes_from_the_canine_policy() {
+        let dog = HostBudgetProfile::default_for(
+            SpeciesId::CanisLupusFamiliaris,
+            HostType::NonHumanCompanion,
+        );
+        let healthy = CanineHostBudget::derive(20.0, 0.0, 0.0);
+        assert_eq!(dog.d_max, healthy.max_demand_ceiling);
+        assert_eq!(dog.dw_max, healthy.max_dw_ceiling);
+        assert_eq!((dog.d_max, dog.dw_max), (0.35, 0.10));
+        assert!((dog.d_warn - 0.28).abs() < 1e-6);
+        assert!((dog.dw_warn - 0.08).abs() < 1e-6);
+    }
+
+    #[test]
+    fn wildlife_gets_a_margin_and_half_the_duty_cycle() {
+        let pet =
+            HostBudgetProfile::default_for(SpeciesId::CorvusCorax, HostType::NonHumanCompanion);
+        let wild = HostBudgetProfile::default_for(SpeciesId::CorvusCorax, HostType::Wildlife);
+        assert!((wild.d_max - 0.8 * pet.d_max).abs() < 1e-6);
+        assert!((wild.dw_max - 0.8 * pet.dw_max).abs() < 1e-6);
+        assert!((wild.duty_cycle_max - 0.5 * pet.duty_cycle_max).abs() < 1e-6);
+        assert_eq!(wild.metabolic_budget_uw, pet.metabolic_budget_uw);
+    }
+
+    #[test]
+    fn metabolic_budget_follows_kleiber() {
+        assert_eq!(HostBudgetProfile::human().metabolic_budget_uw, 1_000_000.0);
+        let dog = HostBudgetProfile::default_for(
+            SpeciesId::CanisLupusFamiliaris,
+            HostType::WorkingAnimal,
+        );
+        let expected = 1_000_000.0 * (20.0_f64 / 70.0).powf(0.75);
+        assert!((dog.metabolic_budget_uw - expected).abs() < 1e-6);
+        // 4 kg cat against the 1 kg fallback: 4^0.75 times the budget.
+        let cat =
+            HostBudgetProfile::default_for(SpeciesId::FelisCatus, HostType::NonHumanCompanion);
+        let other = HostBudgetProfile::default_for(
+            SpeciesId::Other("ferret".to_string()),
+            HostType::NonHumanCompanion,
+        );
+        let ratio = cat.metabolic_budget_uw / other.metabolic_budget_uw;
+        assert!((ratio - 4.0_f64.powf(0.75)).abs() < 1e-9);
+    }
 }
@@ -0,0 +1,670 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, BinaryHeap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use super::geometry::{self, Vec3};
+use super::nanopolygon::{Face, Nanopolygon, VertexNm};
+
+// Boundary edges get a perpendicular constraint plane so open patches keep their rim.
+const BOUNDARY_WEIGHT: f64 = 100.0;
+
+// Collapses that shrink a triangle below this (in nm^2) are rejected as degenerate.
+const MIN_TRIANGLE_AREA_NM2: f64 = 1e-12;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LodSettings {
+    /// Largest quadric error a collapse may introduce, in nm.
+    pub max_error_nm: f64,
+    /// Triangle budget per level as a fraction of the full-resolution count.
+    pub triangle_fractions: Vec<f64>,
+}
+
+impl Default for LodSettings {
+    fn default() -> Self {
+        Self {
+            max_error_nm: 0.5,
+            triangle_fractions: vec![0.5, 0.25, 0.1],
+        }
+    }
+}
+
+/// How a simplified mesh differs from its full-resolution source.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LodReport {
+    pub level: usize,
+    pub source_id: String,
+    pub source_triangles: usize,
+    pub triangles: usize,
+    pub vertices: usize,
+    /// Largest quadric error accepted: an upper bound on the distance from any
+    /// kept vertex to the original face planes it absorbed, in nm.
+    pub error_bound_nm: f64,
+    /// Measured distance from the farthest source vertex to the simplified surface, in nm.
+    pub max_deviation_nm: f64,
+    /// False when the error bound stopped simplification above the triangle budget.
+    pub reached_target: bool,
+    pub source_area_nm2: f64,
+    pub area_nm2: f64,
+    pub source_mean_curvature: f64,
+    pub mean_curvature: f64,
+}
+
+impl LodReport {
+    pub fn area_change_nm2(&self) -> f64 {
+        self.area_nm2 - self.source_area_nm2
+    }
+
+    /// Relative area change; 0.0 for a source without area.
+    pub fn area_change_fraction(&self) -> f64 {
+        if self.source_area_nm2 > 0.0 {
+            self.area_change_nm2() / self.source_area_nm2
+        } else {
+            0.0
+        }
+    }
+
+    pub fn curvature_change_per_nm(&self) -> f64 {
+        self.mean_curvature - self.source_mean_curvature
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct LodLevel {
+    pub poly: Nanopolygon,
+    pub report: LodReport,
+}
+
+impl Nanopolygon {
+    /// Quadric-error edge collapse down to `target_triangles`, never accepting
+    /// a collapse whose error exceeds `max_error_nm`. The result is a
+    /// triangle mesh with the same biophysical metadata.
+    pub fn decimate(&self, target_triangles: usize, max_error_nm: f64) -> LodLevel {
+        let points = self.points();
+        let triangles = self.triangulate();
+
+        let mut mesh = CollapseMesh::new(&points, &triangles);
+        let error_bound = mesh.simplify(target_triangles, max_error_nm.max(0.0));
+        let reached_target = mesh.live_triangles() <= target_triangles;
+        let (out_points, out_triangles) = mesh.compact();
+
+        let vertices = out_points.iter().map(|&p| VertexNm::from_vec3(p)).collect();
+        let faces = out_triangles
+            .iter()
+            .map(|t| Face {
+                vertex_indices: t.to_vec(),
+            })
+            .collect();
+        let poly = Nanopolygon::from_faces(&self.id, vertices, faces, self.bio.clone());
+
+        let max_deviation_nm = points
+            .iter()
+            .map(|&p| geometry::distance_to_mesh(p, &out_points, &out_triangles))
+            .filter(|d| d.is_finite())
+            .fold(0.0_f64, f64::max);
+
+        let report = LodReport {
+            level: 0,
+            source_id: self.id.clone(),
+            source_triangles: triangles.len(),
+            triangles: out_triangles.len(),
+            vertices: out_points.len(),
+            error_bound_nm: error_bound,
+            max_deviation_nm,
+            reached_target,
+            source_area_nm2: self.surface_area_nm2,
+            area_nm2: poly.surface_area_nm2,
+            source_mean_curvature: self.mean_curvature,
+            mean_curvature: poly.mean_curvature,
+        };
+        LodLevel { poly, report }
+    }
+
+    /// Level 0 is the full-resolution mesh; each further level is simplified
+    /// from it directly, so every report compares against the original.
+    pub fn lod_levels(&self, settings: &LodSettings) -> Vec<LodLevel> {
+        let source_triangles = self.triangulate().len();
+        let mut levels = vec![LodLevel {
+            poly: self.clone(),
+            report: LodReport {
+                level: 0,
+                source_id: self.id.clone(),
+                source_triangles,
+                triangles: source_triangles,
+                vertices: self.vertices.len(),
+                error_bound_nm: 0.0,
+                max_deviation_nm: 0.0,
+                reached_target: true,
+                source_area_nm2: self.surface_area_nm2,
+                area_nm2: self.surface_area_nm2,
+                source_mean_curvature: self.mean_curvature,
+                mean_curvature: self.mean_curvature,
+            },
+        }];
+
+        for (i, fraction) in settings.triangle_fractions.iter().enumerate() {
+            let target = (source_triangles as f64 * fraction.clamp(0.0, 1.0)).round() as usize;
+            let mut level = self.decimate(target, settings.max_error_nm);
+            level.report.level = i + 1;
+            level.poly.id = format!("{}#lod{}", self.id, i + 1);
+            levels.push(level);
+        }
+        levels
+    }
+}
+
+// Symmetric 4x4 quadric stored as its upper triangle:
+// a2 ab ac ad / b2 bc bd / c2 cd / d2.
+#[derive(Clone, Copy, Debug, Default)]
+struct Quadric([f64; 10]);
+
+impl Quadric {
+    fn from_plane(n: Vec3, d: f64, weight: f64) -> Self {
+        let [a, b, c] = n;
+        Self([
+            a * a * weight,
+            a * b * weight,
+            a * c * weight,
+            a * d * weight,
+            b * b * weight,
+            b * c * weight,
+            b * d * weight,
+            c * c * weight,
+            c * d * weight,
+            d * d * weight,
+        ])
+    }
+
+    fn add(&self, other: &Quadric) -> Self {
+        let mut out = *self;
+        for (o, v) in out.0.iter_mut().zip(other.0.iter()) {
+            *o += v;
+        }
+        out
+    }
+
+    fn error(&self, p: Vec3) -> f64 {
+        let q = &self.0;
+        let [x, y, z] = p;
+        let e = q[0] * x * x
+            + 2.0 * q[1] * x * y
+            + 2.0 * q[2] * x * z
+            + 2.0 * q[3] * x
+            + q[4] * y * y
+            + 2.0 * q[5] * y * z
+            + 2.0 * q[6] * y
+            + q[7] * z * z
+            + 2.0 * q[8] * z
+            + q[9];
+        e.max(0.0)
+    }
+
+    // Point minimising the error, when the 3x3 part is well conditioned.
+    fn optimum(&self) -> Option<Vec3> {
+        let q = &self.0;
+        let m = [[q[0], q[1], q[2]], [q[1], q[4], q[5]], [q[2], q[5], q[7]]];
+        let rhs = [-q[3], -q[6], -q[8]];
+        let det = m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0]);
+        let trace = m[0][0] + m[1][1] + m[2][2];
+        if !det.is_finite() || det.abs() <= 1e-9 * trace.powi(3).max(f64::MIN_POSITIVE) {
+            return None;
+        }
+
+        // Cramer's rule.
+        let solve = |col: usize| {
+            let mut mm = m;
+            for (row, r) in mm.iter_mut().enumerate() {
+                r[col] = rhs[row];
+            }
+            (mm[0][0] * (mm[1][1] * mm[2][2] - mm[1][2] * mm[2][1])
+                - mm[0][1] * (mm[1][0] * mm[2][2] - mm[1][2] * mm[2][0])
+                + mm[0][2] * (mm[1][0] * mm[2][1] - mm[1][1] * mm[2][0]))
+                / det
+        };
+        Some([solve(0), solve(1), solve(2)])
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Candidate {
+    cost: f64,
+    keep: usize,
+    remove: usize,
+    stamps: (u32, u32),
+    target: Vec3,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    // Reversed so the max-heap pops the cheapest collapse; ties break on indices
+    // to keep the result deterministic.
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .cost
+            .total_cmp(&self.cost)
+            .then_with(|| other.keep.cmp(&self.keep))
+            .then_with(|| other.remove.cmp(&self.remove))
+    }
+}
+
+struct CollapseMesh {
+    points: Vec<Vec3>,
+    triangles: Vec<[usize; 3]>,
+    triangle_alive: Vec<bool>,
+    vertex_alive: Vec<bool>,
+    vertex_triangles: Vec<Vec<usize>>,
+    quadrics: Vec<Quadric>,
+    stamps: Vec<u32>,
+    live: usize,
+}
+
+impl CollapseMesh {
+    fn new(points: &[Vec3], triangles: &[[usize; 3]]) -> Self {
+        let n = points.len();
+        let mut quadrics = vec![Quadric::default(); n];
+        let mut vertex_triangles = vec![Vec::new(); n];
+        let mut edge_faces: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+
+        for (ti, t) in triangles.iter().enumerate() {
+            let normal = geometry::cross(
+                geometry::sub(points[t[1]], points[t[0]]),
+                geometry::sub(points[t[2]], points[t[0]]),
+            );
+            let len = geometry::norm(normal);
+            for corner in 0..3 {
+                vertex_triangles[t[corner]].push(ti);
+                edge_faces
+                    .entry(geometry::edge_key(t[corner], t[(corner + 1) % 3]))
+                    .or_default()
+                    .push(ti);
+            }
+            if len <= 0.0 {
+                continue;
+            }
+            let unit = geometry::scale(normal, 1.0 / len);
+            let plane = Quadric::from_plane(unit, -geometry::dot(unit, points[t[0]]), 1.0);
+            for &v in t {
+                quadrics[v] = quadrics[v].add(&plane);
+            }
+        }
+
+        for (&(a, b), faces) in &edge_faces {
+            if faces.len() != 1 {
+                continue;
+            }
+            let t = triangles[faces[0]];
+            let face_normal = geometry::cross(
+                geometry::sub(points[t[1]], points[t[0]]),
+                geometry::sub(points[t[2]], points[t[0]]),
+            );
+            let along = geometry::sub(points[b], points[a]);
+            let side = geometry::cross(along, face_normal);
+            let len = geometry::norm(side);
+            if len <= 0.0 {
+                continue;
+            }
+            let unit = geometry::scale(side, 1.0 / len);
+            let plane = Quadric::from_plane(unit, -geometry::dot(unit, points[a]), BOUNDARY_WEIGHT);
+            quadrics[a] = quadrics[a].add(&plane);
+            quadrics[b] = quadrics[b].add(&plane);
+        }
+
+        let mut vertex_alive = vec![false; n];
+        for t in triangles {
+            for &v in t {
+                vertex_alive[v] = true;
+            }
+        }
+
+        Self {
+            points: points.to_vec(),
+            triangles: triangles.to_vec(),
+            triangle_alive: vec![true; triangles.len()],
+            vertex_alive,
+            vertex_triangles,
+            quadrics,
+            stamps: vec![0; n],
+            live: triangles.len(),
+        }
+    }
+
+    fn live_triangles(&self) -> usize {
+        self.live
+    }
+
+    /// Collapse edges cheapest first; returns the largest accepted error in nm.
+    fn simplify(&mut self, target_triangles: usize, max_error_nm: f64) -> f64 {
+        let max_cost = max_error_nm * max_error_nm;
+        let mut heap = BinaryHeap::new();
+        let mut seen = BTreeSet::new();
+        for t in &self.triangles {
+            for corner in 0..3 {
+                let key = geometry::edge_key(t[corner], t[(corner + 1) % 3]);
+                if seen.insert(key) {
+                    heap.push(self.candidate(key.0, key.1));
+                }
+            }
+        }
+
+        let mut worst_cost = 0.0_f64;
+        while self.live > target_triangles {
+            let Some(c) = heap.pop() else {
+                break;
+            };
+            if c.cost > max_cost {
+                break;
+            }
+            if !self.vertex_alive[c.keep]
+                || !self.vertex_alive[c.remove]
+                || (self.stamps[c.keep], self.stamps[c.remove]) != c.stamps
+                || !self.can_collapse(c.keep, c.remove, c.target)
+            {
+                continue;
+            }
+
+            self.collapse(c.keep, c.remove, c.target);
+            worst_cost = worst_cost.max(c.cost);
+            for n in self.neighbours(c.keep) {
+                heap.push(self.candidate(c.keep, n));
+            }
+        }
+        worst_cost.sqrt()
+    }
+
+    fn candidate(&self, a: usize, b: usize) -> Candidate {
+        let q = self.quadrics[a].add(&self.quadrics[b]);
+        let midpoint = geometry::scale(geometry::add(self.points[a], self.points[b]), 0.5);
+        let mut options = vec![self.points[a], self.points[b], midpoint];
+        if let Some(p) = q.optimum() {
+            // Keep the solve local; far-off optima come from nearly parallel planes.
+            let reach = geometry::norm(geometry::sub(self.points[a], self.points[b]));
+            if geometry::norm(geometry::sub(p, midpoint)) <= reach {
+                options.insert(0, p);
+            }
+        }
+        let (target, cost) = options
+            .into_iter()
+            .map(|p| (p, q.error(p)))
+            .min_by(|x, y| x.1.total_cmp(&y.1))
+            .unwrap_or((midpoint, 0.0));
+        Candidate {
+            cost,
+            keep: a,
+            remove: b,
+            stamps: (self.stamps[a], self.stamps[b]),
+            target,
+        }
+    }
+
+    fn live_triangles_of(&self, v: usize) -> impl Iterator<Item = usize> + '_ {
+        self.vertex_triangles[v]
+            .iter()
+            .copied()
+            .filter(move |&t| self.triangle_alive[t])
+    }
+
+    fn neighbours(&self, v: usize) -> BTreeSet<usize> {
+        self.live_triangles_of(v)
+            .flat_map(|t| self.triangles[t])
+            .filter(|&u| u != v)
+            .collect()
+    }
+
+    // Link condition keeps the surface manifold; the normal check stops fold-overs.
+    fn can_collapse(&self, keep: usize, remove: usize, target: Vec3) -> bool {
+        let keep_ring = self.neighbours(keep);
+        let remove_ring = self.neighbours(remove);
+        let shared: BTreeSet<usize> = keep_ring.intersection(&remove_ring).copied().collect();
+        let opposite: BTreeSet<usize> = self
+            .live_triangles_of(keep)
+            .filter(|&t| self.triangles[t].contains(&remove))
+            .flat_map(|t| self.triangles[t])
+            .filter(|&u| u != keep && u != remove)
+            .collect();
+        if shared != opposite {
+            return false;
+        }
+        // A lone triangle, or an edge of a tetrahedron, has nothing left to
+        // span once collapsed.
+        let ring = keep_ring.union(&remove_ring).count().saturating_sub(2);
+        if ring < 2 || (opposite.len() == 2 && ring == 2) {
+            return false;
+        }
+
+        for v in [keep, remove] {
+            for ti in self.live_triangles_of(v) {
+                let t = self.triangles[ti];
+                if t.contains(&keep) && t.contains(&remove) {
+                    continue;
+                }
+                let before = self.normal_of(t, None);
+                let after = self.normal_of(t, Some((v, target)));
+                if 0.5 * geometry::norm(after) <= MIN_TRIANGLE_AREA_NM2
+                    || geometry::dot(before, after) <= 0.0
+                {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    fn normal_of(&self, t: [usize; 3], moved: Option<(usize, Vec3)>) -> Vec3 {
+        let p = |i: usize| match moved {
+            Some((v, target)) if v == i => target,
+            _ => self.points[i],
+        };
+        geometry::cross(
+            geometry::sub(p(t[1]), p(t[0])),
+            geometry::sub(p(t[2]), p(t[0])),
+        )
+    }
+
+    fn collapse(&mut self, keep: usize, remove: usize, target: Vec3) {
+        let incident: Vec<usize> = self.live_triangles_of(remove).collect();
+        for ti in incident {
+            if self.triangles[ti].contains(&keep) {
+                self.triangle_alive[ti] = false;
+                self.live -= 1;
+            } else {
+                for v in self.triangles[ti].iter_mut() {
+                    if *v == remove {
+                        *v = keep;
+                    }
+                }
+                self.vertex_triangles[keep].push(ti);
+            }
+        }
+
+        self.points[keep] = target;
+        self.quadrics[keep] = self.quadrics[keep].add(&self.quadrics[remove]);
+        self.vertex_alive[remove] = false;
+        self.vertex_triangles[remove].clear();
+        self.stamps[keep] += 1;
+        self.stamps[remove] += 1;
+    }
+
+    /// Surviving vertices and triangles, reindexed in original order.
+    fn compact(&self) -> (Vec<Vec3>, Vec<[usize; 3]>) {
+        let mut remap = vec![usize::MAX; self.points.len()];
+        let mut points = Vec::new();
+        for (i, &alive) in self.vertex_alive.iter().enumerate() {
+            if alive && self.live_triangles_of(i).next().is_some() {
+                remap[i] = points.len();
+                points.push(self.points[i]);
+            }
+        }
+        let triangles = self
+            .triangles
+            .iter()
+            .zip(&self.triangle_alive)
+            .filter(|(_, &alive)| alive)
+            .map(|(t, _)| [remap[t[0]], remap[t[1]], remap[t[2]]])
+            .collect();
+        (points, triangles)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xr_lab_grid::nanopoly::generators::{self, PlatonicSolid};
+    use crate::xr_lab_grid::nanopoly::nanopolygon::{BioAffinityTarget, BiophysicalMetadata};
+
+    fn bio() -> BiophysicalMetadata {
+        BiophysicalMetadata {
+            target: BioAffinityTarget::ExtracellularMatrix,
+            zeta_potential_mv: -10.0,
+            hydrophobicity_index: 0.3,
+            elastic_modulus_kpa: 1.0,
+            ligands: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn flat_patch_collapses_without_error_or_area_loss() {
+        let grid = generators::flat_tile("grid", 8.0, 8.0, 8, 8, bio()).unwrap();
+        let level = grid.decimate(16, 0.01);
+        let r = &level.report;
+        assert_eq!(r.source_triangles, 128);
+        assert!(r.reached_target);
+        assert!(r.triangles <= 16);
+        assert!(r.error_bound_nm < 1e-9);
+        assert!(r.max_deviation_nm < 1e-9);
+        // Boundary planes keep the rim, so the patch keeps its area.
+        assert!(r.area_change_fraction().abs() < 1e-9);
+    }
+
+    #[test]
+    fn sphere_stays_within_error_bound() {
+        let sphere = generators::icosphere("s", 10.0, 3, bio()).unwrap();
+        let level = sphere.decimate(320, 0.5);
+        let r = &level.report;
+        assert_eq!(r.source_triangles, 1280);
+        assert!(r.triangles < r.source_triangles);
+        assert!(r.error_bound_nm <= 0.5);
+        assert!(r.max_deviation_nm <= 0.5 + 1e-9);
+        assert!(level.poly.is_closed());
+        assert!(r.area_change_fraction() < 0.0);
+    }
+
+    #[test]
+    fn zero_error_budget_keeps_curved_mesh() {
+        let sphere = generators::icosphere("s", 10.0, 1, bio()).unwrap();
+        let r = sphere.decimate(10, 0.0).report;
+        assert!(!r.reached_target);
+        assert_eq!(r.triangles, r.source_triangles);
+    }
+
+    #[test]
+    fn lod_levels_number_and_shrink() {
+        let sphere = generators::icosphere("s", 10.0, 2, bio()).unwrap();
+        let levels = sphere.lod_levels(&LodSettings {
+            max_error_nm: 5.0,
+            triangle_fractions: vec![0.5, 0.25],
+        });
+        assert_eq!(levels.len(), 3);
+        assert_eq!(levels[0].report.triangles, 320);
+        assert_eq!(levels[0].poly.id, "s");
+        for (i, level) in levels.iter().enumerate().skip(1) {
+            assert_eq!(level.report.level, i);
+            assert_eq!(level.poly.id, format!("s#lod{}", i));
+            assert!(level.report.triangles < levels[i - 1].report.triangles);
+        }
+    }
+
+    #[test]
+    fn mesh_within_budget_is_left_alone() {
+        let cube = generators::platonic("c", PlatonicSolid::Cube, 5.0, bio()).unwrap();
+        let level = cube.decimate(12, 0.0);
+        let r = &level.report;
+        assert_eq!((r.source_triangles, r.triangles, r.vertices), (12, 12, 8));
+        assert!(r.reached_target);
+        assert_eq!(r.error_bound_nm, 0.0);
+        assert_eq!(r.max_deviation_nm, 0.0);
+        assert!(r.area_change_nm2().abs() < 1e-9);
+        assert!(r.curvature_change_per_nm().abs() < 1e-12);
+    }
+
+    #[test]
+    fn looser_budgets_simplify_further() {
+        let sphere = generators::icosphere("s", 10.0, 3, bio()).unwrap();
+        let mut previous = usize::MAX;
+        for budget in [0.02, 0.1, 0.5, 2.0] {
+            let r = sphere.decimate(1, budget).report;
+            assert!(r.error_bound_nm <= budget, "{}", r.error_bound_nm);
+            assert!(r.triangles <= previous, "{} at {}", r.triangles, budget);
+            assert!(!r.reached_target);
+            previous = r.triangles;
+        }
+        assert!(previous < 1280 / 4);
+    }
+
+    #[test]
+    fn coarse_levels_stay_valid_closed_meshes() {
+        let sphere = generators::icosphere("s", 10.0, 2, bio()).unwrap();
+        let level = sphere.decimate(40, f64::INFINITY);
+        assert!(level.report.reached_target);
+        assert!(level.poly.validate().is_ok());
+        assert!(level.poly.is_closed());
+        assert_eq!(level.poly.bio, sphere.bio);
+        assert_eq!(level.report.vertices, level.poly.vertices.len());
+        // Collapsing a convex surface cuts corners: less area, same curvature sign.
+        assert!(level.report.area_nm2 < level.report.source_area_nm2);
+        assert!(level.poly.mean_curvature > 0.0);
+        let r = &level.report;
+        assert!((r.area_change_nm2() - (r.area_nm2 - r.source_area_nm2)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn smallest_surfaces_are_not_collapsed_away() {
+        let tetra = generators::platonic("t", PlatonicSolid::Tetrahedron, 5.0, bio()).unwrap();
+        let r = tetra.decimate(0, f64::INFINITY).report;
+        assert_eq!((r.triangles, r.vertices), (4, 4));
+        assert!(!r.reached_target);
+
+        let triangle = generators::polygon_tile("tri", 3, 5.0, bio()).unwrap();
+        assert_eq!(triangle.decimate(0, f64::INFINITY).report.triangles, 1);
+
+        // A quad may still lose one of its two triangles along its rim.
+        let quad = generators::flat_tile("q", 2.0, 2.0, 1, 1, bio()).unwrap();
+        let r = quad.decimate(1, f64::INFINITY).report;
+        assert_eq!(r.source_triangles, 2);
+        assert_eq!(r.triangles, 1);
+    }
+
+    #[test]
+    fn lod_budgets_round_and_clamp_their_fractions() {
+        let sphere = generators::icosphere("s", 10.0, 1, bio()).unwrap();
+        let levels = sphere.lod_levels(&LodSettings {
+            max_error_nm: f64::INFINITY,
+            triangle_fractions: vec![1.5, 0.33, -1.0],
+        });
+        // 80 triangles: 1.5 clamps to all of them, 0.33 rounds to 26.
+        assert_eq!(levels[1].report.triangles, 80);
+        assert!(levels[2].report.triangles <= 26);
+        assert!(levels[2].report.reached_target);
+        // A zero budget cannot be met; the surface stays closed instead.
+        let floor = &levels[3];
+        assert!(!floor.report.reached_target);
+        assert!(floor.report.triangles >= 4);
+        assert!(floor.poly.is_closed());
+        assert!(floor.poly.validate().is_ok());
+        assert!(levels.iter().all(|l| l.report.source_id == "s"));
+    }
+}
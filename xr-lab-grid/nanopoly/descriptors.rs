@@ -0,0 +1,248 @@
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+use safety_core::types::{BioLoadFlag, SafetyState};
+
+use super::geometry::{self, Vec3};
+use super::nanopolygon::{GeometricDescriptors, Nanopolygon};
+
+/// |H| above this (1/nm) is high curvature: features tighter than a 5 nm radius.
+pub const HIGH_CURVATURE_PER_NM: f64 = 0.2;
+
+// Thinnest extent used for aspect ratio, so flat sheets stay finite
+// (graphene monolayer thickness).
+const MIN_THICKNESS_NM: f64 = 0.34;
+
+// Fibre paradigm: aspect ratios of 3:1 and above count fully.
+const FIBRE_ASPECT_RATIO: f64 = 3.0;
+
+// Bends gentler than 60 degrees carry no sharpness risk.
+const SHARPNESS_ONSET_RAD: f64 = PI / 3.0;
+
+// Surface-to-volume of a 5 nm radius sphere.
+const HIGH_SURFACE_TO_VOLUME_PER_NM: f64 = 0.6;
+
+// Geometric risk at which safety snapshots are escalated to Caution.
+pub const GEOMETRIC_CAUTION_RISK: f32 = 0.5;
+
+impl GeometricDescriptors {
+    pub fn from_nanopolygon(poly: &Nanopolygon) -> Self {
+        let points = poly.points();
+        let triangles = poly.triangulate();
+        let area = geometry::surface_area_nm2(&points, &triangles);
+        let volume = poly.volume_nm3().filter(|v| *v > 0.0);
+
+        let sphericity = volume
+            .filter(|_| area > 0.0)
+            .map(|v| PI.cbrt() * (6.0 * v).powf(2.0 / 3.0) / area);
+        let surface_to_volume_per_nm = volume.map(|v| area / v);
+
+        let aspect_ratio = poly
+            .obb()
+            .map(|b| {
+                let e = b.half_extents_nm.map(|h| 2.0 * h);
+                let longest = e[0].max(e[1]).max(e[2]);
+                let shortest = e[0].min(e[1]).min(e[2]).max(MIN_THICKNESS_NM);
+                (longest / shortest).max(1.0)
+            })
+            .unwrap_or(1.0);
+
+        Self {
+            sphericity,
+            aspect_ratio,
+            surface_to_volume_per_nm,
+            max_edge_sharpness_rad: max_edge_sharpness(&points, &triangles),
+            high_curvature_fraction: high_curvature_fraction(&points, &triangles),
+        }
+    }
+
+    /// Combined geometric toxicity risk in [0, 1]. Each factor is scaled to
+    /// [0, 1] against its known-hazard level, then weighted.
+    pub fn toxicity_risk(&self) -> f32 {
+        let aspect = ((self.aspect_ratio - 1.0) / (FIBRE_ASPECT_RATIO - 1.0)).clamp(0.0, 1.0);
+        let sharpness = ((self.max_edge_sharpness_rad - SHARPNESS_ONSET_RAD)
+            / (PI - SHARPNESS_ONSET_RAD))
+            .clamp(0.0, 1.0);
+        let curvature = self.high_curvature_fraction.clamp(0.0, 1.0);
+        let surface = self.surface_to_volume_per_nm.map_or(0.0, |sv| {
+            (sv / HIGH_SURFACE_TO_VOLUME_PER_NM).clamp(0.0, 1.0)
+        });
+        let irregular = self.sphericity.map_or(0.0, |s| (1.0 - s).clamp(0.0, 1.0));
+
+        let risk =
+            0.30 * aspect + 0.25 * sharpness + 0.20 * curvature + 0.15 * surface + 0.10 * irregular;
+        risk.clamp(0.0, 1.0) as f32
+    }
+
+    /// Fold geometric risk into a member's safety snapshot: DW rises and
+    /// lifeforce falls with risk, and a Normal flag becomes Caution at
+    /// `GEOMETRIC_CAUTION_RISK`.
+    pub fn adjust_safety(&self, state: &SafetyState) -> SafetyState {
+        let risk = self.toxicity_risk();
+        let bio_flag = match state.bio_flag {
+            BioLoadFlag::Normal if risk >= GEOMETRIC_CAUTION_RISK => BioLoadFlag::Caution,
+            ref flag => flag.clone(),
+        };
+        SafetyState::new(
+            state.k,
+            state.d,
+            state.dw + 0.2 * risk,
+            state.lifeforce.0 - 0.1 * risk,
+            state.roh.0,
+            bio_flag,
+            state.swarm_mode.clone(),
+        )
+    }
+}
+
+impl Nanopolygon {
+    pub fn geometric_descriptors(&self) -> GeometricDescriptors {
+        GeometricDescriptors::from_nanopolygon(self)
+    }
+}
+
+/// Largest bend across any edge shared by two triangles, from the angle
+/// between the two half-planes (independent of winding).
+fn max_edge_sharpness(points: &[Vec3], triangles: &[[usize; 3]]) -> f64 {
+    let mut opposite: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+    for t in triangles {
+        for corner in 0..3 {
+            let key = geometry::edge_key(t[corner], t[(corner + 1) % 3]);
+            opposite.entry(key).or_default().push(t[(corner + 2) % 3]);
+        }
+    }
+
+    let mut sharpest = 0.0_f64;
+    for (&(i, j), others) in &opposite {
+        if others.len() != 2 {
+            continue;
+        }
+        let axis = geometry::sub(points[j], points[i]);
+        let len2 = geometry::dot(axis, axis);
+        if len2 <= 0.0 {
+            continue;
+        }
+        // Components of each opposite vertex perpendicular to the edge.
+        let perpendicular = |k: usize| {
+            let v = geometry::sub(points[k], points[i]);
+            geometry::sub(v, geometry::scale(axis, geometry::dot(v, axis) / len2))
+        };
+        let (u, w) = (perpendicular(others[0]), perpendicular(others[1]));
+        let (nu, nw) = (geometry::norm(u), geometry::norm(w));
+        if nu <= 0.0 || nw <= 0.0 {
+            continue;
+        }
+        let interior = (geometry::dot(u, w) / (nu * nw)).clamp(-1.0, 1.0).acos();
+        sharpest = sharpest.max(PI - interior);
+    }
+    sharpest
+}
+
+fn high_curvature_fraction(points: &[Vec3], triangles: &[[usize; 3]]) -> f64 {
+    let mut vertex_area = vec![0.0_f64; points.len()];
+    for t in triangles {
+        let a = geometry::triangle_area(points[t[0]], points[t[1]], points[t[2]]);
+        for &i in t {
+            vertex_area[i] += a / 3.0;
+        }
+    }
+    let total: f64 = vertex_area.iter().sum();
+    if total <= 0.0 {
+        return 0.0;
+    }
+
+    let high = geometry::vertex_mean_curvatures(points, triangles)
+        .iter()
+        .zip(&vertex_area)
+        .filter(|(h, _)| h.is_some_and(|h| h > HIGH_CURVATURE_PER_NM))
+        .fold(0.0, |acc, (_, a)| acc + a);
+    high / total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xr_lab_grid::nanopoly::generators::{self, PlatonicSolid};
+    use crate::xr_lab_grid::nanopoly::nanopolygon::{BioAffinityTarget, BiophysicalMetadata};
+    use safety_core::types::SwarmMode;
+
+    fn bio() -> BiophysicalMetadata {
+        BiophysicalMetadata {
+            target: BioAffinityTarget::GlialCell,
+            zeta_potential_mv: -20.0,
+            hydrophobicity_index: 0.3,
+            elastic_modulus_kpa: 1.0,
+            ligands: Vec::new(),
+        }
+    }
+
+    fn state(flag: BioLoadFlag) -> SafetyState {
+        SafetyState::new(0.5, 0.2, 0.1, 0.9, 0.1, flag, SwarmMode::Normal)
+    }
+
+    #[test]
+    fn cube_descriptors_match_closed_form() {
+        // Side 100 nm: sphericity (pi/6)^(1/3), S/V = 6/a, right-angle edges.
+        let side = 100.0;
+        let cube = generators::platonic("c", PlatonicSolid::Cube, side * 3f64.sqrt() / 2.0, bio())
+            .unwrap();
+        let d = cube.geometric_descriptors();
+        assert!((d.sphericity.unwrap() - (PI / 6.0).cbrt()).abs() < 1e-9);
+        assert!((d.surface_to_volume_per_nm.unwrap() - 6.0 / side).abs() < 1e-9);
+        assert!((d.aspect_ratio - 1.0).abs() < 1e-9);
+        assert!((d.max_edge_sharpness_rad - PI / 2.0).abs() < 1e-9);
+        assert_eq!(d.high_curvature_fraction, 0.0);
+
+        // 0.25 * 0.25 (sharpness) + 0.15 * 0.1 (S/V) + 0.10 * (1 - sphericity).
+        let expected = 0.0625 + 0.015 + 0.1 * (1.0 - (PI / 6.0).cbrt());
+        assert!((d.toxicity_risk() as f64 - expected).abs() < 1e-6);
+    }
+
+    #[test]
+    fn flat_tile_uses_minimum_thickness() {
+        let tile = generators::flat_tile("t", 34.0, 17.0, 2, 1, bio()).unwrap();
+        let d = tile.geometric_descriptors();
+        assert_eq!(d.sphericity, None);
+        assert_eq!(d.surface_to_volume_per_nm, None);
+        assert!((d.aspect_ratio - 100.0).abs() < 1e-9);
+        // A flat sheet has no bend and no curvature.
+        assert!(d.max_edge_sharpness_rad.abs() < 1e-9);
+        assert_eq!(d.high_curvature_fraction, 0.0);
+    }
+
+    #[test]
+    fn large_sphere_is_low_risk() {
+        let sphere = generators::icosphere("s", 100.0, 3, bio()).unwrap();
+        let d = sphere.geometric_descriptors();
+        assert!(d.sphericity.unwrap() > 0.99);
+        assert!(d.aspect_ratio < 1.01);
+        assert!(d.max_edge_sharpness_rad < SHARPNESS_ONSET_RAD);
+        assert_eq!(d.high_curvature_fraction, 0.0);
+        assert!(d.toxicity_risk() < 0.05);
+
+        let adjusted = d.adjust_safety(&state(BioLoadFlag::Normal));
+        assert_eq!(adjusted.bio_flag, BioLoadFlag::Normal);
+    }
+
+    #[test]
+    fn thin_needle_escalates_to_caution() {
+        // 1 nm radius: every vertex is above 0.2 /nm mean curvature.
+        let needle = generators::capped_rod("n", 1.0, 40.0, 12, bio()).unwrap();
+        let d = needle.geometric_descriptors();
+        assert!(d.aspect_ratio > FIBRE_ASPECT_RATIO);
+        assert!(d.high_curvature_fraction > 0.99);
+        let risk = d.toxicity_risk();
+        assert!(risk >= GEOMETRIC_CAUTION_RISK);
+
+        let before = state(BioLoadFlag::Normal);
+        let after = d.adjust_safety(&before);
+        assert_eq!(after.bio_flag, BioLoadFlag::Caution);
+        assert!((after.dw - (before.dw + 0.2 * risk)).abs() < 1e-6);
+        assert!((after.lifeforce.0 - (before.lifeforce.0 - 0.1 * risk)).abs() < 1e-6);
+        assert_eq!(after.d, before.d);
+
+        // Violation is never downgraded.
+        let critical = d.adjust_safety(&state(BioLoadFlag::Violation));
+        assert_eq!(critical.bio_flag, BioLoadFlag::Violation);
+    }
+}
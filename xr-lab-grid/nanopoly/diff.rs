@@ -0,0 +1,390 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::geometry::{self, Vec3};
+use super::nanopolygon::Nanopolygon;
+use super::shape_descriptor;
+use super::spatial::PlacedMesh;
+use super::topology;
+use super::transform::RigidTransform;
+
+// Surface samples per side for the Hausdorff estimate, on top of the vertices.
+const HAUSDORFF_SAMPLES: usize = 512;
+const HAUSDORFF_SEED: u64 = 0xd1ff_5eed_0bad_c0de;
+
+/// Per-vertex movement between two revisions with the same vertex count,
+/// matched by index.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DisplacementStats {
+    pub moved_vertices: usize,
+    pub mean_nm: f64,
+    pub rms_nm: f64,
+    pub max_nm: f64,
+    /// Index of the vertex that moved furthest.
+    pub max_vertex: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum TopologyChange {
+    VertexCount {
+        from: usize,
+        to: usize,
+    },
+    EdgeCount {
+        from: usize,
+        to: usize,
+    },
+    FaceCount {
+        from: usize,
+        to: usize,
+    },
+    /// Same counts, but the edges join different vertices.
+    Connectivity,
+    Closed {
+        from: bool,
+        to: bool,
+    },
+    /// V - E + F, for meshes with explicit faces.
+    EulerCharacteristic {
+        from: i64,
+        to: i64,
+    },
+}
+
+impl fmt::Display for TopologyChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TopologyChange::VertexCount { from, to } => write!(f, "vertices {} -> {}", from, to),
+            TopologyChange::EdgeCount { from, to } => write!(f, "edges {} -> {}", from, to),
+            TopologyChange::FaceCount { from, to } => write!(f, "faces {} -> {}", from, to),
+            TopologyChange::Connectivity => write!(f, "edge connectivity rewired"),
+            TopologyChange::Closed { from, to } => write!(f, "closed {} -> {}", from, to),
+            TopologyChange::EulerCharacteristic { from, to } => {
+                write!(f, "Euler characteristic {} -> {}", from, to)
+            }
+        }
+    }
+}
+
+/// How a child revision differs from its parent. Deltas are child minus parent.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GeometryDiff {
+    pub parent_id: String,
+    pub child_id: String,
+    /// Only when both revisions have the same number of vertices.
+    pub displacement: Option<DisplacementStats>,
+    /// Farthest parent surface point from the child surface, in nm.
+    pub parent_to_child_nm: f64,
+    /// Farthest child surface point from the parent surface, in nm.
+    pub child_to_parent_nm: f64,
+    pub area_delta_nm2: f64,
+    pub curvature_delta_per_nm: f64,
+    pub topology_changes: Vec<TopologyChange>,
+    /// Biophysical metadata fields whose value changed.
+    pub bio_changes: Vec<String>,
+}
+
+impl GeometryDiff {
+    /// Both revisions are compared in their own frames; no alignment is attempted.
+    /// The Hausdorff distance is estimated from vertices plus deterministic
+    /// surface samples, so it can fall slightly short of the exact value.
+    pub fn between(parent: &Nanopolygon, child: &Nanopolygon) -> Self {
+        let (parent_to_child_nm, child_to_parent_nm) = directed_hausdorff(parent, child);
+
+        Self {
+            parent_id: parent.id.clone(),
+            child_id: child.id.clone(),
+            displacement: displacement(&parent.points(), &child.points()),
+            parent_to_child_nm,
+            child_to_parent_nm,
+            area_delta_nm2: child.surface_area_nm2 - parent.surface_area_nm2,
+            curvature_delta_per_nm: child.mean_curvature - parent.mean_curvature,
+            topology_changes: topology_changes(parent, child),
+            bio_changes: bio_changes(parent, child),
+        }
+    }
+
+    /// Symmetric Hausdorff distance in nm.
+    pub fn hausdorff_nm(&self) -> f64 {
+        self.parent_to_child_nm.max(self.child_to_parent_nm)
+    }
+
+    /// Relative area change; 0.0 when the parent has no area.
+    pub fn area_change_fraction(&self, parent: &Nanopolygon) -> f64 {
+        if parent.surface_area_nm2 > 0.0 {
+            self.area_delta_nm2 / parent.surface_area_nm2
+        } else {
+            0.0
+        }
+    }
+
+    pub fn is_geometry_unchanged(&self, tolerance_nm: f64) -> bool {
+        self.topology_changes.is_empty() && self.hausdorff_nm() <= tolerance_nm
+    }
+
+    /// One line per difference, for review notes.
+    pub fn summary(&self) -> Vec<String> {
+        let mut lines = vec![format!("{} -> {}", self.parent_id, self.child_id)];
+        if let Some(d) = &self.displacement {
+            lines.push(format!(
+                "{} vertices moved: mean {:.3} nm, rms {:.3} nm, max {:.3} nm at vertex {}",
+                d.moved_vertices, d.mean_nm, d.rms_nm, d.max_nm, d.max_vertex
+            ));
+        }
+        lines.push(format!("Hausdorff {:.3} nm", self.hausdorff_nm()));
+        lines.push(format!(
+            "area {:+.3} nm^2, mean curvature {:+.5} 1/nm",
+            self.area_delta_nm2, self.curvature_delta_per_nm
+        ));
+        for change in &self.topology_changes {
+            lines.push(format!("topology: {}", change));
+        }
+        for field in &self.bio_changes {
+            lines.push(format!("bio: {} changed", field));
+        }
+        lines
+    }
+}
+
+impl Nanopolygon {
+    /// Diff from `self` (the parent) to `child`.
+    pub fn diff(&self, child: &Nanopolygon) -> GeometryDiff {
+        GeometryDiff::between(self, child)
+    }
+}
+
+fn displacement(parent: &[Vec3], child: &[Vec3]) -> Option<DisplacementStats> {
+    if parent.len() != child.len() || parent.is_empty() {
+        return None;
+    }
+
+    let mut stats = DisplacementStats {
+        moved_vertices: 0,
+        mean_nm: 0.0,
+        rms_nm: 0.0,
+        max_nm: 0.0,
+        max_vertex: 0,
+    };
+    let mut sum_sq = 0.0_f64;
+    for (i, (&a, &b)) in parent.iter().zip(child).enumerate() {
+        let d = geometry::norm(geometry::sub(b, a));
+        if d > 0.0 {
+            stats.moved_vertices += 1;
+        }
+        if d > stats.max_nm {
+            stats.max_nm = d;
+            stats.max_vertex = i;
+        }
+        stats.mean_nm += d;
+        sum_sq += d * d;
+    }
+    let n = parent.len() as f64;
+    stats.mean_nm /= n;
+    stats.rms_nm = (sum_sq / n).sqrt();
+    Some(stats)
+}
+
+fn directed_hausdorff(parent: &Nanopolygon, child: &Nanopolygon) -> (f64, f64) {
+    let identity = RigidTransform::identity();
+    match (
+        PlacedMesh::from_nanopolygon(parent, &identity),
+        PlacedMesh::from_nanopolygon(child, &identity),
+    ) {
+        (Some(p), Some(c)) => (farthest(&p, &c), farthest(&c, &p)),
+        (None, None) => (0.0, 0.0),
+        // Everything appeared or vanished.
+        _ => (f64::INFINITY, f64::INFINITY),
+    }
+}
+
+// Farthest sampled point of `from` to the surface of `to`.
+fn farthest(from: &PlacedMesh, to: &PlacedMesh) -> f64 {
+    let solid: Vec<[usize; 3]> = from
+        .triangles
+        .iter()
+        .copied()
+        .filter(|t| t[0] != t[1] && t[1] != t[2])
+        .collect();
+    let mut samples = from.points.clone();
+    if !solid.is_empty() {
+        samples.extend(shape_descriptor::sample_surface(
+            &from.points,
+            &solid,
+            HAUSDORFF_SAMPLES,
+            HAUSDORFF_SEED,
+        ));
+    }
+    samples
+        .into_iter()
+        .map(|p| to.distance_to_point(p))
+        .filter(|d| d.is_finite())
+        .fold(0.0_f64, f64::max)
+}
+
+fn topology_changes(parent: &Nanopolygon, child: &Nanopolygon) -> Vec<TopologyChange> {
+    let mut changes = Vec::new();
+    let (pv, cv) = (parent.vertices.len(), child.vertices.len());
+    let (pe, ce) = (parent.edges.len(), child.edges.len());
+    let (pf, cf) = (parent.faces.len(), child.faces.len());
+
+    if pv != cv {
+        changes.push(TopologyChange::VertexCount { from: pv, to: cv });
+    }
+    if pe != ce {
+        changes.push(TopologyChange::EdgeCount { from: pe, to: ce });
+    }
+    if pf != cf {
+        changes.push(TopologyChange::FaceCount { from: pf, to: cf });
+    }
+    if pv == cv && pe == ce && pf == cf && edge_set(parent) != edge_set(child) {
+        changes.push(TopologyChange::Connectivity);
+    }
+
+    let (p_closed, c_closed) = (parent.is_closed(), child.is_closed());
+    if p_closed != c_closed {
+        changes.push(TopologyChange::Closed {
+            from: p_closed,
+            to: c_closed,
+        });
+    }
+
+    if pf > 0 && cf > 0 {
+        let from = topology::euler_characteristic(&parent.faces);
+        let to = topology::euler_characteristic(&child.faces);
+        if from != to {
+            changes.push(TopologyChange::EulerCharacteristic { from, to });
+        }
+    }
+    changes
+}
+
+fn edge_set(poly: &Nanopolygon) -> BTreeSet<(usize, usize)> {
+    poly.edges
+        .iter()
+        .map(|e| geometry::edge_key(e.start_index, e.end_index))
+        .collect()
+}
+
+fn bio_changes(parent: &Nanopolygon, child: &Nanopolygon) -> Vec<String> {
+    let (a, b) = (&parent.bio, &child.bio);
+    let mut fields = Vec::new();
+    if a.target != b.target {
+        fields.push("target".to_string());
+    }
+    if a.zeta_potential_mv != b.zeta_potential_mv {
+        fields.push("zeta_potential_mv".to_string());
+    }
+    if a.hydrophobicity_index != b.hydrophobicity_index {
+        fields.push("hydrophobicity_index".to_string());
+    }
+    if a.elastic_modulus_kpa != b.elastic_modulus_kpa {
+        fields.push("elastic_modulus_kpa".to_string());
+    }
+    if a.ligands != b.ligands {
+        fields.push("ligands".to_string());
+    }
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xr_lab_grid::nanopoly::generators::{self, PlatonicSolid};
+    use crate::xr_lab_grid::nanopoly::nanopolygon::{
+        BioAffinityTarget, BiophysicalMetadata, SurfaceChemistry, SurfaceLigand,
+    };
+
+    fn bio() -> BiophysicalMetadata {
+        BiophysicalMetadata {
+            target: BioAffinityTarget::GlialCell,
+            zeta_potential_mv: -20.0,
+            hydrophobicity_index: 0.3,
+            elastic_modulus_kpa: 1.0,
+            ligands: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn identical_revisions_have_no_diff() {
+        let cube = generators::platonic("a", PlatonicSolid::Cube, 10.0, bio()).unwrap();
+        let diff = cube.diff(&cube);
+        assert!(diff.hausdorff_nm() < 1e-9);
+        assert!(diff.is_geometry_unchanged(1e-9));
+        assert!(diff.bio_changes.is_empty());
+        assert_eq!(diff.displacement.as_ref().unwrap().moved_vertices, 0);
+        assert_eq!(diff.summary().len(), 4);
+    }
+
+    #[test]
+    fn inflated_sphere_moves_every_vertex_by_the_radius_change() {
+        let parent = generators::icosphere("v1", 10.0, 1, bio()).unwrap();
+        let child = generators::icosphere("v2", 12.0, 1, bio()).unwrap();
+        let diff = parent.diff(&child);
+
+        let d = diff.displacement.as_ref().unwrap();
+        assert_eq!(d.moved_vertices, parent.vertices.len());
+        for value in [d.mean_nm, d.rms_nm, d.max_nm] {
+            assert!((value - 2.0).abs() < 1e-9);
+        }
+        // Child vertices sit 2 nm outside the parent; no sample is farther.
+        assert!((diff.child_to_parent_nm - 2.0).abs() < 1e-9);
+        assert!(diff.parent_to_child_nm > 0.0 && diff.parent_to_child_nm <= 2.0);
+        assert!((diff.area_change_fraction(&parent) - 0.44).abs() < 1e-9);
+        assert!(diff.topology_changes.is_empty());
+        assert!(!diff.is_geometry_unchanged(1.0));
+    }
+
+    #[test]
+    fn counts_and_euler_characteristic_are_reported() {
+        let sphere = generators::icosphere("s", 10.0, 0, bio()).unwrap();
+        let ring = generators::torus("t", 10.0, 3.0, 4, 3, bio()).unwrap();
+        let diff = sphere.diff(&ring);
+        assert!(diff.displacement.is_some());
+        assert_eq!(
+            diff.topology_changes,
+            vec![
+                TopologyChange::EdgeCount { from: 30, to: 24 },
+                TopologyChange::FaceCount { from: 20, to: 12 },
+                TopologyChange::EulerCharacteristic { from: 2, to: 0 },
+            ]
+        );
+        assert!(diff
+            .summary()
+            .contains(&"topology: Euler characteristic 2 -> 0".to_string()));
+    }
+
+    #[test]
+    fn rewired_quad_and_closing_a_surface() {
+        // Same four corners and counts, but the grid quad joins 0-2 and 1-3.
+        let grid = generators::flat_tile("g", 2.0, 2.0, 1, 1, bio()).unwrap();
+        let square = generators::polygon_tile("q", 4, 1.0, bio()).unwrap();
+        assert_eq!(
+            grid.diff(&square).topology_changes,
+            vec![TopologyChange::Connectivity]
+        );
+
+        let tetra = generators::platonic("t", PlatonicSolid::Tetrahedron, 1.0, bio()).unwrap();
+        let changes = square.diff(&tetra).topology_changes;
+        assert!(changes.contains(&TopologyChange::Closed {
+            from: false,
+            to: true
+        }));
+        assert!(changes.contains(&TopologyChange::EulerCharacteristic { from: 1, to: 2 }));
+    }
+
+    #[test]
+    fn bio_field_changes_are_named() {
+        let parent = generators::platonic("a", PlatonicSolid::Octahedron, 5.0, bio()).unwrap();
+        let mut child = parent.clone();
+        child.bio.zeta_potential_mv = -5.0;
+        child.bio.ligands.push(SurfaceLigand {
+            chemistry: SurfaceChemistry::Peg,
+            density_per_nm2: 0.5,
+        });
+        let diff = parent.diff(&child);
+        assert_eq!(diff.bio_changes, vec!["zeta_potential_mv", "ligands"]);
+        assert!(diff.is_geometry_unchanged(1e-9));
+    }
+}
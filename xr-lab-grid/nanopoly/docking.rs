@@ -0,0 +1,396 @@
+use std::f64::consts::PI;
+
+use serde::{Deserialize, Serialize};
+
+use super::generators;
+use super::geometry::{self, Vec3};
+use super::nanopolygon::{BioAffinityTarget, BiophysicalMetadata, Nanopolygon};
+use super::transform::{Quaternion, RigidTransform};
+use super::validation::GeometryError;
+
+// Zeta product (mV^2) at which electrostatic attraction or repulsion
+// is well developed: two surfaces at 25 mV.
+const ZETA_SCALE_MV2: f64 = 625.0;
+
+// Contact area at which the contact term reaches one half.
+const HALF_CONTACT_AREA_NM2: f64 = 50.0;
+
+// Weights of contact, electrostatics and ligand affinity in the score.
+const CONTACT_WEIGHT: f64 = 0.4;
+const ELECTROSTATIC_WEIGHT: f64 = 0.3;
+const AFFINITY_WEIGHT: f64 = 0.3;
+
+// Face normals closer than this are docked only once.
+const DUPLICATE_NORMAL_COS: f64 = 0.999;
+
+// Largest faces whose normals are tried; the sampled spread covers the rest.
+const MAX_FACE_DIRECTIONS: usize = 256;
+
+/// Flat membrane patch in the xy plane at z = 0, with the cell on the -z
+/// side and particles approaching from +z. The patch mesh's metadata
+/// describes the membrane itself.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MembranePatch {
+    pub mesh: Nanopolygon,
+    pub width_nm: f64,
+    pub height_nm: f64,
+}
+
+impl MembranePatch {
+    /// 200 x 200 nm patch with typical surface properties for `target`.
+    pub fn for_target(target: BioAffinityTarget) -> Result<Self, Vec<GeometryError>> {
+        Self::new(membrane_bio(target), 200.0, 200.0)
+    }
+
+    pub fn new(
+        bio: BiophysicalMetadata,
+        width_nm: f64,
+        height_nm: f64,
+    ) -> Result<Self, Vec<GeometryError>> {
+        let id = format!("membrane_{:?}", bio.target);
+        let mesh = generators::flat_tile(&id, width_nm, height_nm, 8, 8, bio)?;
+        Ok(Self {
+            mesh,
+            width_nm,
+            height_nm,
+        })
+    }
+
+    pub fn target(&self) -> &BioAffinityTarget {
+        &self.mesh.bio.target
+    }
+
+    pub fn zeta_potential_mv(&self) -> f32 {
+        self.mesh.bio.zeta_potential_mv
+    }
+
+    fn covers(&self, p: Vec3) -> bool {
+        p[0].abs() <= 0.5 * self.width_nm && p[1].abs() <= 0.5 * self.height_nm
+    }
+}
+
+/// Resting surface properties of each membrane. Cell surfaces carry
+/// sialylated glycocalyx and sit around -20 to -40 mV; matrix is less charged.
+pub fn membrane_bio(target: BioAffinityTarget) -> BiophysicalMetadata {
+    let zeta_potential_mv = match target {
+        BioAffinityTarget::NeuralMembrane => -40.0,
+        BioAffinityTarget::GlialCell => -35.0,
+        BioAffinityTarget::EndothelialCell => -30.0,
+        BioAffinityTarget::MuscleFiber => -30.0,
+        BioAffinityTarget::ExtracellularMatrix => -15.0,
+    };
+    let (lo, hi) = target.modulus_range_kpa();
+    BiophysicalMetadata {
+        target,
+        zeta_potential_mv,
+        hydrophobicity_index: 0.3,
+        // Geometric middle of the native range.
+        elastic_modulus_kpa: (lo * hi).sqrt(),
+        ligands: Vec::new(),
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DockingSettings {
+    /// Evenly spread approach directions tried on top of the particle's own
+    /// face normals.
+    pub sampled_orientations: usize,
+    /// Surface within this distance of the membrane counts as in contact.
+    pub contact_distance_nm: f64,
+}
+
+impl Default for DockingSettings {
+    fn default() -> Self {
+        Self {
+            sampled_orientations: 128,
+            contact_distance_nm: 1.0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DockingResult {
+    pub poly_id: String,
+    pub target: BioAffinityTarget,
+    /// Places the particle on the patch, resting on it at the best orientation.
+    pub pose: RigidTransform,
+    /// Unit direction, in the particle's own frame, that faces the membrane.
+    pub approach_direction: Vec3,
+    pub contact_area_nm2: f64,
+    /// Contact area over the particle's surface area.
+    pub contact_fraction: f64,
+    /// 0 (strong repulsion) to 1 (strong attraction); 0.5 when either side is neutral.
+    pub electrostatic_compatibility: f64,
+    /// Coating affinity for the membrane's tissue.
+    pub affinity: f64,
+    /// Weighted combination of contact, electrostatics and affinity in [0, 1].
+    pub score: f64,
+    pub orientations_tried: usize,
+}
+
+/// Rest `poly` on `patch` in every candidate orientation and keep the one
+/// with the most contact. None for a particle without surface triangles.
+pub fn dock(
+    poly: &Nanopolygon,
+    patch: &MembranePatch,
+    settings: &DockingSettings,
+) -> Option<DockingResult> {
+    let points = poly.points();
+    let triangles = poly.triangulate();
+    let total_area = geometry::surface_area_nm2(&points, &triangles);
+    if triangles.is_empty() || total_area <= 0.0 {
+        return None;
+    }
+
+    let directions = candidate_directions(&points, &triangles, settings.sampled_orientations);
+    let mut best: Option<(Vec3, RigidTransform, f64)> = None;
+    for &direction in &directions {
+        let pose = resting_pose(&points, direction);
+        let area = contact_area(&points, &triangles, &pose, patch, settings);
+        if best.as_ref().is_none_or(|(_, _, a)| area > *a) {
+            best = Some((direction, pose, area));
+        }
+    }
+    let (approach_direction, pose, contact_area_nm2) = best?;
+
+    let zeta_product = poly.bio.zeta_potential_mv as f64 * patch.zeta_potential_mv() as f64;
+    let electrostatic_compatibility = 0.5 - 0.5 * (zeta_product / ZETA_SCALE_MV2).tanh();
+    let affinity = poly.bio.coating().affinity(patch.target()) as f64;
+    let contact = contact_area_nm2 / (contact_area_nm2 + HALF_CONTACT_AREA_NM2);
+    let score = CONTACT_WEIGHT * contact
+        + ELECTROSTATIC_WEIGHT * electrostatic_compatibility
+        + AFFINITY_WEIGHT * affinity;
+
+    Some(DockingResult {
+        poly_id: poly.id.clone(),
+        target: patch.target().clone(),
+        pose,
+        approach_direction,
+        contact_area_nm2,
+        contact_fraction: contact_area_nm2 / total_area,
+        electrostatic_compatibility,
+        affinity,
+        score: score.clamp(0.0, 1.0),
+        orientations_tried: directions.len(),
+    })
+}
+
+/// Dock every candidate and order them best first.
+pub fn rank_candidates(
+    candidates: &[Nanopolygon],
+    patch: &MembranePatch,
+    settings: &DockingSettings,
+) -> Vec<DockingResult> {
+    let mut results: Vec<DockingResult> = candidates
+        .iter()
+        .filter_map(|poly| dock(poly, patch, settings))
+        .collect();
+    results.sort_by(|a, b| b.score.total_cmp(&a.score));
+    results
+}
+
+impl Nanopolygon {
+    pub fn dock_against(&self, patch: &MembranePatch) -> Option<DockingResult> {
+        dock(self, patch, &DockingSettings::default())
+    }
+}
+
+// Outward face normals (largest faces first, duplicates dropped), then a
+// Fibonacci spread over the sphere.
+fn candidate_directions(points: &[Vec3], triangles: &[[usize; 3]], sampled: usize) -> Vec<Vec3> {
+    let mut faces: Vec<(f64, Vec3)> = triangles
+        .iter()
+        .filter_map(|t| {
+            let n = geometry::cross(
+                geometry::sub(points[t[1]], points[t[0]]),
+                geometry::sub(points[t[2]], points[t[0]]),
+            );
+            let len = geometry::norm(n);
+            (len > 0.0).then(|| (len, geometry::scale(n, 1.0 / len)))
+        })
+        .collect();
+    faces.sort_by(|a, b| b.0.total_cmp(&a.0));
+    faces.truncate(MAX_FACE_DIRECTIONS);
+
+    let mut directions: Vec<Vec3> = Vec::new();
+    let golden = PI * (3.0 - 5.0_f64.sqrt());
+    let sampled_directions = (0..sampled).map(|i| {
+        let z = 1.0 - 2.0 * (i as f64 + 0.5) / sampled as f64;
+        let r = (1.0 - z * z).max(0.0).sqrt();
+        let phi = golden * i as f64;
+        [r * phi.cos(), r * phi.sin(), z]
+    });
+    for d in faces.into_iter().map(|(_, n)| n).chain(sampled_directions) {
+        if directions
+            .iter()
+            .all(|e| geometry::dot(*e, d) < DUPLICATE_NORMAL_COS)
+        {
+            directions.push(d);
+        }
+    }
+    directions
+}
+
+// Turn `direction` to face -z, centre the particle over the patch and lower
+// it until its lowest point touches z = 0.
+fn resting_pose(points: &[Vec3], direction: Vec3) -> RigidTransform {
+    let down = [0.0, 0.0, -1.0];
+    let cos = geometry::dot(direction, down).clamp(-1.0, 1.0);
+    let axis = geometry::cross(direction, down);
+    let rotation = if geometry::norm(axis) > 1e-12 {
+        Quaternion::from_axis_angle(axis, cos.acos())
+    } else if cos > 0.0 {
+        Quaternion::identity()
+    } else {
+        Quaternion::from_axis_angle([1.0, 0.0, 0.0], PI)
+    };
+
+    let rotated: Vec<Vec3> = points.iter().map(|&p| rotation.rotate(p)).collect();
+    let n = rotated.len().max(1) as f64;
+    let centroid = rotated
+        .iter()
+        .fold([0.0; 3], |acc, &p| geometry::add(acc, p));
+    let lowest = rotated.iter().map(|p| p[2]).fold(f64::INFINITY, f64::min);
+    RigidTransform::new(rotation, [-centroid[0] / n, -centroid[1] / n, -lowest])
+}
+
+// Area of triangles lying flat enough and close enough to the patch, counted
+// where they sit over it.
+fn contact_area(
+    points: &[Vec3],
+    triangles: &[[usize; 3]],
+    pose: &RigidTransform,
+    patch: &MembranePatch,
+    settings: &DockingSettings,
+) -> f64 {
+    let placed: Vec<Vec3> = points.iter().map(|&p| pose.apply(p)).collect();
+    triangles
+        .iter()
+        .filter_map(|t| {
+            let (a, b, c) = (placed[t[0]], placed[t[1]], placed[t[2]]);
+            let centroid = geometry::scale(geometry::add(geometry::add(a, b), c), 1.0 / 3.0);
+            if centroid[2] > settings.contact_distance_nm || !patch.covers(centroid) {
+                return None;
+            }
+            // Projected area: triangles meeting the membrane edge-on add nothing.
+            let n = geometry::cross(geometry::sub(b, a), geometry::sub(c, a));
+            Some(0.5 * n[2].abs())
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xr_lab_grid::nanopoly::generators::PlatonicSolid;
+    use crate::xr_lab_grid::nanopoly::nanopolygon::{Edge, VertexNm};
+
+    fn bio(zeta_potential_mv: f32) -> BiophysicalMetadata {
+        BiophysicalMetadata {
+            target: BioAffinityTarget::NeuralMembrane,
+            zeta_potential_mv,
+            hydrophobicity_index: 0.3,
+            elastic_modulus_kpa: 0.5,
+            ligands: Vec::new(),
+        }
+    }
+
+    fn cube(id: &str, side_nm: f64, zeta_mv: f32) -> Nanopolygon {
+        let r = side_nm * 3f64.sqrt() / 2.0;
+        generators::platonic(id, PlatonicSolid::Cube, r, bio(zeta_mv)).unwrap()
+    }
+
+    #[test]
+    fn membranes_use_their_tissue_properties() {
+        let patch = MembranePatch::for_target(BioAffinityTarget::NeuralMembrane).unwrap();
+        assert_eq!(patch.zeta_potential_mv(), -40.0);
+        assert!((patch.mesh.surface_area_nm2 - 40_000.0).abs() < 1e-6);
+        // Geometric middle of 0.1-1.0 kPa.
+        assert!((patch.mesh.bio.elastic_modulus_kpa - 0.1f32.sqrt()).abs() < 1e-6);
+        assert_eq!(
+            membrane_bio(BioAffinityTarget::ExtracellularMatrix).zeta_potential_mv,
+            -15.0
+        );
+    }
+
+    #[test]
+    fn cube_rests_on_a_face() {
+        let patch = MembranePatch::for_target(BioAffinityTarget::NeuralMembrane).unwrap();
+        let poly = cube("c", 20.0, 0.0);
+        let result = poly.dock_against(&patch).unwrap();
+
+        assert!((result.contact_area_nm2 - 400.0).abs() < 1e-6);
+        assert!((result.contact_fraction - 1.0 / 6.0).abs() < 1e-9);
+        // The approach direction is one of the six face normals.
+        let largest = result
+            .approach_direction
+            .iter()
+            .fold(0.0_f64, |m, c| m.max(c.abs()));
+        assert!((largest - 1.0).abs() < 1e-9);
+        // Resting on the membrane, centred over the patch.
+        let placed: Vec<Vec3> = poly
+            .points()
+            .iter()
+            .map(|&p| result.pose.apply(p))
+            .collect();
+        let lowest = placed.iter().map(|p| p[2]).fold(f64::INFINITY, f64::min);
+        assert!(lowest.abs() < 1e-9);
+        assert!(placed
+            .iter()
+            .all(|p| p[0].abs() <= 10.0 + 1e-9 && p[1].abs() <= 10.0 + 1e-9));
+
+        // Neutral particle: electrostatics 0.5, bare affinity 0.2.
+        assert_eq!(result.electrostatic_compatibility, 0.5);
+        assert!((result.affinity - 0.2).abs() < 1e-6);
+        let expected = 0.4 * 400.0 / 450.0 + 0.3 * 0.5 + 0.3 * 0.2;
+        assert!((result.score - expected).abs() < 1e-6);
+    }
+
+    #[test]
+    fn opposite_charges_attract() {
+        let patch = MembranePatch::new(
+            BiophysicalMetadata {
+                zeta_potential_mv: -25.0,
+                ..membrane_bio(BioAffinityTarget::GlialCell)
+            },
+            200.0,
+            200.0,
+        )
+        .unwrap();
+        let attract = dock(&cube("a", 20.0, 25.0), &patch, &DockingSettings::default()).unwrap();
+        let repel = dock(&cube("r", 20.0, -25.0), &patch, &DockingSettings::default()).unwrap();
+        assert!((attract.electrostatic_compatibility - (0.5 + 0.5 * 1f64.tanh())).abs() < 1e-9);
+        assert!((repel.electrostatic_compatibility - (0.5 - 0.5 * 1f64.tanh())).abs() < 1e-9);
+        assert!(attract.score > repel.score);
+    }
+
+    #[test]
+    fn flat_faces_outrank_spheres() {
+        let patch = MembranePatch::for_target(BioAffinityTarget::EndothelialCell).unwrap();
+        let sphere =
+            generators::icosphere("sphere", 20.0 * 3f64.sqrt() / 2.0, 2, bio(-20.0)).unwrap();
+        let ranked = rank_candidates(
+            &[sphere, cube("cube", 20.0, -20.0)],
+            &patch,
+            &DockingSettings::default(),
+        );
+        let ids: Vec<&str> = ranked.iter().map(|r| r.poly_id.as_str()).collect();
+        assert_eq!(ids, vec!["cube", "sphere"]);
+        assert!(ranked[1].contact_area_nm2 < ranked[0].contact_area_nm2);
+    }
+
+    #[test]
+    fn wire_without_surface_does_not_dock() {
+        let patch = MembranePatch::for_target(BioAffinityTarget::MuscleFiber).unwrap();
+        let vertices = vec![
+            VertexNm::from_vec3([0.0, 0.0, 0.0]),
+            VertexNm::from_vec3([5.0, 0.0, 0.0]),
+        ];
+        let edges = vec![Edge {
+            start_index: 0,
+            end_index: 1,
+        }];
+        let wire = Nanopolygon::new("w", vertices, edges, bio(0.0));
+        assert!(wire.dock_against(&patch).is_none());
+    }
+}
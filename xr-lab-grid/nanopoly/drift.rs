@@ -0,0 +1,266 @@
+use serde::{Deserialize, Serialize};
+
+use super::core::nanopoly_object::NanopolyObject;
+use super::nanoswarm::NanoswarmMember;
+
+/// DW (psych-drift) model, independent of metabolic demand: drift comes from
+/// neural interfacing and from bursty activity at neurally exposed sites.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DriftModel {
+    /// BCI bit rate at which the interface term saturates.
+    pub reference_bit_rate_bps: f32,
+    /// Share of the interface term kept for read-only interfaces.
+    pub read_only_factor: f32,
+    pub interface_weight: f32,
+    pub burst_weight: f32,
+}
+
+impl Default for DriftModel {
+    fn default() -> Self {
+        Self {
+            reference_bit_rate_bps: 1000.0,
+            read_only_factor: 0.5,
+            interface_weight: 0.6,
+            burst_weight: 0.4,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MemberDrift {
+    pub member_id: String,
+    pub neural_exposure: f32,
+    /// BCI load in [0, 1].
+    pub interface_load: f32,
+    /// Time-averaged swing between basal and peak draw, in [0, 1].
+    pub burstiness: f32,
+    pub dw: f32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DriftReport {
+    pub members: Vec<MemberDrift>,
+    /// Members combined as independent contributions: 1 - prod(1 - dw).
+    pub dw: f32,
+}
+
+impl DriftModel {
+    pub fn member(&self, object: &NanopolyObject, duty_cycle: f32) -> MemberDrift {
+        let neural_exposure = object.polygon.bio.target.neural_exposure();
+
+        let bci = &object.bci;
+        let rate = (bci.max_bit_rate_bps / self.reference_bit_rate_bps).clamp(0.0, 1.0);
+        let interface_load = if bci.output_bands_hz.is_empty() {
+            rate * self.read_only_factor
+        } else {
+            rate
+        };
+
+        let energy = &object.energy;
+        let burstiness = if energy.peak_glucose_uW > 0.0 {
+            let swing = (energy.peak_glucose_uW - energy.basal_glucose_uW).max(0.0)
+                / energy.peak_glucose_uW;
+            (duty_cycle.clamp(0.0, 1.0) as f64 * swing) as f32
+        } else {
+            0.0
+        };
+
+        let dw = neural_exposure
+            * (self.interface_weight * interface_load + self.burst_weight * burstiness);
+        MemberDrift {
+            member_id: object.id.clone(),
+            neural_exposure,
+            interface_load,
+            burstiness,
+            dw: dw.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Only active members drift; quarantine cuts the interface.
+    pub fn evaluate(&self, members: &[NanoswarmMember], duty_cycle: f32) -> DriftReport {
+        let members: Vec<MemberDrift> = members
+            .iter()
+            .filter(|m| m.lifecycle.is_active())
+            .map(|m| self.member(&m.object, duty_cycle))
+            .collect();
+        let dw = 1.0 - members.iter().fold(1.0, |acc, m| acc * (1.0 - m.dw));
+        DriftReport { members, dw }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xr_lab_grid::nanopoly::core::governance::GovernanceLayer;
+    use crate::xr_lab_grid::nanopoly::core::nanopoly_object::{BciInterface, EnergeticProfile};
+    use crate::xr_lab_grid::nanopoly::core::species::{HostBudgetProfile, HostType, SpeciesId};
+    use crate::xr_lab_grid::nanopoly::energy::EnergyModel;
+    use crate::xr_lab_grid::nanopoly::generators;
+    use crate::xr_lab_grid::nanopoly::lifecycle::MemberState;
+    use crate::xr_lab_grid::nanopoly::nanopolygon::{BioAffinityTarget, BiophysicalMetadata};
+    use crate::xr_lab_grid::nanopoly::transform::RigidTransform;
+
+    fn object(
+        target: BioAffinityTarget,
+        basal: f64,
+        peak: f64,
+        bci: BciInterface,
+    ) -> NanopolyObject {
+        let bio = BiophysicalMetadata {
+            target,
+            zeta_potential_mv: -20.0,
+            hydrophobicity_index: 0.3,
+            elastic_modulus_kpa: 1.0,
+            ligands: Vec::new(),
+        };
+        let energy = EnergeticProfile {
+            basal_glucose_uW: basal,
+            peak_glucose_uW: peak,
+            protein_turnover_uW: 0.0,
+            hemodynamic_coupling: 0.0,
+        };
+        NanopolyObject::from_parts(
+            "m",
+            generators::icosphere("m", 50.0, 1, bio).unwrap(),
+            energy,
+            bci,
+            GovernanceLayer::human("did:example:owner"),
+        )
+    }
+
+    fn bci(bit_rate_bps: f32, writes: bool) -> BciInterface {
+        BciInterface {
+            input_bands_hz: vec![(8.0, 12.0)],
+            output_bands_hz: if writes {
+                vec![(30.0, 80.0)]
+            } else {
+                Vec::new()
+            },
+            max_bit_rate_bps: bit_rate_bps,
+        }
+    }
+
+    #[test]
+    fn burstiness_is_duty_times_the_relative_swing() {
+        let model = DriftModel::default();
+        let o = object(
+            BioAffinityTarget::NeuralMembrane,
+            100.0,
+            400.0,
+            BciInterface::none(),
+        );
+        let drift = model.member(&o, 0.5);
+        assert!((drift.burstiness - 0.375).abs() < 1e-6);
+        assert_eq!(drift.interface_load, 0.0);
+        assert!((drift.dw - 0.4 * 0.375).abs() < 1e-6);
+
+        // Flat draw and zero duty never burst.
+        let flat = object(
+            BioAffinityTarget::NeuralMembrane,
+            100.0,
+            100.0,
+            BciInterface::none(),
+        );
+        assert_eq!(model.member(&flat, 1.0).burstiness, 0.0);
+        assert_eq!(model.member(&o, 0.0).burstiness, 0.0);
+    }
+
+    #[test]
+    fn read_only_interfaces_carry_half_the_interface_term() {
+        let model = DriftModel::default();
+        let writer = object(
+            BioAffinityTarget::NeuralMembrane,
+            1.0,
+            1.0,
+            bci(500.0, true),
+        );
+        let reader = object(
+            BioAffinityTarget::NeuralMembrane,
+            1.0,
+            1.0,
+            bci(500.0, false),
+        );
+        let w = model.member(&writer, 0.0);
+        let r = model.member(&reader, 0.0);
+        assert!((w.interface_load - 0.5).abs() < 1e-6);
+        assert!((r.interface_load - 0.25).abs() < 1e-6);
+        assert!((w.dw - 0.3).abs() < 1e-6);
+        assert!((r.dw - 0.15).abs() < 1e-6);
+
+        // The interface term saturates at the reference bit rate.
+        let fast = object(
+            BioAffinityTarget::NeuralMembrane,
+            1.0,
+            1.0,
+            bci(5000.0, true),
+        );
+        assert_eq!(model.member(&fast, 0.0).interface_load, 1.0);
+    }
+
+    #[test]
+    fn exposure_scales_drift_by_attachment_site() {
+        let model = DriftModel::default();
+        let dw = |target| {
+            model
+                .member(&object(target, 1.0, 1.0, bci(1000.0, true)), 0.0)
+                .dw
+        };
+        assert!((dw(BioAffinityTarget::NeuralMembrane) - 0.6).abs() < 1e-6);
+        assert!((dw(BioAffinityTarget::GlialCell) - 0.36).abs() < 1e-6);
+        assert!((dw(BioAffinityTarget::MuscleFiber) - 0.06).abs() < 1e-6);
+    }
+
+    #[test]
+    fn drift_is_independent_of_demand() {
+        let budget = HostBudgetProfile::default_for(SpeciesId::HomoSapiens, HostType::Human);
+        let model = DriftModel::default();
+        let small = object(
+            BioAffinityTarget::NeuralMembrane,
+            1_000.0,
+            3_000.0,
+            bci(200.0, true),
+        );
+        let large = object(
+            BioAffinityTarget::NeuralMembrane,
+            10_000.0,
+            30_000.0,
+            bci(200.0, true),
+        );
+
+        // Ten times the draw at the same peak/basal ratio is ten times the D ...
+        let d = |o: &NanopolyObject| {
+            let m = NanoswarmMember::new(o.clone(), RigidTransform::identity());
+            EnergyModel::default().evaluate(&[m], &budget).demand_d
+        };
+        assert!((d(&large) - 10.0 * d(&small)).abs() < 1e-6);
+
+        // ... and exactly the same DW.
+        let duty = budget.duty_cycle_max;
+        assert_eq!(model.member(&small, duty).dw, model.member(&large, duty).dw);
+    }
+
+    #[test]
+    fn active_members_combine_as_independent_contributions() {
+        let model = DriftModel::default();
+        let member = |rate: f32, state: MemberState| {
+            let mut m = NanoswarmMember::new(
+                object(BioAffinityTarget::NeuralMembrane, 1.0, 1.0, bci(rate, true)),
+                RigidTransform::identity(),
+            );
+            m.lifecycle.state = state;
+            m
+        };
+        // Two members at dw 0.3 and 0.6.
+        let members = [
+            member(500.0, MemberState::Active),
+            member(1000.0, MemberState::Active),
+            member(1000.0, MemberState::Quarantined),
+            member(1000.0, MemberState::Retired),
+        ];
+        let report = model.evaluate(&members, 0.5);
+        assert_eq!(report.members.len(), 2);
+        assert!((report.dw - (1.0 - 0.7 * 0.4)).abs() < 1e-6);
+
+        assert_eq!(model.evaluate(&[], 0.5).dw, 0.0);
+    }
+}
@@ -0,0 +1,305 @@
+use serde::{Deserialize, Serialize};
+
+use super::core::nanopoly_object::EnergeticProfile;
+use super::core::species::HostBudgetProfile;
+use super::lifecycle::MemberState;
+use super::nanopolygon::BioAffinityTarget;
+use super::nanoswarm::NanoswarmMember;
+
+impl EnergeticProfile {
+    /// Time-averaged draw when active (peak) for `duty_cycle` of the time,
+    /// plus protein turnover.
+    pub fn mean_draw_uw(&self, duty_cycle: f64) -> f64 {
+        let duty = duty_cycle.clamp(0.0, 1.0);
+        let glucose =
+            self.basal_glucose_uW + duty * (self.peak_glucose_uW - self.basal_glucose_uW).max(0.0);
+        glucose + self.protein_turnover_uW
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EnergyModel {
+    /// Perfusion at which blood-coupled draw costs the same as uncoupled draw.
+    pub reference_perfusion_ml_per_100g_min: f64,
+    /// Floor on perfusion relative to the reference, so a poorly perfused
+    /// tissue raises the cost of coupled draw at most 1 / floor times.
+    pub min_relative_perfusion: f64,
+}
+
+impl Default for EnergyModel {
+    fn default() -> Self {
+        Self {
+            reference_perfusion_ml_per_100g_min: 60.0,
+            min_relative_perfusion: 0.25,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MemberEnergy {
+    pub member_id: String,
+    pub target: BioAffinityTarget,
+    pub mean_draw_uw: f64,
+    /// Cost multiplier from the blood-coupled share of the draw.
+    pub perfusion_factor: f64,
+    /// Draw as felt by the host: mean draw times the perfusion factor.
+    pub load_uw: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EnergyReport {
+    pub members: Vec<MemberEnergy>,
+    pub total_load_uw: f64,
+    pub metabolic_budget_uw: f64,
+    /// Total load over the host's metabolic budget, clamped to [0, 1].
+    pub demand_d: f32,
+}
+
+impl EnergyModel {
+    /// Share of the draw coupled to blood flow is supplied by local
+    /// perfusion; where the tissue is poorly perfused that share costs more.
+    pub fn perfusion_factor(&self, profile: &EnergeticProfile, target: &BioAffinityTarget) -> f64 {
+        let coupling = profile.hemodynamic_coupling.clamp(0.0, 1.0);
+        let relative = (target.perfusion_ml_per_100g_min()
+            / self.reference_perfusion_ml_per_100g_min)
+            .max(self.min_relative_perfusion);
+        (1.0 - coupling) + coupling / relative
+    }
+
+    pub fn member(&self, member: &NanoswarmMember, budget: &HostBudgetProfile) -> MemberEnergy {
+        // Quarantined members are held at basal draw.
+        let duty_cycle = match member.lifecycle.state {
+            MemberState::Active => budget.duty_cycle_max as f64,
+            MemberState::Quarantined | MemberState::Retired => 0.0,
+        };
+        self.member_at_duty(member, duty_cycle)
+    }
+
+    /// Draw and load of `member` at peak for `duty_cycle` of the time,
+    /// regardless of its lifecycle state.
+    pub fn member_at_duty(&self, member: &NanoswarmMember, duty_cycle: f64) -> MemberEnergy {
+        let object = &member.object;
+        let target = object.polygon.bio.target.clone();
+        let mean_draw_uw = object.energy.mean_draw_uw(duty_cycle);
+        let perfusion_factor = self.perfusion_factor(&object.energy, &target);
+        MemberEnergy {
+            member_id: object.id.clone(),
+            target,
+            mean_draw_uw,
+            perfusion_factor,
+            load_uw: mean_draw_uw * perfusion_factor,
+        }
+    }
+
+    /// Total load over the host's metabolic budget, clamped to [0, 1]. Any
+    /// load against an empty budget saturates.
+    pub fn demand_d(total_load_uw: f64, budget: &HostBudgetProfile) -> f32 {
+        if budget.metabolic_budget_uw > 0.0 {
+            (total_load_uw / budget.metabolic_budget_uw).clamp(0.0, 1.0) as f32
+        } else if total_load_uw > 0.0 {
+            1.0
+        } else {
+            0.0
+        }
+    }
+
+    /// Retired members draw nothing and are left out.
+    pub fn evaluate(
+        &self,
+        members: &[NanoswarmMember],
+        budget: &HostBudgetProfile,
+    ) -> EnergyReport {
+        let members: Vec<MemberEnergy> = members
+            .iter()
+            .filter(|m| m.lifecycle.state != MemberState::Retired)
+            .map(|m| self.member(m, budget))
+            .collect();
+        let total_load_uw: f64 = members.iter().map(|m| m.load_uw).sum();
+        EnergyReport {
+            members,
+            total_load_uw,
+            metabolic_budget_uw: budget.metabolic_budget_uw,
+            demand_d: Self::demand_d(total_load_uw, budget),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xr_lab_grid::nanopoly::core::governance::GovernanceLayer;
+    use crate::xr_lab_grid::nanopoly::core::nanopoly_object::{BciInterface, NanopolyObject};
+    use crate::xr_lab_grid::nanopoly::core::species::{HostType, SpeciesId};
+    use crate::xr_lab_grid::nanopoly::generators;
+    use crate::xr_lab_grid::nanopoly::nanopolygon::BiophysicalMetadata;
+    use crate::xr_lab_grid::nanopoly::transform::RigidTransform;
+
+    fn bio(target: BioAffinityTarget) -> BiophysicalMetadata {
+        BiophysicalMetadata {
+            target,
+            zeta_potential_mv: -20.0,
+            hydrophobicity_index: 0.3,
+            elastic_modulus_kpa: 10.0,
+            ligands: Vec::new(),
+        }
+    }
+
+    fn profile(basal: f64, peak: f64, turnover: f64, coupling: f64) -> EnergeticProfile {
+        EnergeticProfile {
+            basal_glucose_uW: basal,
+            peak_glucose_uW: peak,
+            protein_turnover_uW: turnover,
+            hemodynamic_coupling: coupling,
+        }
+    }
+
+    fn member(id: &str, target: BioAffinityTarget, energy: EnergeticProfile) -> NanoswarmMember {
+        let poly = generators::icosphere(id, 50.0, 1, bio(target)).unwrap();
+        let object = NanopolyObject::from_parts(
+            id,
+            poly,
+            energy,
+            BciInterface::none(),
+            GovernanceLayer::human("did:example:owner"),
+        );
+        NanoswarmMember::new(object, RigidTransform::identity())
+    }
+
+    fn human() -> HostBudgetProfile {
+        HostBudgetProfile::default_for(SpeciesId::HomoSapiens, HostType::Human)
+    }
+
+    #[test]
+    fn mean_draw_interpolates_basal_to_peak_by_duty() {
+        let p = profile(100.0, 300.0, 10.0, 0.0);
+        assert_eq!(p.mean_draw_uw(0.0), 110.0);
+        assert_eq!(p.mean_draw_uw(0.25), 160.0);
+        assert_eq!(p.mean_draw_uw(1.0), 310.0);
+        // Duty is clamped, and a peak below basal adds nothing.
+        assert_eq!(p.mean_draw_uw(2.0), 310.0);
+        assert_eq!(p.mean_draw_uw(-1.0), 110.0);
+        assert_eq!(profile(100.0, 50.0, 0.0, 0.0).mean_draw_uw(1.0), 100.0);
+    }
+
+    #[test]
+    fn perfusion_weights_the_blood_coupled_share() {
+        let model = EnergyModel::default();
+        let coupled = profile(1.0, 1.0, 0.0, 1.0);
+        // Muscle at 4 ml/100g/min sits below the floor of 0.25 x 60.
+        let muscle = model.perfusion_factor(&coupled, &BioAffinityTarget::MuscleFiber);
+        assert!((muscle - 4.0).abs() < 1e-12);
+        // Well-perfused endothelium makes coupled draw cheaper: 60 / 80.
+        let vessel = model.perfusion_factor(&coupled, &BioAffinityTarget::EndothelialCell);
+        assert!((vessel - 0.75).abs() < 1e-12);
+        // Neural tissue sits at the reference.
+        let neural = model.perfusion_factor(&coupled, &BioAffinityTarget::NeuralMembrane);
+        assert!((neural - 1.0).abs() < 1e-12);
+
+        // Half coupled on glia: 0.5 + 0.5 / (22 / 60).
+        let half = profile(1.0, 1.0, 0.0, 0.5);
+        let glia = model.perfusion_factor(&half, &BioAffinityTarget::GlialCell);
+        assert!((glia - (0.5 + 0.5 * 60.0 / 22.0)).abs() < 1e-12);
+
+        // Uncoupled draw ignores perfusion entirely.
+        for target in BioAffinityTarget::ALL {
+            let factor = model.perfusion_factor(&profile(1.0, 1.0, 0.0, 0.0), &target);
+            assert_eq!(factor, 1.0);
+        }
+    }
+
+    #[test]
+    fn member_load_is_mean_draw_times_perfusion_factor() {
+        let budget = human();
+        let m = member(
+            "m",
+            BioAffinityTarget::MuscleFiber,
+            profile(100.0, 300.0, 0.0, 0.5),
+        );
+        let energy = EnergyModel::default().member(&m, &budget);
+        // Human duty cycle 0.5: 100 + 0.5 x 200; factor 0.5 + 0.5 x 4.
+        assert_eq!(energy.member_id, "m");
+        assert_eq!(energy.mean_draw_uw, 200.0);
+        assert!((energy.perfusion_factor - 2.5).abs() < 1e-12);
+        assert!((energy.load_uw - 500.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn demand_rises_with_the_peak_to_basal_ratio() {
+        let budget = human();
+        let model = EnergyModel::default();
+        let demand = |ratio: f64| {
+            let m = member(
+                "m",
+                BioAffinityTarget::NeuralMembrane,
+                profile(10_000.0, 10_000.0 * ratio, 0.0, 0.0),
+            );
+            model.evaluate(&[m], &budget).demand_d
+        };
+        let ds: Vec<f32> = [1.0, 2.0, 4.0, 8.0].iter().map(|&r| demand(r)).collect();
+        assert!(ds.windows(2).all(|w| w[1] > w[0]), "{:?}", ds);
+        // Flat draw of 10 mW against the 1 W human budget.
+        assert!((ds[0] - 0.01).abs() < 1e-6);
+        // Ratio 8 at duty 0.5: 10 + 0.5 x 70 = 45 mW.
+        assert!((ds[3] - 0.045).abs() < 1e-6);
+    }
+
+    #[test]
+    fn demand_is_measured_against_the_kleiber_budget() {
+        let model = EnergyModel::default();
+        let m = member(
+            "m",
+            BioAffinityTarget::NeuralMembrane,
+            EnergeticProfile::constant(50_000.0),
+        );
+        let person = model.evaluate(&[m.clone()], &human());
+        assert_eq!(person.metabolic_budget_uw, 1.0e6);
+        assert!((person.demand_d - 0.05).abs() < 1e-6);
+
+        // A 20 kg dog has (20 / 70)^0.75 of the human budget.
+        let dog_budget = HostBudgetProfile::default_for(
+            SpeciesId::CanisLupusFamiliaris,
+            HostType::NonHumanCompanion,
+        );
+        let dog = model.evaluate(&[m], &dog_budget);
+        let scale = (20.0f64 / 70.0).powf(0.75);
+        assert!((dog.metabolic_budget_uw - 1.0e6 * scale).abs() < 1e-3);
+        assert!((dog.demand_d as f64 - 0.05 / scale).abs() < 1e-6);
+
+        // Draw beyond the budget saturates at 1; an empty budget blocks any draw.
+        let big = member(
+            "big",
+            BioAffinityTarget::NeuralMembrane,
+            EnergeticProfile::constant(5.0e6),
+        );
+        assert_eq!(model.evaluate(&[big.clone()], &human()).demand_d, 1.0);
+        let empty = HostBudgetProfile {
+            metabolic_budget_uw: 0.0,
+            ..human()
+        };
+        assert_eq!(model.evaluate(&[big], &empty).demand_d, 1.0);
+        assert_eq!(model.evaluate(&[], &empty).demand_d, 0.0);
+    }
+
+    #[test]
+    fn quarantined_members_draw_basal_and_retired_members_are_left_out() {
+        let budget = human();
+        let model = EnergyModel::default();
+        let energy = profile(100.0, 300.0, 20.0, 0.0);
+        let active = member("a", BioAffinityTarget::NeuralMembrane, energy.clone());
+        let mut held = member("q", BioAffinityTarget::NeuralMembrane, energy.clone());
+        held.lifecycle.state = MemberState::Quarantined;
+        let mut gone = member("r", BioAffinityTarget::NeuralMembrane, energy);
+        gone.lifecycle.state = MemberState::Retired;
+
+        let report = model.evaluate(&[active, held, gone], &budget);
+        let ids: Vec<&str> = report
+            .members
+            .iter()
+            .map(|m| m.member_id.as_str())
+            .collect();
+        assert_eq!(ids, ["a", "q"]);
+        assert_eq!(report.members[0].mean_draw_uw, 220.0);
+        assert_eq!(report.members[1].mean_draw_uw, 120.0);
+        assert_eq!(report.total_load_uw, 340.0);
+    }
+}
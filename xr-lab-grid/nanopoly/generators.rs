@@ -0,0 +1,547 @@
+use std::collections::{BTreeSet, HashMap};
+use std::f64::consts::PI;
+
+use serde::{Deserialize, Serialize};
+
+use super::geometry::{self, Vec3};
+use super::nanopolygon::{BiophysicalMetadata, Face, Nanopolygon, VertexNm};
+use super::validation::GeometryError;
+
+// Golden ratio, for the icosahedron and dodecahedron.
+const PHI: f64 = 1.618_033_988_749_895;
+
+// Relative tolerance for coplanarity when collecting hull faces.
+const HULL_TOLERANCE: f64 = 1e-9;
+
+// Every generator centres its shape on the origin with outward-facing winding
+// and returns it through the validating constructor.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PlatonicSolid {
+    Tetrahedron,
+    Cube,
+    Octahedron,
+    Dodecahedron,
+    Icosahedron,
+}
+
+/// Platonic solid with every vertex at `circumradius_nm`.
+pub fn platonic(
+    id: &str,
+    solid: PlatonicSolid,
+    circumradius_nm: f64,
+    bio: BiophysicalMetadata,
+) -> Result<Nanopolygon, Vec<GeometryError>> {
+    let points = scaled_to_radius(platonic_vertices(solid), circumradius_nm);
+    let faces = convex_hull_faces(&points);
+    build(id, points, faces, bio)
+}
+
+/// Geodesic sphere: an icosahedron with each triangle split into four
+/// `subdivisions` times, vertices pushed out to `radius_nm`.
+/// Triangle count is 20 * 4^subdivisions.
+pub fn icosphere(
+    id: &str,
+    radius_nm: f64,
+    subdivisions: u32,
+    bio: BiophysicalMetadata,
+) -> Result<Nanopolygon, Vec<GeometryError>> {
+    let mut points = scaled_to_radius(platonic_vertices(PlatonicSolid::Icosahedron), 1.0);
+    let mut triangles: Vec<[usize; 3]> = convex_hull_faces(&points)
+        .into_iter()
+        .map(|f| [f[0], f[1], f[2]])
+        .collect();
+
+    for _ in 0..subdivisions {
+        let mut midpoints: HashMap<(usize, usize), usize> = HashMap::new();
+        let mut next = Vec::with_capacity(triangles.len() * 4);
+        for [a, b, c] in triangles {
+            let ab = midpoint_on_sphere(&mut points, &mut midpoints, a, b);
+            let bc = midpoint_on_sphere(&mut points, &mut midpoints, b, c);
+            let ca = midpoint_on_sphere(&mut points, &mut midpoints, c, a);
+            next.extend([[a, ab, ca], [b, bc, ab], [c, ca, bc], [ab, bc, ca]]);
+        }
+        triangles = next;
+    }
+
+    let points = points
+        .into_iter()
+        .map(|p| geometry::scale(p, radius_nm))
+        .collect();
+    build(
+        id,
+        points,
+        triangles.iter().map(|t| t.to_vec()).collect(),
+        bio,
+    )
+}
+
+/// Closed cylinder along z with flat polygonal caps.
+pub fn cylinder(
+    id: &str,
+    radius_nm: f64,
+    length_nm: f64,
+    segments: usize,
+    bio: BiophysicalMetadata,
+) -> Result<Nanopolygon, Vec<GeometryError>> {
+    let segments = segments.max(3);
+    let half = 0.5 * length_nm;
+    let mut points = ring(radius_nm, -half, segments);
+    points.extend(ring(radius_nm, half, segments));
+
+    let mut faces = Vec::with_capacity(segments + 2);
+    for j in 0..segments {
+        let k = (j + 1) % segments;
+        faces.push(vec![j, k, segments + k, segments + j]);
+    }
+    faces.push((0..segments).rev().collect());
+    faces.push((segments..2 * segments).collect());
+    build(id, points, faces, bio)
+}
+
+/// Rod along z: a cylinder of `length_nm` with a hemisphere on each end,
+/// so the overall length is `length_nm + 2 * radius_nm`.
+pub fn capped_rod(
+    id: &str,
+    radius_nm: f64,
+    length_nm: f64,
+    segments: usize,
+    bio: BiophysicalMetadata,
+) -> Result<Nanopolygon, Vec<GeometryError>> {
+    let segments = segments.max(3);
+    let cap_rings = (segments / 4).max(2);
+    let half = 0.5 * length_nm;
+
+    // Rings from the bottom pole to the top pole; the two equator rings bound the shaft.
+    let mut rings: Vec<(f64, f64)> = Vec::new();
+    for i in 1..=cap_rings {
+        let theta = 0.5 * PI * i as f64 / cap_rings as f64;
+        rings.push((radius_nm * theta.sin(), -half - radius_nm * theta.cos()));
+    }
+    for i in (1..=cap_rings).rev() {
+        let theta = 0.5 * PI * i as f64 / cap_rings as f64;
+        rings.push((radius_nm * theta.sin(), half + radius_nm * theta.cos()));
+    }
+
+    let mut points = vec![[0.0, 0.0, -half - radius_nm]];
+    for &(r, z) in &rings {
+        points.extend(ring(r, z, segments));
+    }
+    let top = points.len();
+    points.push([0.0, 0.0, half + radius_nm]);
+
+    let at = |ring: usize, j: usize| 1 + ring * segments + j % segments;
+    let mut faces = Vec::new();
+    for j in 0..segments {
+        faces.push(vec![0, at(0, j + 1), at(0, j)]);
+    }
+    for r in 0..rings.len() - 1 {
+        for j in 0..segments {
+            faces.push(vec![at(r, j), at(r, j + 1), at(r + 1, j + 1), at(r + 1, j)]);
+        }
+    }
+    let last = rings.len() - 1;
+    for j in 0..segments {
+        faces.push(vec![top, at(last, j), at(last, j + 1)]);
+    }
+    build(id, points, faces, bio)
+}
+
+/// Torus around z: tube of `minor_radius_nm` swept at `major_radius_nm`.
+pub fn torus(
+    id: &str,
+    major_radius_nm: f64,
+    minor_radius_nm: f64,
+    major_segments: usize,
+    minor_segments: usize,
+    bio: BiophysicalMetadata,
+) -> Result<Nanopolygon, Vec<GeometryError>> {
+    let (nu, nv) = (major_segments.max(3), minor_segments.max(3));
+    let mut points = Vec::with_capacity(nu * nv);
+    for i in 0..nu {
+        let (su, cu) = (2.0 * PI * i as f64 / nu as f64).sin_cos();
+        for j in 0..nv {
+            let (sv, cv) = (2.0 * PI * j as f64 / nv as f64).sin_cos();
+            let r = major_radius_nm + minor_radius_nm * cv;
+            points.push([r * cu, r * su, minor_radius_nm * sv]);
+        }
+    }
+
+    let at = |i: usize, j: usize| (i % nu) * nv + j % nv;
+    let mut faces = Vec::with_capacity(nu * nv);
+    for i in 0..nu {
+        for j in 0..nv {
+            faces.push(vec![at(i, j), at(i + 1, j), at(i + 1, j + 1), at(i, j + 1)]);
+        }
+    }
+    build(id, points, faces, bio)
+}
+
+/// Open rectangular tile in the xy plane, facing +z, split into a grid of quads.
+pub fn flat_tile(
+    id: &str,
+    width_nm: f64,
+    height_nm: f64,
+    columns: usize,
+    rows: usize,
+    bio: BiophysicalMetadata,
+) -> Result<Nanopolygon, Vec<GeometryError>> {
+    let (columns, rows) = (columns.max(1), rows.max(1));
+    let mut points = Vec::with_capacity((columns + 1) * (rows + 1));
+    for r in 0..=rows {
+        for c in 0..=columns {
+            points.push([
+                width_nm * (c as f64 / columns as f64 - 0.5),
+                height_nm * (r as f64 / rows as f64 - 0.5),
+                0.0,
+            ]);
+        }
+    }
+
+    let at = |c: usize, r: usize| r * (columns + 1) + c;
+    let mut faces = Vec::with_capacity(columns * rows);
+    for r in 0..rows {
+        for c in 0..columns {
+            faces.push(vec![at(c, r), at(c + 1, r), at(c + 1, r + 1), at(c, r + 1)]);
+        }
+    }
+    build(id, points, faces, bio)
+}
+
+/// Regular polygon tile in the xy plane, facing +z, as a single face.
+pub fn polygon_tile(
+    id: &str,
+    sides: usize,
+    circumradius_nm: f64,
+    bio: BiophysicalMetadata,
+) -> Result<Nanopolygon, Vec<GeometryError>> {
+    let sides = sides.max(3);
+    let points = ring(circumradius_nm, 0.0, sides);
+    build(id, points, vec![(0..sides).collect()], bio)
+}
+
+/// Faces of the convex hull of `points`, merged per plane and wound outward.
+/// Brute force over vertex triples; meant for small templates.
+pub fn convex_hull_faces(points: &[Vec3]) -> Vec<Vec<usize>> {
+    let n = points.len();
+    let centroid = geometry::scale(
+        points
+            .iter()
+            .fold([0.0; 3], |acc, &p| geometry::add(acc, p)),
+        1.0 / n.max(1) as f64,
+    );
+    let extent = points
+        .iter()
+        .map(|&p| geometry::norm(geometry::sub(p, centroid)))
+        .fold(0.0_f64, f64::max);
+    let tolerance = HULL_TOLERANCE * extent.max(f64::MIN_POSITIVE);
+
+    let mut seen: BTreeSet<Vec<usize>> = BTreeSet::new();
+    let mut faces = Vec::new();
+    for i in 0..n {
+        for j in i + 1..n {
+            for k in j + 1..n {
+                let normal = geometry::cross(
+                    geometry::sub(points[j], points[i]),
+                    geometry::sub(points[k], points[i]),
+                );
+                let len = geometry::norm(normal);
+                if len <= tolerance * extent {
+                    continue;
+                }
+                let mut normal = geometry::scale(normal, 1.0 / len);
+                if geometry::dot(normal, geometry::sub(points[i], centroid)) < 0.0 {
+                    normal = geometry::scale(normal, -1.0);
+                }
+
+                let offset = |p: Vec3| geometry::dot(normal, geometry::sub(p, points[i]));
+                if points.iter().any(|&p| offset(p) > tolerance) {
+                    continue;
+                }
+                let on_plane: Vec<usize> = (0..n)
+                    .filter(|&m| offset(points[m]).abs() <= tolerance)
+                    .collect();
+                if seen.insert(on_plane.clone()) {
+                    faces.push(wind_around(points, on_plane, normal));
+                }
+            }
+        }
+    }
+    faces
+}
+
+fn platonic_vertices(solid: PlatonicSolid) -> Vec<Vec3> {
+    let signs = [-1.0, 1.0];
+    let mut out = Vec::new();
+    match solid {
+        PlatonicSolid::Tetrahedron => {
+            out = vec![
+                [1.0, 1.0, 1.0],
+                [1.0, -1.0, -1.0],
+                [-1.0, 1.0, -1.0],
+                [-1.0, -1.0, 1.0],
+            ];
+        }
+        PlatonicSolid::Cube => {
+            for x in signs {
+                for y in signs {
+                    for z in signs {
+                        out.push([x, y, z]);
+                    }
+                }
+            }
+        }
+        PlatonicSolid::Octahedron => {
+            for s in signs {
+                out.extend([[s, 0.0, 0.0], [0.0, s, 0.0], [0.0, 0.0, s]]);
+            }
+        }
+        PlatonicSolid::Icosahedron => {
+            for a in signs {
+                for b in signs {
+                    out.extend([[0.0, a, b * PHI], [a, b * PHI, 0.0], [b * PHI, 0.0, a]]);
+                }
+            }
+        }
+        PlatonicSolid::Dodecahedron => {
+            for x in signs {
+                for y in signs {
+                    for z in signs {
+                        out.push([x, y, z]);
+                    }
+                }
+            }
+            for a in signs {
+                for b in signs {
+                    out.extend([
+                        [0.0, a / PHI, b * PHI],
+                        [a / PHI, b * PHI, 0.0],
+                        [b * PHI, 0.0, a / PHI],
+                    ]);
+                }
+            }
+        }
+    }
+    out
+}
+
+fn scaled_to_radius(points: Vec<Vec3>, radius_nm: f64) -> Vec<Vec3> {
+    points
+        .into_iter()
+        .map(|p| geometry::scale(p, radius_nm / geometry::norm(p)))
+        .collect()
+}
+
+fn midpoint_on_sphere(
+    points: &mut Vec<Vec3>,
+    cache: &mut HashMap<(usize, usize), usize>,
+    a: usize,
+    b: usize,
+) -> usize {
+    *cache.entry(geometry::edge_key(a, b)).or_insert_with(|| {
+        let m = geometry::add(points[a], points[b]);
+        points.push(geometry::scale(m, 1.0 / geometry::norm(m)));
+        points.len() - 1
+    })
+}
+
+// Counter-clockwise ring in the plane z = `z_nm`, starting on +x.
+fn ring(radius_nm: f64, z_nm: f64, segments: usize) -> Vec<Vec3> {
+    (0..segments)
+        .map(|j| {
+            let (s, c) = (2.0 * PI * j as f64 / segments as f64).sin_cos();
+            [radius_nm * c, radius_nm * s, z_nm]
+        })
+        .collect()
+}
+
+// Order coplanar hull vertices counter-clockwise about `normal`.
+fn wind_around(points: &[Vec3], mut ring: Vec<usize>, normal: Vec3) -> Vec<usize> {
+    let centre = geometry::scale(
+        ring.iter()
+            .fold([0.0; 3], |acc, &i| geometry::add(acc, points[i])),
+        1.0 / ring.len() as f64,
+    );
+    let u = geometry::sub(points[ring[0]], centre);
+    let v = geometry::cross(normal, u);
+    let angle = |i: usize| {
+        let d = geometry::sub(points[i], centre);
+        geometry::dot(d, v).atan2(geometry::dot(d, u))
+    };
+    ring.sort_by(|&a, &b| angle(a).total_cmp(&angle(b)));
+    ring
+}
+
+fn build(
+    id: &str,
+    points: Vec<Vec3>,
+    faces: Vec<Vec<usize>>,
+    bio: BiophysicalMetadata,
+) -> Result<Nanopolygon, Vec<GeometryError>> {
+    let vertices = points.into_iter().map(VertexNm::from_vec3).collect();
+    let faces = faces
+        .into_iter()
+        .map(|vertex_indices| Face { vertex_indices })
+        .collect();
+    Nanopolygon::try_from_faces(id, vertices, faces, bio)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xr_lab_grid::nanopoly::nanopolygon::BioAffinityTarget;
+    use crate::xr_lab_grid::nanopoly::topology;
+
+    fn bio() -> BiophysicalMetadata {
+        BiophysicalMetadata {
+            target: BioAffinityTarget::GlialCell,
+            zeta_potential_mv: -20.0,
+            hydrophobicity_index: 0.3,
+            elastic_modulus_kpa: 1.0,
+            ligands: Vec::new(),
+        }
+    }
+
+    fn close(actual: f64, expected: f64) -> bool {
+        (actual - expected).abs() <= 1e-9 * expected.abs().max(1.0)
+    }
+
+    #[test]
+    fn platonic_solids_match_closed_form() {
+        let r = 10.0;
+        let s3 = 3f64.sqrt();
+        let s5 = 5f64.sqrt();
+        // (solid, vertices, faces, edge length, area / a^2, volume / a^3)
+        let cases = [
+            (
+                PlatonicSolid::Tetrahedron,
+                4,
+                4,
+                r * 4.0 / 6f64.sqrt(),
+                s3,
+                1.0 / (6.0 * 2f64.sqrt()),
+            ),
+            (PlatonicSolid::Cube, 8, 6, 2.0 * r / s3, 6.0, 1.0),
+            (
+                PlatonicSolid::Octahedron,
+                6,
+                8,
+                r * 2f64.sqrt(),
+                2.0 * s3,
+                2f64.sqrt() / 3.0,
+            ),
+            (
+                PlatonicSolid::Dodecahedron,
+                20,
+                12,
+                4.0 * r / (s3 * (1.0 + s5)),
+                3.0 * (25.0 + 10.0 * s5).sqrt(),
+                (15.0 + 7.0 * s5) / 4.0,
+            ),
+            (
+                PlatonicSolid::Icosahedron,
+                12,
+                20,
+                4.0 * r / (10.0 + 2.0 * s5).sqrt(),
+                5.0 * s3,
+                5.0 * (3.0 + s5) / 12.0,
+            ),
+        ];
+        for (solid, vertices, faces, a, area, volume) in cases {
+            let poly = platonic("p", solid, r, bio()).unwrap();
+            assert_eq!(poly.vertices.len(), vertices, "{solid:?}");
+            assert_eq!(poly.faces.len(), faces, "{solid:?}");
+            assert!(poly.points().iter().all(|&p| close(geometry::norm(p), r)));
+            assert!(close(poly.surface_area_nm2, area * a * a), "{solid:?}");
+            assert!(
+                close(poly.volume_nm3().unwrap(), volume * a * a * a),
+                "{solid:?}"
+            );
+            assert_eq!(topology::genus(&poly.faces), Some(0));
+        }
+    }
+
+    #[test]
+    fn icosphere_counts_and_radius() {
+        for s in 0..3u32 {
+            let sphere = icosphere("s", 5.0, s, bio()).unwrap();
+            assert_eq!(sphere.faces.len(), 20 * 4usize.pow(s));
+            assert_eq!(sphere.vertices.len(), 10 * 4usize.pow(s) + 2);
+            assert!(sphere
+                .points()
+                .iter()
+                .all(|&p| close(geometry::norm(p), 5.0)));
+        }
+        // Area and volume converge on the sphere from below.
+        let fine = icosphere("s", 5.0, 4, bio()).unwrap();
+        let (area, volume) = (4.0 * PI * 25.0, 4.0 / 3.0 * PI * 125.0);
+        assert!(fine.surface_area_nm2 < area && fine.surface_area_nm2 > 0.99 * area);
+        let v = fine.volume_nm3().unwrap();
+        assert!(v < volume && v > 0.99 * volume);
+    }
+
+    #[test]
+    fn cylinder_is_a_regular_prism() {
+        let (r, len, n) = (3.0, 12.0, 16);
+        let cyl = cylinder("c", r, len, n, bio()).unwrap();
+        assert_eq!(cyl.faces.len(), n + 2);
+        let cap = 0.5 * n as f64 * r * r * (2.0 * PI / n as f64).sin();
+        let side = 2.0 * r * (PI / n as f64).sin();
+        assert!(close(
+            cyl.surface_area_nm2,
+            2.0 * cap + n as f64 * side * len
+        ));
+        assert!(close(cyl.volume_nm3().unwrap(), cap * len));
+    }
+
+    #[test]
+    fn rod_spans_its_length_plus_caps() {
+        let rod = capped_rod("r", 2.0, 20.0, 12, bio()).unwrap();
+        let z: Vec<f64> = rod.points().iter().map(|p| p[2]).collect();
+        let (lo, hi) = z
+            .iter()
+            .fold((f64::MAX, f64::MIN), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+        assert!(close(lo, -12.0) && close(hi, 12.0));
+        assert_eq!(topology::genus(&rod.faces), Some(0));
+    }
+
+    #[test]
+    fn torus_has_genus_one() {
+        let ring = torus("t", 10.0, 2.0, 12, 6, bio()).unwrap();
+        assert_eq!(ring.vertices.len(), 72);
+        assert_eq!(topology::euler_characteristic(&ring.faces), 0);
+        assert_eq!(topology::genus(&ring.faces), Some(1));
+    }
+
+    #[test]
+    fn tiles_are_flat_and_open() {
+        let tile = flat_tile("f", 8.0, 4.0, 4, 2, bio()).unwrap();
+        assert_eq!(tile.faces.len(), 8);
+        assert!(close(tile.surface_area_nm2, 32.0));
+        assert!(!tile.is_closed());
+        assert!(tile.volume_nm3().is_none());
+
+        let hexagon = polygon_tile("h", 6, 2.0, bio()).unwrap();
+        assert_eq!(hexagon.faces.len(), 1);
+        assert!(close(hexagon.surface_area_nm2, 1.5 * 3f64.sqrt() * 4.0));
+    }
+
+    #[test]
+    fn hull_merges_coplanar_points_and_skips_interior_ones() {
+        let mut points = platonic_vertices(PlatonicSolid::Cube);
+        points.push([0.0, 0.0, 0.0]);
+        points.push([1.0, 0.0, 0.0]);
+        let faces = convex_hull_faces(&points);
+        assert_eq!(faces.len(), 6);
+        // The face-centre point joins its face; the origin joins none.
+        assert_eq!(faces.iter().filter(|f| f.len() == 5).count(), 1);
+        assert!(faces.iter().all(|f| !f.contains(&8)));
+        for f in &faces {
+            let normal = geometry::cross(
+                geometry::sub(points[f[1]], points[f[0]]),
+                geometry::sub(points[f[2]], points[f[0]]),
+            );
+            assert!(geometry::dot(normal, points[f[0]]) > 0.0);
+        }
+    }
+}
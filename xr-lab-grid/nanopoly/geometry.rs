@@ -0,0 +1,433 @@
+use std::collections::{BTreeSet, HashMap};
+
+use super::nanopolygon::{Edge, VertexNm};
+
+/// Plain nm-space vector used by the mesh routines.
+pub type Vec3 = [f64; 3];
+
+// Triangles thinner than this (in nm^2) carry no usable angle information.
+const DEGENERATE_AREA_NM2: f64 = 1e-12;
+
+pub fn to_vec3(v: &VertexNm) -> Vec3 {
+    [v.x_nm, v.y_nm, v.z_nm]
+}
+
+pub fn sub(a: Vec3, b: Vec3) -> Vec3 {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+pub fn add(a: Vec3, b: Vec3) -> Vec3 {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+pub fn scale(a: Vec3, s: f64) -> Vec3 {
+    [a[0] * s, a[1] * s, a[2] * s]
+}
+
+pub fn dot(a: Vec3, b: Vec3) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+pub fn cross(a: Vec3, b: Vec3) -> Vec3 {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+pub fn norm(a: Vec3) -> f64 {
+    dot(a, a).sqrt()
+}
+
+pub fn triangle_area(a: Vec3, b: Vec3, c: Vec3) -> f64 {
+    0.5 * norm(cross(sub(b, a), sub(c, a)))
+}
+
+/// Closest point to `p` on triangle (a, b, c), by Voronoi region of the triangle.
+pub fn closest_point_on_triangle(p: Vec3, a: Vec3, b: Vec3, c: Vec3) -> Vec3 {
+    let ab = sub(b, a);
+    let ac = sub(c, a);
+    let ap = sub(p, a);
+    let d1 = dot(ab, ap);
+    let d2 = dot(ac, ap);
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return a;
+    }
+
+    let bp = sub(p, b);
+    let d3 = dot(ab, bp);
+    let d4 = dot(ac, bp);
+    if d3 >= 0.0 && d4 <= d3 {
+        return b;
+    }
+
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        return add(a, scale(ab, d1 / (d1 - d3)));
+    }
+
+    let cp = sub(p, c);
+    let d5 = dot(ab, cp);
+    let d6 = dot(ac, cp);
+    if d6 >= 0.0 && d5 <= d6 {
+        return c;
+    }
+
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        return add(a, scale(ac, d2 / (d2 - d6)));
+    }
+
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && (d4 - d3) >= 0.0 && (d5 - d6) >= 0.0 {
+        let w = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+        return add(b, scale(sub(c, b), w));
+    }
+
+    let denom = 1.0 / (va + vb + vc);
+    let v = vb * denom;
+    let w = vc * denom;
+    add(a, add(scale(ab, v), scale(ac, w)))
+}
+
+/// Distance in nm from `p` to the nearest point of any triangle; infinite when there are none.
+pub fn distance_to_mesh(p: Vec3, points: &[Vec3], triangles: &[[usize; 3]]) -> f64 {
+    triangles
+        .iter()
+        .map(|t| {
+            let q = closest_point_on_triangle(p, points[t[0]], points[t[1]], points[t[2]]);
+            norm(sub(p, q))
+        })
+        .fold(f64::INFINITY, f64::min)
+}
+
+/// Recover triangles from an edge graph as its 3-cycles.
+/// Out-of-range indices and self-loops are ignored.
+pub fn triangles_from_edges(vertex_count: usize, edges: &[Edge]) -> Vec<[usize; 3]> {
+    let adjacency = adjacency(vertex_count, edges);
+    let mut triangles = Vec::new();
+
+    for a in 0..vertex_count {
+        for &b in adjacency[a].range(a + 1..) {
+            for &c in adjacency[b].range(b + 1..) {
+                if adjacency[a].contains(&c) {
+                    triangles.push([a, b, c]);
+                }
+            }
+        }
+    }
+
+    triangles
+}
+
+/// Walk the edge graph as a single closed loop, if that is what it is.
+/// Every referenced vertex must have degree two and be reachable from the first.
+pub fn boundary_loop(vertex_count: usize, edges: &[Edge]) -> Option<Vec<usize>> {
+    let adjacency = adjacency(vertex_count, edges);
+    let used: Vec<usize> = (0..vertex_count)
+        .filter(|&i| !adjacency[i].is_empty())
+        .collect();
+
+    if used.len() < 3 || used.iter().any(|&i| adjacency[i].len() != 2) {
+        return None;
+    }
+
+    let start = used[0];
+    let mut ordered = vec![start];
+    let mut previous = start;
+    let mut current = *adjacency[start].iter().next()?;
+
+    while current != start {
+        ordered.push(current);
+        let next = *adjacency[current].iter().find(|&&n| n != previous)?;
+        previous = current;
+        current = next;
+    }
+
+    if ordered.len() == used.len() {
+        Some(ordered)
+    } else {
+        None
+    }
+}
+
+/// Sum of triangle areas in nm^2.
+pub fn surface_area_nm2(points: &[Vec3], triangles: &[[usize; 3]]) -> f64 {
+    triangles
+        .iter()
+        .map(|t| triangle_area(points[t[0]], points[t[1]], points[t[2]]))
+        .sum()
+}
+
+/// Per-vertex unsigned mean curvature |H| in 1/nm from the cotangent Laplacian.
+/// Boundary vertices (any incident edge not shared by exactly two
+/// triangles) have no well-defined value and are returned as `None`.
+pub fn vertex_mean_curvatures(points: &[Vec3], triangles: &[[usize; 3]]) -> Vec<Option<f64>> {
+    let n = points.len();
+    let mut laplacian = vec![[0.0_f64; 3]; n];
+    let mut vertex_area = vec![0.0_f64; n];
+    let mut edge_faces: HashMap<(usize, usize), usize> = HashMap::new();
+
+    for t in triangles {
+        let area = triangle_area(points[t[0]], points[t[1]], points[t[2]]);
+        for corner in 0..3 {
+            let i = t[corner];
+            let j = t[(corner + 1) % 3];
+            let k = t[(corner + 2) % 3];
+
+            *edge_faces.entry(edge_key(i, j)).or_insert(0) += 1;
+            vertex_area[i] += area / 3.0;
+
+            if area <= DEGENERATE_AREA_NM2 {
+                continue;
+            }
+
+            // Angle at k is opposite edge (i, j).
+            let u = sub(points[i], points[k]);
+            let v = sub(points[j], points[k]);
+            let cot = dot(u, v) / norm(cross(u, v));
+
+            let ij = sub(points[j], points[i]);
+            laplacian[i] = add(laplacian[i], scale(ij, cot));
+            laplacian[j] = add(laplacian[j], scale(ij, -cot));
+        }
+    }
+
+    let mut interior = vec![true; n];
+    let mut touched = vec![false; n];
+    for (&(a, b), &count) in &edge_faces {
+        touched[a] = true;
+        touched[b] = true;
+        if count != 2 {
+            interior[a] = false;
+            interior[b] = false;
+        }
+    }
+
+    (0..n)
+        .map(|i| {
+            if !touched[i] || !interior[i] || vertex_area[i] <= DEGENERATE_AREA_NM2 {
+                None
+            } else {
+                let delta = scale(laplacian[i], 1.0 / (2.0 * vertex_area[i]));
+                Some(0.5 * norm(delta))
+            }
+        })
+        .collect()
+}
+
+/// Area-weighted average of per-vertex |H| over interior vertices, in 1/nm.
+/// Flat or fully open meshes yield 0.0.
+pub fn mean_curvature_per_nm(points: &[Vec3], triangles: &[[usize; 3]]) -> f64 {
+    let per_vertex = vertex_mean_curvatures(points, triangles);
+
+    let mut vertex_area = vec![0.0_f64; points.len()];
+    for t in triangles {
+        let area = triangle_area(points[t[0]], points[t[1]], points[t[2]]);
+        for &i in t {
+            vertex_area[i] += area / 3.0;
+        }
+    }
+
+    let mut weighted = 0.0_f64;
+    let mut total_area = 0.0_f64;
+    for (i, h) in per_vertex.iter().enumerate() {
+        if let Some(h) = h {
+            weighted += h * vertex_area[i];
+            total_area += vertex_area[i];
+        }
+    }
+
+    if total_area > 0.0 {
+        weighted / total_area
+    } else {
+        0.0
+    }
+}
+
+pub fn edge_key(a: usize, b: usize) -> (usize, usize) {
+    if a < b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+fn adjacency(vertex_count: usize, edges: &[Edge]) -> Vec<BTreeSet<usize>> {
+    let mut adjacency = vec![BTreeSet::new(); vertex_count];
+    for e in edges {
+        let (a, b) = (e.start_index, e.end_index);
+        if a < vertex_count && b < vertex_count && a != b {
+            adjacency[a].insert(b);
+            adjacency[b].insert(a);
+        }
+    }
+    adjacency
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xr_lab_grid::nanopoly::generators;
+    use crate::xr_lab_grid::nanopoly::nanopolygon::{
+        BioAffinityTarget, BiophysicalMetadata, Nanopolygon,
+    };
+
+    // Regular octahedron of circumradius r: every face is equilateral, so the
+    // cotangent Laplacian gives |H| = 1/r exactly at each vertex.
+    fn octahedron(r: f64) -> (Vec<Vec3>, Vec<[usize; 3]>) {
+        let points = vec![
+            [r, 0.0, 0.0],
+            [-r, 0.0, 0.0],
+            [0.0, r, 0.0],
+            [0.0, -r, 0.0],
+            [0.0, 0.0, r],
+            [0.0, 0.0, -r],
+        ];
+        let triangles = vec![
+            [0, 2, 4],
+            [2, 1, 4],
+            [1, 3, 4],
+            [3, 0, 4],
+            [2, 0, 5],
+            [1, 2, 5],
+            [3, 1, 5],
+            [0, 3, 5],
+        ];
+        (points, triangles)
+    }
+
+    #[test]
+    fn octahedron_area_and_curvature() {
+        let (points, triangles) = octahedron(5.0);
+        let area = surface_area_nm2(&points, &triangles);
+        assert!((area - 8.0 * 3f64.sqrt() / 2.0 * 25.0).abs() < 1e-9);
+
+        for h in vertex_mean_curvatures(&points, &triangles) {
+            assert!((h.unwrap() - 0.2).abs() < 1e-12);
+        }
+        assert!((mean_curvature_per_nm(&points, &triangles) - 0.2).abs() < 1e-12);
+    }
+
+    #[test]
+    fn curvature_scales_inversely_with_size() {
+        let (small, triangles) = octahedron(1.0);
+        let (large, _) = octahedron(10.0);
+        let h_small = mean_curvature_per_nm(&small, &triangles);
+        let h_large = mean_curvature_per_nm(&large, &triangles);
+        assert!((h_small / h_large - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn flat_patch_has_zero_interior_curvature_and_open_boundary() {
+        // 3x3 grid in the z = 0 plane; only the centre vertex is interior.
+        let points: Vec<Vec3> = (0..9)
+            .map(|i| [(i % 3) as f64, (i / 3) as f64, 0.0])
+            .collect();
+        let mut triangles = Vec::new();
+        for y in 0..2 {
+            for x in 0..2 {
+                let a = y * 3 + x;
+                triangles.push([a, a + 1, a + 4]);
+                triangles.push([a, a + 4, a + 3]);
+            }
+        }
+        let per_vertex = vertex_mean_curvatures(&points, &triangles);
+        for (i, h) in per_vertex.iter().enumerate() {
+            if i == 4 {
+                assert!(h.unwrap().abs() < 1e-12);
+            } else {
+                assert!(h.is_none());
+            }
+        }
+        assert_eq!(mean_curvature_per_nm(&points, &triangles), 0.0);
+        assert!((surface_area_nm2(&points, &triangles) - 4.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn triangles_recovered_from_tetrahedron_edges() {
+        // The six edges of a tetrahedron, a self-loop and an out-of-range edge.
+        let tetrahedron = [(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)];
+        let edges = edges(&[&tetrahedron[..], &[(3, 3), (2, 9)]].concat());
+        let triangles = triangles_from_edges(4, &edges);
+        assert_eq!(triangles, vec![[0, 1, 2], [0, 1, 3], [0, 2, 3], [1, 2, 3]]);
+    }
+
+    fn edges(pairs: &[(usize, usize)]) -> Vec<Edge> {
+        pairs
+            .iter()
+            .map(|&(a, b)| Edge {
+                start_index: a,
+                end_index: b,
+            })
+            .collect()
+    }
+
+    fn bio() -> BiophysicalMetadata {
+        BiophysicalMetadata {
+            target: BioAffinityTarget::ExtracellularMatrix,
+            zeta_potential_mv: -5.0,
+            hydrophobicity_index: 0.5,
+            elastic_modulus_kpa: 10.0,
+            ligands: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn boundary_loop_walks_a_single_cycle_only() {
+        // Pentagon listed out of order and with mixed directions.
+        let pentagon = edges(&[(3, 4), (1, 0), (2, 3), (0, 4), (1, 2)]);
+        assert_eq!(boundary_loop(5, &pentagon), Some(vec![0, 1, 2, 3, 4]));
+
+        // Open chain, figure eight and two separate triangles.
+        assert_eq!(boundary_loop(4, &edges(&[(0, 1), (1, 2), (2, 3)])), None);
+        let bowtie = edges(&[(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 2)]);
+        assert_eq!(boundary_loop(5, &bowtie), None);
+        let pair = edges(&[(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3)]);
+        assert_eq!(boundary_loop(6, &pair), None);
+        assert_eq!(boundary_loop(2, &edges(&[(0, 1)])), None);
+    }
+
+    #[test]
+    fn edge_loop_is_read_as_a_flat_polygon() {
+        // Regular hexagon of circumradius 2 given by its rim only.
+        let vertices: Vec<VertexNm> = (0..6)
+            .map(|i| {
+                let angle = i as f64 * std::f64::consts::PI / 3.0;
+                VertexNm {
+                    x_nm: 2.0 * angle.cos(),
+                    y_nm: 2.0 * angle.sin(),
+                    z_nm: 1.0,
+                }
+            })
+            .collect();
+        let rim = edges(&[(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (5, 0)]);
+        let hexagon = Nanopolygon::new("hex", vertices.clone(), rim, bio());
+        assert!((hexagon.surface_area_nm2 - 6.0 * 3f64.sqrt()).abs() < 1e-9);
+        assert_eq!(hexagon.mean_curvature, 0.0);
+
+        // A bare wire has neither area nor curvature.
+        let wire = Nanopolygon::new("wire", vertices, edges(&[(0, 1), (1, 2)]), bio());
+        assert_eq!(wire.surface_area_nm2, 0.0);
+        assert_eq!(wire.mean_curvature, 0.0);
+    }
+
+    #[test]
+    fn sphere_curvature_matches_inverse_radius() {
+        // The icosahedron is vertex-transitive, so |H| is exact at s = 0 and
+        // stays within a fraction of a percent as subdivision breaks symmetry.
+        for s in 0..4 {
+            let sphere = generators::icosphere("s", 8.0, s, bio()).unwrap();
+            let h = mean_curvature_per_nm(&sphere.points(), &sphere.triangulate());
+            assert!((sphere.mean_curvature - h).abs() < 1e-12);
+            assert!((h * 8.0 - 1.0).abs() < 1e-3, "s = {}: {}", s, h);
+        }
+
+        // Area approaches 4 pi r^2 from below.
+        let fine = generators::icosphere("s", 8.0, 3, bio()).unwrap();
+        let sphere_area = 4.0 * std::f64::consts::PI * 64.0;
+        assert!(fine.surface_area_nm2 < sphere_area);
+        assert!(fine.surface_area_nm2 > 0.98 * sphere_area);
+    }
+}
@@ -0,0 +1,551 @@
+use std::io::{self, Write};
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use safety_core::types::{BioLoadFlag, SafetyState, SwarmMode};
+use serde_json::{json, Value};
+
+use super::geometry;
+use super::nanopolygon::{BiophysicalMetadata, Nanopolygon};
+use super::nanoswarm::Nanoswarm;
+use super::transform::Quaternion;
+
+// glTF component types, buffer targets and primitive modes.
+const FLOAT: u32 = 5126;
+const UNSIGNED_INT: u32 = 5125;
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+const MODE_LINES: u32 = 1;
+const MODE_TRIANGLES: u32 = 4;
+
+// Gap left between members when the source has no placement of its own.
+const LAYOUT_MARGIN_NM: f64 = 10.0;
+
+/// Vertex colour (RGBA) for a member's bio-load flag; unknown state is grey.
+pub fn bio_flag_color(flag: Option<&BioLoadFlag>) -> [f32; 4] {
+    match flag {
+        Some(BioLoadFlag::Normal) => [0.20, 0.75, 0.35, 1.0],
+        Some(BioLoadFlag::Caution) => [0.95, 0.70, 0.15, 1.0],
+        Some(BioLoadFlag::Violation) => [0.90, 0.15, 0.15, 1.0],
+        None => [0.60, 0.60, 0.60, 1.0],
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct GltfExportOptions {
+    /// Scale on the root node. Geometry is stored in nm; 1e-9 gives true metres,
+    /// larger values magnify the lab for a headset.
+    pub scene_scale: f64,
+}
+
+impl Default for GltfExportOptions {
+    fn default() -> Self {
+        Self { scene_scale: 1e-9 }
+    }
+}
+
+/// One renderable member: its mesh, where it sits and its safety snapshot.
+#[derive(Clone, Debug)]
+pub struct SceneMember<'a> {
+    pub name: String,
+    pub poly: &'a Nanopolygon,
+    pub translation_nm: [f64; 3],
+    pub rotation: Quaternion,
+    pub safety: Option<&'a SafetyState>,
+}
+
+/// A built glTF 2.0 document: JSON plus its single binary buffer.
+#[derive(Clone, Debug)]
+pub struct GltfDocument {
+    pub json: Value,
+    pub buffer: Vec<u8>,
+}
+
+impl GltfDocument {
+    /// Self-contained `.gltf` text with the buffer inlined as a data URI.
+    pub fn to_embedded_gltf(&self) -> String {
+        let mut json = self.json.clone();
+        if let Some(buffer) = json.pointer_mut("/buffers/0") {
+            buffer["uri"] = Value::String(format!(
+                "data:application/octet-stream;base64,{}",
+                BASE64.encode(&self.buffer)
+            ));
+        }
+        json.to_string()
+    }
+
+    /// Binary `.glb` container.
+    pub fn write_glb<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let mut json = self.json.to_string().into_bytes();
+        while !json.len().is_multiple_of(4) {
+            json.push(b' ');
+        }
+        let mut bin = self.buffer.clone();
+        while !bin.len().is_multiple_of(4) {
+            bin.push(0);
+        }
+
+        let total = 12 + 8 + json.len() + 8 + bin.len();
+        writer.write_all(b"glTF")?;
+        writer.write_all(&2u32.to_le_bytes())?;
+        writer.write_all(&(total as u32).to_le_bytes())?;
+
+        writer.write_all(&(json.len() as u32).to_le_bytes())?;
+        writer.write_all(b"JSON")?;
+        writer.write_all(&json)?;
+
+        writer.write_all(&(bin.len() as u32).to_le_bytes())?;
+        writer.write_all(b"BIN\0")?;
+        writer.write_all(&bin)
+    }
+}
+
+/// Export a swarm with one node per member, in member order, placed by member pose.
+/// `safety` is matched to members by index; missing entries render grey.
+pub fn export_swarm(
+    swarm: &Nanoswarm,
+    safety: &[SafetyState],
+    swarm_mode: &SwarmMode,
+    options: &GltfExportOptions,
+) -> GltfDocument {
+    let members = posed_members(swarm, safety);
+    build_scene(&swarm.id, &members, swarm_mode, options)
+}
+
+/// Assemble a scene from arbitrary members (e.g. XR grid cells).
+pub fn build_scene(
+    scene_name: &str,
+    members: &[SceneMember],
+    swarm_mode: &SwarmMode,
+    options: &GltfExportOptions,
+) -> GltfDocument {
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut buffer_views: Vec<Value> = Vec::new();
+    let mut accessors: Vec<Value> = Vec::new();
+    let mut meshes: Vec<Value> = Vec::new();
+    let mut nodes: Vec<Value> = vec![Value::Null]; // root filled in last
+
+    for member in members {
+        let mesh = member_mesh(
+            member,
+            &mut buffer,
+            &mut buffer_views,
+            &mut accessors,
+            &mut meshes,
+        );
+        let q = member.rotation;
+        let mut node = json!({
+            "name": member.name,
+            "translation": member.translation_nm,
+            "rotation": [q.x, q.y, q.z, q.w],
+            "extras": member_extras(member, swarm_mode),
+        });
+        if let Some(mesh) = mesh {
+            node["mesh"] = json!(mesh);
+        }
+        nodes.push(node);
+    }
+
+    let s = options.scene_scale;
+    nodes[0] = json!({
+        "name": scene_name,
+        "scale": [s, s, s],
+        "children": (1..nodes.len()).collect::<Vec<_>>(),
+        "extras": {
+            "swarm_id": scene_name,
+            "swarm_mode": format!("{:?}", swarm_mode),
+        },
+    });
+
+    let mut json = json!({
+        "asset": { "version": "2.0", "generator": "nanopoly xr-lab-grid" },
+        "scene": 0,
+        "scenes": [{ "nodes": [0] }],
+        "nodes": nodes,
+        // Vertex colours carry the state; the material only makes them unlit-friendly.
+        "materials": [{
+            "name": "bio_load",
+            "doubleSided": true,
+            "pbrMetallicRoughness": {
+                "baseColorFactor": [1, 1, 1, 1],
+                "metallicFactor": 0,
+                "roughnessFactor": 1,
+            },
+        }],
+    });
+    if !meshes.is_empty() {
+        json["meshes"] = Value::Array(meshes);
+        json["accessors"] = Value::Array(accessors);
+        json["bufferViews"] = Value::Array(buffer_views);
+        json["buffers"] = json!([{ "byteLength": buffer.len() }]);
+    }
+
+    GltfDocument { json, buffer }
+}
+
+/// Place members side by side along +x, spaced by their extent.
+/// Used when the source has no placement of its own.
+pub fn arrange_in_row(members: &mut [SceneMember]) {
+    let mut cursor = 0.0_f64;
+    for member in members.iter_mut() {
+        let (min, max) = extent(member.poly);
+        member.translation_nm = [cursor - min[0], 0.0, 0.0];
+        cursor += (max[0] - min[0]) + LAYOUT_MARGIN_NM;
+    }
+}
+
+fn posed_members<'a>(swarm: &'a Nanoswarm, safety: &'a [SafetyState]) -> Vec<SceneMember<'a>> {
+    swarm
+        .members
+        .iter()
+        .enumerate()
+        .map(|(i, m)| SceneMember {
+            name: format!("{}#{}", m.poly().id, i),
+            poly: m.poly(),
+            translation_nm: m.pose.translation_nm,
+            rotation: m.pose.rotation,
+            safety: safety.get(i),
+        })
+        .collect()
+}
+
+fn extent(poly: &Nanopolygon) -> (geometry::Vec3, geometry::Vec3) {
+    poly.aabb()
+        .map(|b| (b.min_nm, b.max_nm))
+        .unwrap_or(([0.0; 3], [0.0; 3]))
+}
+
+// Appends the member's buffers and mesh; returns the mesh index if it has geometry.
+fn member_mesh(
+    member: &SceneMember,
+    buffer: &mut Vec<u8>,
+    buffer_views: &mut Vec<Value>,
+    accessors: &mut Vec<Value>,
+    meshes: &mut Vec<Value>,
+) -> Option<usize> {
+    let poly = member.poly;
+    let points = poly.points();
+
+    let triangles = poly.triangulate();
+    let (indices, mode): (Vec<u32>, u32) = if !triangles.is_empty() {
+        (
+            triangles.iter().flatten().map(|&i| i as u32).collect(),
+            MODE_TRIANGLES,
+        )
+    } else {
+        (
+            poly.edges
+                .iter()
+                .filter(|e| e.start_index < points.len() && e.end_index < points.len())
+                .flat_map(|e| [e.start_index as u32, e.end_index as u32])
+                .collect(),
+            MODE_LINES,
+        )
+    };
+    if points.is_empty() || indices.is_empty() {
+        return None;
+    }
+
+    let (min, max) = extent(poly);
+    let color = bio_flag_color(member.safety.map(|s| &s.bio_flag));
+
+    let position_view = push_view(
+        buffer,
+        buffer_views,
+        points.iter().flatten().map(|&c| (c as f32).to_le_bytes()),
+        ARRAY_BUFFER,
+    );
+    // Bounds must match the stored f32 positions exactly.
+    accessors.push(json!({
+        "bufferView": position_view,
+        "componentType": FLOAT,
+        "count": points.len(),
+        "type": "VEC3",
+        "min": min.map(|c| c as f32),
+        "max": max.map(|c| c as f32),
+    }));
+    let position_accessor = accessors.len() - 1;
+
+    let color_view = push_view(
+        buffer,
+        buffer_views,
+        (0..points.len()).flat_map(|_| color.map(|c| c.to_le_bytes())),
+        ARRAY_BUFFER,
+    );
+    accessors.push(json!({
+        "bufferView": color_view,
+        "componentType": FLOAT,
+        "count": points.len(),
+        "type": "VEC4",
+    }));
+    let color_accessor = accessors.len() - 1;
+
+    let index_view = push_view(
+        buffer,
+        buffer_views,
+        indices.iter().map(|i| i.to_le_bytes()),
+        ELEMENT_ARRAY_BUFFER,
+    );
+    accessors.push(json!({
+        "bufferView": index_view,
+        "componentType": UNSIGNED_INT,
+        "count": indices.len(),
+        "type": "SCALAR",
+    }));
+    let index_accessor = accessors.len() - 1;
+
+    meshes.push(json!({
+        "name": poly.id,
+        "primitives": [{
+            "attributes": { "POSITION": position_accessor, "COLOR_0": color_accessor },
+            "indices": index_accessor,
+            "mode": mode,
+            "material": 0,
+        }],
+    }));
+    Some(meshes.len() - 1)
+}
+
+fn push_view(
+    buffer: &mut Vec<u8>,
+    buffer_views: &mut Vec<Value>,
+    words: impl Iterator<Item = [u8; 4]>,
+    target: u32,
+) -> usize {
+    let offset = buffer.len();
+    for w in words {
+        buffer.extend_from_slice(&w);
+    }
+    buffer_views.push(json!({
+        "buffer": 0,
+        "byteOffset": offset,
+        "byteLength": buffer.len() - offset,
+        "target": target,
+    }));
+    buffer_views.len() - 1
+}
+
+fn member_extras(member: &SceneMember, swarm_mode: &SwarmMode) -> Value {
+    let safety = match member.safety {
+        Some(s) => json!({
+            "k": short_f32(s.k),
+            "d": short_f32(s.d),
+            "dw": short_f32(s.dw),
+            "lifeforce": short_f32(s.lifeforce.0),
+            "roh": short_f32(s.roh.0),
+            "bio_flag": format!("{:?}", s.bio_flag),
+            "swarm_mode": format!("{:?}", s.swarm_mode),
+        }),
+        None => Value::Null,
+    };
+    json!({
+        "poly_id": member.poly.id,
+        "surface_area_nm2": member.poly.surface_area_nm2,
+        "mean_curvature": member.poly.mean_curvature,
+        "bio": bio_json(&member.poly.bio),
+        "safety": safety,
+        "swarm_mode": format!("{:?}", swarm_mode),
+    })
+}
+
+fn bio_json(bio: &BiophysicalMetadata) -> Value {
+    let ligands: Vec<Value> = bio
+        .ligands
+        .iter()
+        .map(|l| {
+            json!({
+                "chemistry": format!("{:?}", l.chemistry),
+                "density_per_nm2": short_f32(l.density_per_nm2),
+            })
+        })
+        .collect();
+    json!({
+        "target": format!("{:?}", bio.target),
+        "zeta_potential_mv": short_f32(bio.zeta_potential_mv),
+        "surface_charge": format!("{:?}", bio.surface_charge()),
+        "hydrophobicity_index": short_f32(bio.hydrophobicity_index),
+        "elastic_modulus_kpa": short_f32(bio.elastic_modulus_kpa),
+        "ligands": ligands,
+    })
+}
+
+// Shortest decimal of the f32, so 0.4 is written as 0.4 and not as
+// 0.4000000059604645. JSON has no NaN/Infinity; those become null.
+fn short_f32(v: f32) -> Value {
+    v.to_string()
+        .parse::<f64>()
+        .ok()
+        .and_then(serde_json::Number::from_f64)
+        .map_or(Value::Null, Value::Number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xr_lab_grid::nanopoly::generators::{self, PlatonicSolid};
+    use crate::xr_lab_grid::nanopoly::nanopolygon::{BioAffinityTarget, Edge, VertexNm};
+
+    fn bio() -> BiophysicalMetadata {
+        BiophysicalMetadata {
+            target: BioAffinityTarget::MuscleFiber,
+            zeta_potential_mv: -0.4,
+            hydrophobicity_index: 0.1,
+            elastic_modulus_kpa: 12.0,
+            ligands: Vec::new(),
+        }
+    }
+
+    // A closed cube, plus a bare three-segment wire that renders as lines.
+    fn scene() -> (Nanopolygon, Nanopolygon, SafetyState) {
+        let cube = generators::platonic("cube", PlatonicSolid::Cube, 5.0, bio()).unwrap();
+        let vertices = (0..4)
+            .map(|i| VertexNm {
+                x_nm: i as f64,
+                y_nm: (i % 2) as f64,
+                z_nm: 0.0,
+            })
+            .collect();
+        let edges = (0..3)
+            .map(|i| Edge {
+                start_index: i,
+                end_index: i + 1,
+            })
+            .collect();
+        let wire = Nanopolygon::new("wire", vertices, edges, bio());
+        let safety = SafetyState::new(
+            0.8,
+            0.3,
+            0.1,
+            0.9,
+            0.2,
+            BioLoadFlag::Caution,
+            SwarmMode::Normal,
+        );
+        (cube, wire, safety)
+    }
+
+    fn document(cube: &Nanopolygon, wire: &Nanopolygon, safety: &SafetyState) -> GltfDocument {
+        let mut members = vec![
+            SceneMember {
+                name: "cube \"a\"".to_string(),
+                poly: cube,
+                translation_nm: [0.0; 3],
+                rotation: Quaternion::identity(),
+                safety: Some(safety),
+            },
+            SceneMember {
+                name: "wire".to_string(),
+                poly: wire,
+                translation_nm: [0.0; 3],
+                rotation: Quaternion::identity(),
+                safety: None,
+            },
+        ];
+        arrange_in_row(&mut members);
+        build_scene(
+            "lab",
+            &members,
+            &SwarmMode::Normal,
+            &GltfExportOptions::default(),
+        )
+    }
+
+    fn u32_at(bytes: &[u8], at: usize) -> usize {
+        u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]]) as usize
+    }
+
+    #[test]
+    fn embedded_gltf_parses_and_carries_the_buffer() {
+        let (cube, wire, safety) = scene();
+        let doc = document(&cube, &wire, &safety);
+        let parsed: Value = serde_json::from_str(&doc.to_embedded_gltf()).unwrap();
+
+        assert_eq!(parsed["asset"]["version"], "2.0");
+        assert_eq!(parsed["nodes"][0]["children"], json!([1, 2]));
+        assert_eq!(parsed["nodes"][1]["name"], "cube \"a\"");
+        assert_eq!(
+            parsed["nodes"][1]["extras"]["safety"]["bio_flag"],
+            "Caution"
+        );
+        assert_eq!(
+            parsed["nodes"][1]["extras"]["bio"]["zeta_potential_mv"],
+            json!(-0.4)
+        );
+        assert_eq!(parsed["nodes"][2]["extras"]["safety"], Value::Null);
+
+        let primitive = |m: usize| &parsed["meshes"][m]["primitives"][0];
+        assert_eq!(primitive(0)["mode"], MODE_TRIANGLES);
+        assert_eq!(primitive(1)["mode"], MODE_LINES);
+        let index_accessor = primitive(1)["indices"].as_u64().unwrap() as usize;
+        assert_eq!(parsed["accessors"][index_accessor]["count"], 6);
+
+        let uri = parsed["buffers"][0]["uri"].as_str().unwrap();
+        let encoded = uri
+            .strip_prefix("data:application/octet-stream;base64,")
+            .unwrap();
+        assert_eq!(BASE64.decode(encoded).unwrap(), doc.buffer);
+        assert_eq!(parsed["buffers"][0]["byteLength"], doc.buffer.len());
+
+        // Every view is 4-byte aligned and the views tile the buffer.
+        let mut end = 0;
+        for view in parsed["bufferViews"].as_array().unwrap() {
+            let offset = view["byteOffset"].as_u64().unwrap() as usize;
+            assert_eq!(offset % 4, 0);
+            assert_eq!(offset, end);
+            end = offset + view["byteLength"].as_u64().unwrap() as usize;
+        }
+        assert_eq!(end, doc.buffer.len());
+
+        // The unembedded document has no uri, only the length.
+        assert!(doc.json["buffers"][0].get("uri").is_none());
+    }
+
+    #[test]
+    fn glb_header_and_chunks() {
+        let (cube, wire, safety) = scene();
+        let doc = document(&cube, &wire, &safety);
+        let mut glb = Vec::new();
+        doc.write_glb(&mut glb).unwrap();
+
+        assert_eq!(&glb[0..4], b"glTF");
+        assert_eq!(u32_at(&glb, 4), 2);
+        assert_eq!(u32_at(&glb, 8), glb.len());
+        assert_eq!(glb.len() % 4, 0);
+
+        let json_len = u32_at(&glb, 12);
+        assert_eq!(&glb[16..20], b"JSON");
+        assert_eq!(json_len % 4, 0);
+        let json: Value = serde_json::from_slice(&glb[20..20 + json_len]).unwrap();
+        assert_eq!(json, doc.json);
+
+        let bin = 20 + json_len;
+        let bin_len = u32_at(&glb, bin);
+        assert_eq!(&glb[bin + 4..bin + 8], b"BIN\0");
+        assert_eq!(bin_len % 4, 0);
+        assert_eq!(bin + 8 + bin_len, glb.len());
+        assert_eq!(
+            &glb[bin + 8..bin + 8 + doc.buffer.len()],
+            doc.buffer.as_slice()
+        );
+    }
+
+    #[test]
+    fn non_finite_values_become_null() {
+        let (cube, _, _) = scene();
+        let member = SceneMember {
+            name: "lost".to_string(),
+            poly: &cube,
+            translation_nm: [f64::NAN, 0.0, 0.0],
+            rotation: Quaternion::identity(),
+            safety: None,
+        };
+        let doc = build_scene(
+            "lab",
+            &[member],
+            &SwarmMode::Normal,
+            &GltfExportOptions::default(),
+        );
+        let parsed: Value = serde_json::from_str(&doc.json.to_string()).unwrap();
+        assert_eq!(parsed["nodes"][1]["translation"], json!([null, 0.0, 0.0]));
+    }
+}
@@ -0,0 +1,513 @@
+use std::collections::{HashMap, HashSet};
+use std::f64::consts::PI;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::geometry::{self, Vec3};
+use super::nanopolygon::Nanopolygon;
+use crate::store::metrics::ResponseMetric;
+
+// Adjacent triangles whose normals differ by less than this share a flat region.
+const COPLANAR_COS: f64 = 0.996_194_7; // cos(5 degrees)
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum HazardKind {
+    SharpVertex,
+    NeedleShape,
+    BarrierCrossingSize,
+    FlatHydrophobicFace,
+}
+
+impl fmt::Display for HazardKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            HazardKind::SharpVertex => "sharp vertex",
+            HazardKind::NeedleShape => "needle shape",
+            HazardKind::BarrierCrossingSize => "barrier-crossing size",
+            HazardKind::FlatHydrophobicFace => "flat hydrophobic face",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum HazardSeverity {
+    Low,
+    Moderate,
+    High,
+}
+
+impl HazardSeverity {
+    /// Added to DW for each flag of this severity.
+    pub fn dw_penalty(&self) -> f32 {
+        match self {
+            HazardSeverity::Low => 0.02,
+            HazardSeverity::Moderate => 0.08,
+            HazardSeverity::High => 0.20,
+        }
+    }
+
+    /// Taken off K for each flag of this severity.
+    pub fn k_penalty(&self) -> f32 {
+        match self {
+            HazardSeverity::Low => 0.01,
+            HazardSeverity::Moderate => 0.04,
+            HazardSeverity::High => 0.10,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HazardFlag {
+    pub kind: HazardKind,
+    pub severity: HazardSeverity,
+    /// The measured quantity that tripped the flag.
+    pub value: f64,
+    pub detail: String,
+}
+
+/// Severity cut-offs as [low, moderate, high]. Sharpness and aspect flag at
+/// or above a cut-off; size flags at or below one.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HazardThresholds {
+    /// Angle deficit at a vertex, in radians (cube corner pi/2, tetrahedron pi).
+    pub vertex_angle_deficit_rad: [f64; 3],
+    /// Longest extent over cross-section.
+    pub aspect_ratio: [f64; 3],
+    /// Smallest pore diameter the particle fits through, in nm.
+    pub cross_section_nm: [f64; 3],
+    /// Effective hydrophobicity at which flat regions start to count.
+    pub hydrophobic_index: f32,
+    /// Area of the largest flat region, in nm^2.
+    pub flat_area_nm2: [f64; 3],
+}
+
+impl Default for HazardThresholds {
+    /// Size cut-offs follow the ~50 nm endocytic optimum, ~20 nm for
+    /// blood-brain barrier passage and ~10 nm for nuclear pores and tight
+    /// junctions. Aspect cut-offs follow the 3:1 fibre paradigm.
+    fn default() -> Self {
+        Self {
+            vertex_angle_deficit_rad: [PI / 2.0, 2.0 * PI / 3.0, 5.0 * PI / 6.0],
+            aspect_ratio: [3.0, 5.0, 10.0],
+            cross_section_nm: [50.0, 20.0, 10.0],
+            hydrophobic_index: 0.6,
+            flat_area_nm2: [100.0, 400.0, 2500.0],
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HazardReport {
+    pub poly_id: String,
+    pub flags: Vec<HazardFlag>,
+}
+
+impl HazardReport {
+    pub fn evaluate(poly: &Nanopolygon) -> Self {
+        Self::with_thresholds(poly, &HazardThresholds::default())
+    }
+
+    pub fn with_thresholds(poly: &Nanopolygon, thresholds: &HazardThresholds) -> Self {
+        let points = poly.points();
+        let triangles = poly.triangulate();
+        let mut flags = Vec::new();
+
+        if let Some((vertex, deficit, count)) =
+            sharpest_vertex(&points, &triangles, thresholds.vertex_angle_deficit_rad[0])
+        {
+            if let Some(severity) = rising(deficit, &thresholds.vertex_angle_deficit_rad) {
+                flags.push(HazardFlag {
+                    kind: HazardKind::SharpVertex,
+                    severity,
+                    value: deficit,
+                    detail: format!(
+                        "{} vertex(es) with angle deficit over {:.2} rad; sharpest is vertex {} at {:.2} rad",
+                        count, thresholds.vertex_angle_deficit_rad[0], vertex, deficit
+                    ),
+                });
+            }
+        }
+
+        if let Some(extents) = sorted_extents_nm(poly) {
+            // Length over cross-section, so flat sheets are not needles.
+            let aspect = if extents[1] > 0.0 {
+                extents[0] / extents[1]
+            } else {
+                f64::INFINITY
+            };
+            if let Some(severity) = rising(aspect, &thresholds.aspect_ratio) {
+                flags.push(HazardFlag {
+                    kind: HazardKind::NeedleShape,
+                    severity,
+                    value: aspect,
+                    detail: format!("aspect ratio {:.1}:1", aspect),
+                });
+            }
+
+            // A particle passes a round pore about as wide as its
+            // second-largest extent, entering along its longest axis.
+            let cross_section = extents[1];
+            if let Some(severity) = falling(cross_section, &thresholds.cross_section_nm) {
+                flags.push(HazardFlag {
+                    kind: HazardKind::BarrierCrossingSize,
+                    severity,
+                    value: cross_section,
+                    detail: format!("fits through a {:.1} nm pore", cross_section),
+                });
+            }
+        }
+
+        let hydrophobicity = poly.bio.effective_hydrophobicity();
+        if hydrophobicity >= thresholds.hydrophobic_index {
+            let area = largest_flat_region_nm2(&points, &triangles);
+            if let Some(severity) = rising(area, &thresholds.flat_area_nm2) {
+                flags.push(HazardFlag {
+                    kind: HazardKind::FlatHydrophobicFace,
+                    severity,
+                    value: area,
+                    detail: format!(
+                        "{:.0} nm^2 flat region at hydrophobicity {:.2}",
+                        area, hydrophobicity
+                    ),
+                });
+            }
+        }
+
+        Self {
+            poly_id: poly.id.clone(),
+            flags,
+        }
+    }
+
+    pub fn is_clear(&self) -> bool {
+        self.flags.is_empty()
+    }
+
+    pub fn max_severity(&self) -> Option<HazardSeverity> {
+        self.flags.iter().map(|f| f.severity).max()
+    }
+
+    pub fn dw_penalty(&self) -> f32 {
+        self.flags
+            .iter()
+            .fold(0.0, |acc, f| acc + f.severity.dw_penalty())
+    }
+
+    pub fn k_penalty(&self) -> f32 {
+        self.flags
+            .iter()
+            .fold(0.0, |acc, f| acc + f.severity.k_penalty())
+    }
+
+    /// `metric` with DW raised and K lowered by every flag, and the flags
+    /// appended to its notes.
+    pub fn apply(&self, metric: &ResponseMetric) -> ResponseMetric {
+        if self.is_clear() {
+            return metric.clone();
+        }
+        let notes = format!("{} {}", metric.notes, self.notes());
+        ResponseMetric::new(
+            metric.knowledge_factor_k - self.k_penalty(),
+            metric.demand_d,
+            metric.dracula_wave_dw + self.dw_penalty(),
+            &notes,
+        )
+    }
+
+    /// One sentence listing every flag; empty when clear.
+    pub fn notes(&self) -> String {
+        if self.is_clear() {
+            return String::new();
+        }
+        let flags: Vec<String> = self
+            .flags
+            .iter()
+            .map(|f| format!("{:?} {} ({})", f.severity, f.kind, f.detail))
+            .collect();
+        format!("Geometry hazards: {}.", flags.join("; "))
+    }
+}
+
+impl Nanopolygon {
+    pub fn hazard_report(&self) -> HazardReport {
+        HazardReport::evaluate(self)
+    }
+}
+
+// Highest severity whose cut-off `value` reaches.
+fn rising(value: f64, cutoffs: &[f64; 3]) -> Option<HazardSeverity> {
+    severity_at(|c| value >= c, cutoffs)
+}
+
+// Highest severity whose cut-off `value` falls to.
+fn falling(value: f64, cutoffs: &[f64; 3]) -> Option<HazardSeverity> {
+    severity_at(|c| value <= c, cutoffs)
+}
+
+fn severity_at(reached: impl Fn(f64) -> bool, cutoffs: &[f64; 3]) -> Option<HazardSeverity> {
+    [
+        HazardSeverity::High,
+        HazardSeverity::Moderate,
+        HazardSeverity::Low,
+    ]
+    .into_iter()
+    .zip(cutoffs.iter().rev())
+    .find(|(_, &c)| reached(c))
+    .map(|(s, _)| s)
+}
+
+/// Sharpest interior vertex by angle deficit (2 pi minus the corner angles
+/// around it), and how many interior vertices reach `min_deficit`.
+/// Boundary vertices of open surfaces are skipped.
+fn sharpest_vertex(
+    points: &[Vec3],
+    triangles: &[[usize; 3]],
+    min_deficit: f64,
+) -> Option<(usize, f64, usize)> {
+    let mut edge_uses: HashMap<(usize, usize), usize> = HashMap::new();
+    for t in triangles {
+        for k in 0..3 {
+            *edge_uses
+                .entry(geometry::edge_key(t[k], t[(k + 1) % 3]))
+                .or_default() += 1;
+        }
+    }
+    let boundary: HashSet<usize> = edge_uses
+        .iter()
+        .filter(|(_, &n)| n == 1)
+        .flat_map(|(&(a, b), _)| [a, b])
+        .collect();
+
+    let mut angle_sum = vec![0.0_f64; points.len()];
+    let mut touched = vec![false; points.len()];
+    for t in triangles {
+        for k in 0..3 {
+            let (v, a, b) = (t[k], t[(k + 1) % 3], t[(k + 2) % 3]);
+            let (u, w) = (
+                geometry::sub(points[a], points[v]),
+                geometry::sub(points[b], points[v]),
+            );
+            let (nu, nw) = (geometry::norm(u), geometry::norm(w));
+            if nu <= 0.0 || nw <= 0.0 {
+                continue;
+            }
+            angle_sum[v] += (geometry::dot(u, w) / (nu * nw)).clamp(-1.0, 1.0).acos();
+            touched[v] = true;
+        }
+    }
+
+    let mut sharpest: Option<(usize, f64)> = None;
+    let mut count = 0;
+    for (v, sum) in angle_sum.iter().enumerate() {
+        if !touched[v] || boundary.contains(&v) {
+            continue;
+        }
+        let deficit = 2.0 * PI - sum;
+        if deficit >= min_deficit {
+            count += 1;
+        }
+        if sharpest.is_none_or(|(_, d)| deficit > d) {
+            sharpest = Some((v, deficit));
+        }
+    }
+    sharpest.map(|(v, d)| (v, d, count))
+}
+
+// Oriented-box extents, largest first.
+fn sorted_extents_nm(poly: &Nanopolygon) -> Option<[f64; 3]> {
+    let mut extents = poly.obb()?.half_extents_nm.map(|h| 2.0 * h);
+    extents.sort_by(|a, b| b.total_cmp(a));
+    Some(extents)
+}
+
+/// Area of the largest edge-connected set of near-coplanar triangles.
+fn largest_flat_region_nm2(points: &[Vec3], triangles: &[[usize; 3]]) -> f64 {
+    let normals: Vec<Option<Vec3>> = triangles
+        .iter()
+        .map(|t| {
+            let n = geometry::cross(
+                geometry::sub(points[t[1]], points[t[0]]),
+                geometry::sub(points[t[2]], points[t[0]]),
+            );
+            let len = geometry::norm(n);
+            (len > 0.0).then(|| geometry::scale(n, 1.0 / len))
+        })
+        .collect();
+
+    let mut by_edge: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+    for (i, t) in triangles.iter().enumerate() {
+        for k in 0..3 {
+            by_edge
+                .entry(geometry::edge_key(t[k], t[(k + 1) % 3]))
+                .or_default()
+                .push(i);
+        }
+    }
+
+    let mut parent: Vec<usize> = (0..triangles.len()).collect();
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+    for shared in by_edge.values() {
+        for pair in shared.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            // Winding may differ between faces, so either orientation counts.
+            let flat = match (normals[a], normals[b]) {
+                (Some(na), Some(nb)) => geometry::dot(na, nb).abs() >= COPLANAR_COS,
+                _ => false,
+            };
+            if flat {
+                let (ra, rb) = (root(&mut parent, a), root(&mut parent, b));
+                parent[ra] = rb;
+            }
+        }
+    }
+
+    let mut area: HashMap<usize, f64> = HashMap::new();
+    for (i, t) in triangles.iter().enumerate() {
+        let r = root(&mut parent, i);
+        *area.entry(r).or_default() +=
+            geometry::triangle_area(points[t[0]], points[t[1]], points[t[2]]);
+    }
+    area.values().copied().fold(0.0, f64::max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xr_lab_grid::nanopoly::generators::{self, PlatonicSolid};
+    use crate::xr_lab_grid::nanopoly::nanopolygon::{BioAffinityTarget, BiophysicalMetadata};
+
+    fn bio(hydrophobicity: f32) -> BiophysicalMetadata {
+        BiophysicalMetadata {
+            target: BioAffinityTarget::EndothelialCell,
+            zeta_potential_mv: -20.0,
+            hydrophobicity_index: hydrophobicity,
+            elastic_modulus_kpa: 5.0,
+            ligands: Vec::new(),
+        }
+    }
+
+    fn kinds(report: &HazardReport) -> Vec<(HazardKind, HazardSeverity)> {
+        report.flags.iter().map(|f| (f.kind, f.severity)).collect()
+    }
+
+    #[test]
+    fn cut_offs_are_inclusive() {
+        let up = [1.0, 2.0, 3.0];
+        assert_eq!(rising(0.99, &up), None);
+        assert_eq!(rising(1.0, &up), Some(HazardSeverity::Low));
+        assert_eq!(rising(2.5, &up), Some(HazardSeverity::Moderate));
+        assert_eq!(rising(f64::INFINITY, &up), Some(HazardSeverity::High));
+
+        let down = [50.0, 20.0, 10.0];
+        assert_eq!(falling(50.1, &down), None);
+        assert_eq!(falling(50.0, &down), Some(HazardSeverity::Low));
+        assert_eq!(falling(20.0, &down), Some(HazardSeverity::Moderate));
+        assert_eq!(falling(3.0, &down), Some(HazardSeverity::High));
+    }
+
+    #[test]
+    fn tetrahedron_corners_are_sharp_and_icosahedron_corners_are_not() {
+        // Deficit 2 pi - 3 (pi / 3) = pi at every tetrahedron corner.
+        let tetra = generators::platonic("t", PlatonicSolid::Tetrahedron, 100.0, bio(0.3)).unwrap();
+        let report = tetra.hazard_report();
+        assert_eq!(
+            kinds(&report),
+            vec![(HazardKind::SharpVertex, HazardSeverity::High)]
+        );
+        assert!((report.flags[0].value - PI).abs() < 1e-9);
+        assert!(report.flags[0].detail.starts_with("4 vertex(es)"));
+
+        // Deficit pi / 3, under the pi / 2 onset.
+        let icosa = generators::platonic("i", PlatonicSolid::Icosahedron, 100.0, bio(0.3)).unwrap();
+        assert!(icosa.hazard_report().is_clear());
+    }
+
+    #[test]
+    fn size_bands_follow_the_cross_section() {
+        // A sphere's cross-section is its diameter.
+        let cases = [
+            (100.0, None),
+            (20.0, Some(HazardSeverity::Low)),
+            (8.0, Some(HazardSeverity::Moderate)),
+            (4.0, Some(HazardSeverity::High)),
+        ];
+        for (radius, expected) in cases {
+            let sphere = generators::icosphere("s", radius, 2, bio(0.3)).unwrap();
+            let report = sphere.hazard_report();
+            let size = report
+                .flags
+                .iter()
+                .find(|f| f.kind == HazardKind::BarrierCrossingSize)
+                .map(|f| f.severity);
+            assert_eq!(size, expected, "radius {radius}");
+        }
+    }
+
+    #[test]
+    fn thin_rod_is_a_needle() {
+        let rod = generators::cylinder("r", 2.0, 60.0, 16, bio(0.3)).unwrap();
+        let report = rod.hazard_report();
+        let needle = report
+            .flags
+            .iter()
+            .find(|f| f.kind == HazardKind::NeedleShape)
+            .unwrap();
+        assert_eq!(needle.severity, HazardSeverity::High);
+        assert!(needle.value > 10.0 && needle.value <= 15.0);
+        assert_eq!(report.max_severity(), Some(HazardSeverity::High));
+    }
+
+    #[test]
+    fn flat_faces_count_only_when_hydrophobic() {
+        // One 900 nm^2 flat region, and a 30 nm cross-section.
+        let greasy = generators::flat_tile("g", 30.0, 30.0, 3, 3, bio(0.8)).unwrap();
+        let report = greasy.hazard_report();
+        assert_eq!(
+            kinds(&report),
+            vec![
+                (HazardKind::BarrierCrossingSize, HazardSeverity::Low),
+                (HazardKind::FlatHydrophobicFace, HazardSeverity::Moderate),
+            ]
+        );
+        let flat = &report.flags[1];
+        assert!((flat.value - 900.0).abs() < 1e-9);
+
+        let wetting = generators::flat_tile("w", 30.0, 30.0, 3, 3, bio(0.5)).unwrap();
+        assert_eq!(
+            kinds(&wetting.hazard_report()),
+            vec![(HazardKind::BarrierCrossingSize, HazardSeverity::Low)]
+        );
+    }
+
+    #[test]
+    fn flags_adjust_the_response_metric() {
+        let greasy = generators::flat_tile("g", 30.0, 30.0, 3, 3, bio(0.8)).unwrap();
+        let report = greasy.hazard_report();
+        assert!((report.dw_penalty() - 0.10).abs() < 1e-6);
+        assert!((report.k_penalty() - 0.05).abs() < 1e-6);
+
+        let metric = ResponseMetric::new(0.9, 0.3, 0.2, "base.");
+        let adjusted = report.apply(&metric);
+        assert!((adjusted.knowledge_factor_k - 0.85).abs() < 1e-6);
+        assert!((adjusted.dracula_wave_dw - 0.30).abs() < 1e-6);
+        assert_eq!(adjusted.demand_d, metric.demand_d);
+        assert!(adjusted
+            .notes
+            .starts_with("base. Geometry hazards: Low barrier-crossing size"));
+
+        let clear = HazardReport {
+            poly_id: "c".to_string(),
+            flags: Vec::new(),
+        };
+        assert_eq!(clear.apply(&metric).notes, "base.");
+        assert_eq!(clear.notes(), "");
+    }
+}
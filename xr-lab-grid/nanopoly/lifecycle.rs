@@ -0,0 +1,472 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use safety_core::types::{BioLoadFlag, SafetyState};
+
+use super::nanoswarm::{Nanoswarm, NanoswarmMember};
+use super::validation::GeometryError;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MemberState {
+    Active,
+    /// Held at basal draw with its interface cut, pending review.
+    Quarantined,
+    /// Inert and no longer counted; kept until removed.
+    Retired,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MemberLifecycle {
+    pub state: MemberState,
+    pub admitted_at_unix: i64,
+    pub state_since_unix: i64,
+    /// Why the member entered its current state, if not on admission.
+    pub reason: Option<String>,
+}
+
+impl MemberLifecycle {
+    pub fn active(admitted_at_unix: i64) -> Self {
+        Self {
+            state: MemberState::Active,
+            admitted_at_unix,
+            state_since_unix: admitted_at_unix,
+            reason: None,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.state == MemberState::Active
+    }
+}
+
+impl Default for MemberLifecycle {
+    fn default() -> Self {
+        Self::active(0)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LifecycleEventKind {
+    Admitted,
+    Quarantined,
+    Reinstated,
+    Retired,
+    Removed,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LifecycleEvent {
+    pub member_id: String,
+    pub kind: LifecycleEventKind,
+    pub at_unix: i64,
+    /// State before the transition; None on admission.
+    pub from: Option<MemberState>,
+    pub reason: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LifecycleError {
+    UnknownMember {
+        id: String,
+    },
+    DuplicateMember {
+        id: String,
+    },
+    InvalidGeometry {
+        id: String,
+        errors: Vec<GeometryError>,
+    },
+    InvalidTransition {
+        id: String,
+        from: MemberState,
+        event: LifecycleEventKind,
+    },
+}
+
+impl fmt::Display for LifecycleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LifecycleError::UnknownMember { id } => write!(f, "no member '{}' in the swarm", id),
+            LifecycleError::DuplicateMember { id } => {
+                write!(f, "member '{}' is already in the swarm", id)
+            }
+            LifecycleError::InvalidGeometry { id, errors } => {
+                write!(f, "member '{}' has {} geometry defect(s)", id, errors.len())?;
+                if let Some(first) = errors.first() {
+                    write!(f, ", first: {}", first)?;
+                }
+                Ok(())
+            }
+            LifecycleError::InvalidTransition { id, from, event } => {
+                write!(f, "member '{}' cannot be {:?} while {:?}", id, event, from)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LifecycleError {}
+
+impl Nanoswarm {
+    /// Add `member` as active from `now_unix`, after checking its geometry.
+    pub fn admit_member(
+        &mut self,
+        mut member: NanoswarmMember,
+        now_unix: i64,
+    ) -> Result<(), LifecycleError> {
+        member.lifecycle = MemberLifecycle::active(now_unix);
+        let member_id = member.object.id.clone();
+        self.add_member(member)?;
+        self.events.push(LifecycleEvent {
+            member_id,
+            kind: LifecycleEventKind::Admitted,
+            at_unix: now_unix,
+            from: None,
+            reason: None,
+        });
+        Ok(())
+    }
+
+    pub fn member_index(&self, member_id: &str) -> Option<usize> {
+        self.members.iter().position(|m| m.object.id == member_id)
+    }
+
+    pub fn members_in_state(&self, state: MemberState) -> Vec<usize> {
+        (0..self.members.len())
+            .filter(|&i| self.members[i].lifecycle.state == state)
+            .collect()
+    }
+
+    pub fn quarantine_member(
+        &mut self,
+        member_id: &str,
+        now_unix: i64,
+        reason: &str,
+    ) -> Result<(), LifecycleError> {
+        self.transition(
+            member_id,
+            now_unix,
+            reason,
+            LifecycleEventKind::Quarantined,
+            &[MemberState::Active],
+            MemberState::Quarantined,
+        )
+    }
+
+    pub fn reinstate_member(
+        &mut self,
+        member_id: &str,
+        now_unix: i64,
+        reason: &str,
+    ) -> Result<(), LifecycleError> {
+        self.transition(
+            member_id,
+            now_unix,
+            reason,
+            LifecycleEventKind::Reinstated,
+            &[MemberState::Quarantined],
+            MemberState::Active,
+        )
+    }
+
+    pub fn retire_member(
+        &mut self,
+        member_id: &str,
+        now_unix: i64,
+        reason: &str,
+    ) -> Result<(), LifecycleError> {
+        self.transition(
+            member_id,
+            now_unix,
+            reason,
+            LifecycleEventKind::Retired,
+            &[MemberState::Active, MemberState::Quarantined],
+            MemberState::Retired,
+        )
+    }
+
+    /// Take the member out of the swarm; later members shift down one index.
+    /// Removing the Rollback source leaves `comms.rollback_source` naming a
+    /// member that is gone, so Rollback reaches no one until a new source is set.
+    pub fn remove_member(
+        &mut self,
+        member_id: &str,
+        now_unix: i64,
+        reason: &str,
+    ) -> Result<NanoswarmMember, LifecycleError> {
+        let index = self.index_or_err(member_id)?;
+        let member = self.members.remove(index);
+        self.events.push(LifecycleEvent {
+            member_id: member_id.to_string(),
+            kind: LifecycleEventKind::Removed,
+            at_unix: now_unix,
+            from: Some(member.lifecycle.state),
+            reason: Some(reason.to_string()),
+        });
+        Ok(member)
+    }
+
+    /// Quarantine each active member whose safety state carries a Violation
+    /// flag, so one bad member need not roll back the whole swarm. States are
+    /// keyed by member id; ids not in the swarm are skipped. Returns the ids
+    /// quarantined.
+    pub fn quarantine_violations(
+        &mut self,
+        states: &[(String, SafetyState)],
+        now_unix: i64,
+    ) -> Vec<String> {
+        let flagged: Vec<String> = states
+            .iter()
+            .filter(|(_, s)| s.bio_flag == BioLoadFlag::Violation)
+            .filter(|(id, _)| {
+                self.member_index(id)
+                    .is_some_and(|i| self.members[i].lifecycle.is_active())
+            })
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in &flagged {
+            // Active was checked above, so the transition cannot fail.
+            let _ = self.quarantine_member(id, now_unix, "bio-load violation");
+        }
+        flagged
+    }
+
+    /// Events at or after `since_unix`, oldest first.
+    pub fn events_since(&self, since_unix: i64) -> impl Iterator<Item = &LifecycleEvent> {
+        self.events.iter().filter(move |e| e.at_unix >= since_unix)
+    }
+
+    fn index_or_err(&self, member_id: &str) -> Result<usize, LifecycleError> {
+        self.member_index(member_id)
+            .ok_or_else(|| LifecycleError::UnknownMember {
+                id: member_id.to_string(),
+            })
+    }
+
+    fn transition(
+        &mut self,
+        member_id: &str,
+        now_unix: i64,
+        reason: &str,
+        kind: LifecycleEventKind,
+        allowed_from: &[MemberState],
+        to: MemberState,
+    ) -> Result<(), LifecycleError> {
+        let index = self.index_or_err(member_id)?;
+        let lifecycle = &mut self.members[index].lifecycle;
+        let from = lifecycle.state;
+        if !allowed_from.contains(&from) {
+            return Err(LifecycleError::InvalidTransition {
+                id: member_id.to_string(),
+                from,
+                event: kind,
+            });
+        }
+        lifecycle.state = to;
+        lifecycle.state_since_unix = now_unix;
+        lifecycle.reason = Some(reason.to_string());
+        self.events.push(LifecycleEvent {
+            member_id: member_id.to_string(),
+            kind,
+            at_unix: now_unix,
+            from: Some(from),
+            reason: Some(reason.to_string()),
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xr_lab_grid::nanopoly::core::governance::GovernanceLayer;
+    use crate::xr_lab_grid::nanopoly::core::nanopoly_object::{
+        BciInterface, EnergeticProfile, NanopolyObject,
+    };
+    use crate::xr_lab_grid::nanopoly::generators;
+    use crate::xr_lab_grid::nanopoly::nanopolygon::{BioAffinityTarget, BiophysicalMetadata};
+    use crate::xr_lab_grid::nanopoly::transform::{Quaternion, RigidTransform};
+    use safety_core::types::SwarmMode;
+
+    fn member(id: &str, x: f64) -> NanoswarmMember {
+        let bio = BiophysicalMetadata {
+            target: BioAffinityTarget::NeuralMembrane,
+            zeta_potential_mv: -30.0,
+            hydrophobicity_index: 0.25,
+            elastic_modulus_kpa: 0.5,
+            ligands: Vec::new(),
+        };
+        let object = NanopolyObject::from_parts(
+            id,
+            generators::icosphere(id, 10.0, 1, bio).unwrap(),
+            EnergeticProfile::constant(100.0),
+            BciInterface::none(),
+            GovernanceLayer::human("did:example:owner"),
+        );
+        NanoswarmMember::new(
+            object,
+            RigidTransform::new(Quaternion::identity(), [x, 0.0, 0.0]),
+        )
+    }
+
+    fn swarm(ids: &[&str]) -> Nanoswarm {
+        let mut swarm = Nanoswarm::new("did:example:owner");
+        for (i, id) in ids.iter().enumerate() {
+            swarm.admit_member(member(id, 120.0 * i as f64), 0).unwrap();
+        }
+        swarm
+    }
+
+    fn set_state(swarm: &mut Nanoswarm, id: &str, state: MemberState) {
+        match state {
+            MemberState::Active => {}
+            MemberState::Quarantined => swarm.quarantine_member(id, 1, "setup").unwrap(),
+            MemberState::Retired => swarm.retire_member(id, 1, "setup").unwrap(),
+        }
+    }
+
+    #[test]
+    fn transitions_follow_the_state_table() {
+        use LifecycleEventKind::{Quarantined, Reinstated, Retired};
+        use MemberState::{Active, Quarantined as Held, Retired as Gone};
+        let table = [
+            (Active, Quarantined, Some(Held)),
+            (Active, Reinstated, None),
+            (Active, Retired, Some(Gone)),
+            (Held, Quarantined, None),
+            (Held, Reinstated, Some(Active)),
+            (Held, Retired, Some(Gone)),
+            (Gone, Quarantined, None),
+            (Gone, Reinstated, None),
+            (Gone, Retired, None),
+        ];
+        for (from, event, to) in table {
+            let mut swarm = swarm(&["m"]);
+            set_state(&mut swarm, "m", from);
+            let logged = swarm.events.len();
+            let result = match event {
+                Quarantined => swarm.quarantine_member("m", 5, "why"),
+                Reinstated => swarm.reinstate_member("m", 5, "why"),
+                _ => swarm.retire_member("m", 5, "why"),
+            };
+            let lifecycle = &swarm.members[0].lifecycle;
+            match to {
+                Some(to) => {
+                    assert_eq!(result, Ok(()), "{:?} from {:?}", event, from);
+                    assert_eq!(lifecycle.state, to);
+                    assert_eq!(lifecycle.state_since_unix, 5);
+                    assert_eq!(lifecycle.reason.as_deref(), Some("why"));
+                    assert_eq!(swarm.events.len(), logged + 1);
+                }
+                None => {
+                    let expected = LifecycleError::InvalidTransition {
+                        id: "m".to_string(),
+                        from,
+                        event,
+                    };
+                    assert_eq!(result, Err(expected), "{:?} from {:?}", event, from);
+                    assert_eq!(lifecycle.state, from);
+                    assert_eq!(swarm.events.len(), logged);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn any_state_can_be_removed_and_unknown_ids_are_rejected() {
+        for state in [
+            MemberState::Active,
+            MemberState::Quarantined,
+            MemberState::Retired,
+        ] {
+            let mut swarm = swarm(&["a", "b"]);
+            set_state(&mut swarm, "a", state);
+            let removed = swarm.remove_member("a", 9, "explanted").unwrap();
+            assert_eq!(removed.lifecycle.state, state);
+            assert_eq!(swarm.member_index("b"), Some(0));
+            assert_eq!(swarm.events.last().unwrap().from, Some(state));
+        }
+
+        let mut swarm = swarm(&["a"]);
+        let unknown = Err(LifecycleError::UnknownMember {
+            id: "zz".to_string(),
+        });
+        assert_eq!(swarm.quarantine_member("zz", 1, "x"), unknown);
+        assert_eq!(swarm.reinstate_member("zz", 1, "x"), unknown);
+        assert_eq!(swarm.retire_member("zz", 1, "x"), unknown);
+        assert_eq!(swarm.remove_member("zz", 1, "x").err(), unknown.err());
+        assert_eq!(swarm.events.len(), 1);
+    }
+
+    #[test]
+    fn duplicate_ids_are_rejected() {
+        let mut swarm = swarm(&["a"]);
+        let duplicate = Err(LifecycleError::DuplicateMember {
+            id: "a".to_string(),
+        });
+        assert_eq!(swarm.admit_member(member("a", 500.0), 3), duplicate);
+        assert_eq!(swarm.add_member(member("a", 500.0)), duplicate);
+        assert_eq!(swarm.members.len(), 1);
+        assert_eq!(swarm.events.len(), 1);
+
+        // Once removed, the id is free again.
+        swarm.remove_member("a", 4, "explanted").unwrap();
+        assert_eq!(swarm.admit_member(member("a", 500.0), 5), Ok(()));
+    }
+
+    #[test]
+    fn event_log_records_each_transition() {
+        let mut swarm = Nanoswarm::new("did:example:owner");
+        swarm.admit_member(member("a", 0.0), 100).unwrap();
+        swarm.admit_member(member("b", 120.0), 110).unwrap();
+        swarm.quarantine_member("a", 200, "hot").unwrap();
+        swarm.reinstate_member("a", 300, "reviewed").unwrap();
+        swarm.retire_member("b", 400, "end of study").unwrap();
+        swarm.remove_member("b", 500, "explanted").unwrap();
+        let _ = swarm.retire_member("b", 600, "too late");
+
+        let log: Vec<(&str, LifecycleEventKind, i64, Option<MemberState>)> = swarm
+            .events
+            .iter()
+            .map(|e| (e.member_id.as_str(), e.kind, e.at_unix, e.from))
+            .collect();
+        use LifecycleEventKind::*;
+        assert_eq!(
+            log,
+            [
+                ("a", Admitted, 100, None),
+                ("b", Admitted, 110, None),
+                ("a", Quarantined, 200, Some(MemberState::Active)),
+                ("a", Reinstated, 300, Some(MemberState::Quarantined)),
+                ("b", Retired, 400, Some(MemberState::Active)),
+                ("b", Removed, 500, Some(MemberState::Retired)),
+            ]
+        );
+        assert_eq!(swarm.events[0].reason, None);
+        assert_eq!(swarm.events[2].reason.as_deref(), Some("hot"));
+        assert_eq!(swarm.events_since(300).count(), 3);
+        assert_eq!(swarm.events_since(501).count(), 0);
+        assert_eq!(swarm.members[0].lifecycle.admitted_at_unix, 100);
+    }
+
+    #[test]
+    fn violations_are_quarantined_by_member_id() {
+        let mut swarm = swarm(&["a", "b", "c", "d"]);
+        swarm.retire_member("d", 1, "setup").unwrap();
+        let state = |flag| SafetyState::new(0.8, 0.1, 0.1, 0.9, 0.1, flag, SwarmMode::Normal);
+        // Out of member order, with an unknown id and a retired member.
+        let states = [
+            ("c".to_string(), state(BioLoadFlag::Violation)),
+            ("zz".to_string(), state(BioLoadFlag::Violation)),
+            ("a".to_string(), state(BioLoadFlag::Caution)),
+            ("d".to_string(), state(BioLoadFlag::Violation)),
+        ];
+        assert_eq!(swarm.quarantine_violations(&states, 7), ["c"]);
+        assert_eq!(swarm.members_in_state(MemberState::Quarantined), [2]);
+        assert_eq!(swarm.members_in_state(MemberState::Active), [0, 1]);
+
+        // Already quarantined members are not quarantined again.
+        assert!(swarm.quarantine_violations(&states, 8).is_empty());
+        assert_eq!(swarm.events.last().unwrap().at_unix, 7);
+    }
+}
@@ -0,0 +1,387 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::nanopolygon::{
+    BioAffinityTarget, BiophysicalMetadata, Face, Nanopolygon, SurfaceChemistry, SurfaceLigand,
+    VertexNm,
+};
+use super::validation::GeometryError;
+
+/// Bulk and surface properties of a particle material, with the sources
+/// the values were taken from.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Material {
+    pub id: String,
+    pub name: String,
+    pub hydrophobicity_index: f32,
+    pub elastic_modulus_kpa: f32,
+    /// Typical zeta potential in physiological buffer (pH 7.4).
+    pub zeta_potential_mv: f32,
+    /// Groups the bare material carries on its surface.
+    pub native_ligands: Vec<SurfaceLigand>,
+    pub evidence: Vec<String>,
+}
+
+impl Material {
+    /// Metadata for a particle of this material aimed at `target`.
+    pub fn bio(&self, target: BioAffinityTarget) -> BiophysicalMetadata {
+        BiophysicalMetadata {
+            target,
+            zeta_potential_mv: self.zeta_potential_mv,
+            hydrophobicity_index: self.hydrophobicity_index,
+            elastic_modulus_kpa: self.elastic_modulus_kpa,
+            ligands: self.native_ligands.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum MaterialError {
+    UnknownMaterial { id: String },
+    Geometry(Vec<GeometryError>),
+}
+
+impl fmt::Display for MaterialError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MaterialError::UnknownMaterial { id } => {
+                write!(f, "material '{}' is not in the library", id)
+            }
+            MaterialError::Geometry(errors) => {
+                write!(f, "material particle failed validation:")?;
+                for e in errors {
+                    write!(f, " {};", e)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for MaterialError {}
+
+impl From<Vec<GeometryError>> for MaterialError {
+    fn from(errors: Vec<GeometryError>) -> Self {
+        MaterialError::Geometry(errors)
+    }
+}
+
+/// Materials by id. Starts from the built-in set; entries can be added or
+/// replaced.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MaterialLibrary {
+    pub materials: Vec<Material>,
+}
+
+impl Default for MaterialLibrary {
+    fn default() -> Self {
+        Self {
+            materials: builtin_materials(),
+        }
+    }
+}
+
+impl MaterialLibrary {
+    pub fn empty() -> Self {
+        Self {
+            materials: Vec::new(),
+        }
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Material> {
+        self.materials.iter().find(|m| m.id == id)
+    }
+
+    /// Add `material`, replacing any entry with the same id.
+    pub fn insert(&mut self, material: Material) {
+        self.materials.retain(|m| m.id != material.id);
+        self.materials.push(material);
+    }
+
+    pub fn ids(&self) -> Vec<&str> {
+        self.materials.iter().map(|m| m.id.as_str()).collect()
+    }
+
+    pub fn bio(
+        &self,
+        material_id: &str,
+        target: BioAffinityTarget,
+    ) -> Result<BiophysicalMetadata, MaterialError> {
+        self.get(material_id)
+            .map(|m| m.bio(target))
+            .ok_or_else(|| MaterialError::UnknownMaterial {
+                id: material_id.to_string(),
+            })
+    }
+}
+
+impl Nanopolygon {
+    /// Validating face constructor with metadata taken from the library.
+    pub fn from_material(
+        id: &str,
+        vertices: Vec<VertexNm>,
+        faces: Vec<Face>,
+        material_id: &str,
+        target: BioAffinityTarget,
+        library: &MaterialLibrary,
+    ) -> Result<Self, MaterialError> {
+        let bio = library.bio(material_id, target)?;
+        Ok(Self::try_from_faces(id, vertices, faces, bio)?)
+    }
+}
+
+/// Moduli are bulk values; hydrophobicity runs from 0 (fully wetting) to 1
+/// (water contact angle near 120 degrees).
+pub fn builtin_materials() -> Vec<Material> {
+    let ligand = |chemistry, density_per_nm2| SurfaceLigand {
+        chemistry,
+        density_per_nm2,
+    };
+    let material = |id: &str,
+                    name: &str,
+                    hydrophobicity_index,
+                    elastic_modulus_kpa,
+                    zeta_potential_mv,
+                    native_ligands,
+                    evidence: &[&str]| Material {
+        id: id.to_string(),
+        name: name.to_string(),
+        hydrophobicity_index,
+        elastic_modulus_kpa,
+        zeta_potential_mv,
+        native_ligands,
+        evidence: evidence.iter().map(|e| e.to_string()).collect(),
+    };
+
+    vec![
+        material(
+            "gold",
+            "Citrate-capped gold",
+            0.45,
+            7.9e7,
+            -30.0,
+            vec![ligand(SurfaceChemistry::Carboxyl, 1.0)],
+            &[
+                "Polycrystalline Au Young's modulus ~79 GPa (CRC Handbook of Chemistry and Physics)",
+                "Citrate-stabilised Au colloids: zeta -25 to -40 mV at neutral pH",
+            ],
+        ),
+        material(
+            "silica",
+            "Amorphous silica",
+            0.10,
+            7.0e7,
+            -35.0,
+            vec![ligand(SurfaceChemistry::Hydroxyl, 4.6)],
+            &[
+                "Fused silica Young's modulus ~70 GPa",
+                "Fully hydroxylated silica carries ~4.6 silanols/nm^2 (Zhuravlev constant)",
+                "Silica isoelectric point near pH 2-3; strongly negative at pH 7.4",
+            ],
+        ),
+        material(
+            "plga",
+            "PLGA 50:50",
+            0.60,
+            1.5e6,
+            -20.0,
+            vec![ligand(SurfaceChemistry::Carboxyl, 0.5)],
+            &[
+                "PLGA tensile modulus 1-2 GPa",
+                "Acid end groups give PLGA nanoparticles zeta -15 to -30 mV",
+            ],
+        ),
+        material(
+            "lipid_bilayer",
+            "Phosphatidylcholine liposome",
+            0.30,
+            1.0e4,
+            -5.0,
+            Vec::new(),
+            &[
+                "AFM indentation of fluid-phase PC vesicles: apparent modulus ~1-20 MPa",
+                "Zwitterionic PC liposomes sit near neutral zeta potential",
+            ],
+        ),
+        material(
+            "graphene_oxide",
+            "Graphene oxide",
+            0.30,
+            2.07e8,
+            -40.0,
+            vec![
+                ligand(SurfaceChemistry::Hydroxyl, 2.0),
+                ligand(SurfaceChemistry::Carboxyl, 0.5),
+            ],
+            &[
+                "Monolayer GO effective Young's modulus ~200 GPa (AFM nanoindentation)",
+                "Ionised carboxyl edges give GO dispersions zeta -30 to -50 mV",
+            ],
+        ),
+        material(
+            "iron_oxide",
+            "Magnetite (Fe3O4)",
+            0.20,
+            1.75e8,
+            -15.0,
+            vec![ligand(SurfaceChemistry::Hydroxyl, 2.0)],
+            &[
+                "Magnetite Young's modulus ~175 GPa",
+                "Bare magnetite isoelectric point near pH 6.5; mildly negative at pH 7.4",
+            ],
+        ),
+        material(
+            "polystyrene",
+            "Polystyrene latex",
+            0.90,
+            3.0e6,
+            -40.0,
+            Vec::new(),
+            &[
+                "Polystyrene Young's modulus ~3 GPa",
+                "Water contact angle ~90 degrees; sulfate latex zeta ~-40 mV",
+            ],
+        ),
+        material(
+            "peg_hydrogel",
+            "PEG diacrylate hydrogel",
+            0.05,
+            10.0,
+            -2.0,
+            vec![ligand(SurfaceChemistry::Peg, 1.0)],
+            &[
+                "Soft PEGDA hydrogels span ~1-100 kPa depending on crosslink density",
+                "Non-ionic PEG surfaces sit near 0 mV",
+            ],
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xr_lab_grid::nanopoly::nanopolygon::SurfaceCharge;
+    use crate::xr_lab_grid::nanopoly::stiffness::{StiffnessAnalysis, StiffnessMismatch};
+
+    fn tetrahedron() -> (Vec<VertexNm>, Vec<Face>) {
+        let vertices = [
+            [1.0, 1.0, 1.0],
+            [1.0, -1.0, -1.0],
+            [-1.0, 1.0, -1.0],
+            [-1.0, -1.0, 1.0],
+        ]
+        .into_iter()
+        .map(VertexNm::from_vec3)
+        .collect();
+        let faces = [[0, 1, 2], [0, 3, 1], [0, 2, 3], [1, 3, 2]]
+            .iter()
+            .map(|f| Face {
+                vertex_indices: f.to_vec(),
+            })
+            .collect();
+        (vertices, faces)
+    }
+
+    #[test]
+    fn builtin_library_is_complete_and_sourced() {
+        let library = MaterialLibrary::default();
+        let mut ids = library.ids();
+        assert_eq!(ids.len(), 8);
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), 8);
+        for m in &library.materials {
+            assert!(!m.evidence.is_empty(), "{}", m.id);
+            assert!(m.elastic_modulus_kpa > 0.0, "{}", m.id);
+            assert!((0.0..=1.0).contains(&m.hydrophobicity_index), "{}", m.id);
+        }
+    }
+
+    #[test]
+    fn material_metadata_feeds_the_analyses() {
+        let library = MaterialLibrary::default();
+        let gold = library
+            .bio("gold", BioAffinityTarget::NeuralMembrane)
+            .unwrap();
+        assert_eq!(gold.zeta_potential_mv, -30.0);
+        assert_eq!(gold.surface_charge(), SurfaceCharge::Negative);
+        assert!(gold.has_chemistry(SurfaceChemistry::Carboxyl));
+
+        // Liposomes are effectively neutral; PEG hydrogel is fully PEG-covered.
+        let lipid = library
+            .bio("lipid_bilayer", BioAffinityTarget::GlialCell)
+            .unwrap();
+        assert_eq!(lipid.surface_charge(), SurfaceCharge::Neutral);
+        let hydrogel = library
+            .bio("peg_hydrogel", BioAffinityTarget::ExtracellularMatrix)
+            .unwrap();
+        assert!((hydrogel.effective_hydrophobicity() - 0.15).abs() < 1e-6);
+
+        // 10 kPa matches matrix but is a full decade stiffer than neural membrane.
+        let (vertices, faces) = tetrahedron();
+        let soft = Nanopolygon::try_from_faces("h", vertices, faces, hydrogel).unwrap();
+        assert_eq!(
+            soft.stiffness_analysis().mismatch,
+            StiffnessMismatch::Matched
+        );
+        let neural = StiffnessAnalysis::for_target(&soft, &BioAffinityTarget::NeuralMembrane);
+        assert_eq!(neural.mismatch, StiffnessMismatch::TooStiff);
+    }
+
+    #[test]
+    fn insert_replaces_by_id() {
+        let mut library = MaterialLibrary::empty();
+        let mut custom = MaterialLibrary::default().get("silica").unwrap().clone();
+        library.insert(custom.clone());
+        custom.zeta_potential_mv = -10.0;
+        library.insert(custom);
+        assert_eq!(library.ids(), vec!["silica"]);
+        assert_eq!(library.get("silica").unwrap().zeta_potential_mv, -10.0);
+    }
+
+    #[test]
+    fn from_material_reports_unknown_ids_and_bad_geometry() {
+        let library = MaterialLibrary::default();
+        let (vertices, faces) = tetrahedron();
+        let poly = Nanopolygon::from_material(
+            "p",
+            vertices.clone(),
+            faces.clone(),
+            "plga",
+            BioAffinityTarget::MuscleFiber,
+            &library,
+        )
+        .unwrap();
+        assert_eq!(poly.bio.hydrophobicity_index, 0.60);
+        assert_eq!(poly.bio.target, BioAffinityTarget::MuscleFiber);
+
+        let err = Nanopolygon::from_material(
+            "p",
+            vertices.clone(),
+            faces,
+            "unobtainium",
+            BioAffinityTarget::MuscleFiber,
+            &library,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "material 'unobtainium' is not in the library"
+        );
+
+        let broken = vec![Face {
+            vertex_indices: vec![0, 1, 9],
+        }];
+        let err = Nanopolygon::from_material(
+            "p",
+            vertices,
+            broken,
+            "gold",
+            BioAffinityTarget::MuscleFiber,
+            &library,
+        )
+        .unwrap_err();
+        assert!(matches!(err, MaterialError::Geometry(ref e) if !e.is_empty()));
+    }
+}
@@ -0,0 +1,1238 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io::{self, BufRead, Read, Write};
+
+use serde::{Deserialize, Serialize};
+
+use super::geometry;
+use super::nanopolygon::{
+    BioAffinityTarget, BiophysicalMetadata, Edge, Face, Nanopolygon, SurfaceCharge,
+    SurfaceChemistry, SurfaceLigand, VertexNm,
+};
+use super::topology;
+use super::validation::GeometryError;
+
+// Comment / sidecar keys; each line is "<key> name=value name=value ...",
+// except the id line, which carries the id verbatim.
+const BIO_KEY: &str = "nanopoly.bio";
+const UNITS_KEY: &str = "nanopoly.units";
+const ID_KEY: &str = "nanopoly.id";
+
+/// Length unit of coordinates in an external mesh file.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum LengthUnit {
+    Angstrom,
+    Nanometer,
+    Micrometer,
+    Millimeter,
+    Meter,
+}
+
+impl LengthUnit {
+    pub fn nm_per_unit(self) -> f64 {
+        match self {
+            LengthUnit::Angstrom => 0.1,
+            LengthUnit::Nanometer => 1.0,
+            LengthUnit::Micrometer => 1e3,
+            LengthUnit::Millimeter => 1e6,
+            LengthUnit::Meter => 1e9,
+        }
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            LengthUnit::Angstrom => "A",
+            LengthUnit::Nanometer => "nm",
+            LengthUnit::Micrometer => "um",
+            LengthUnit::Millimeter => "mm",
+            LengthUnit::Meter => "m",
+        }
+    }
+
+    pub fn from_symbol(symbol: &str) -> Option<Self> {
+        match symbol {
+            "A" | "angstrom" => Some(LengthUnit::Angstrom),
+            "nm" => Some(LengthUnit::Nanometer),
+            "um" | "µm" => Some(LengthUnit::Micrometer),
+            "mm" => Some(LengthUnit::Millimeter),
+            "m" => Some(LengthUnit::Meter),
+            _ => None,
+        }
+    }
+}
+
+/// How to interpret a mesh file on import.
+#[derive(Clone, Debug)]
+pub struct MeshImportOptions {
+    /// Id used when the file (or sidecar) does not carry one itself.
+    pub id: String,
+    /// Unit used when the file does not declare one itself.
+    pub unit: LengthUnit,
+    /// Used when the file (or sidecar) carries no biophysical metadata.
+    pub fallback_bio: Option<BiophysicalMetadata>,
+}
+
+impl MeshImportOptions {
+    pub fn new(id: &str, unit: LengthUnit) -> Self {
+        Self {
+            id: id.to_string(),
+            unit,
+            fallback_bio: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum MeshIoError {
+    Io(io::Error),
+    Parse { line: usize, message: String },
+    UnsupportedFormat(String),
+    MissingMetadata,
+    Geometry(Vec<GeometryError>),
+}
+
+impl fmt::Display for MeshIoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MeshIoError::Io(e) => write!(f, "mesh i/o failed: {}", e),
+            MeshIoError::Parse { line, message } => {
+                write!(f, "mesh parse error on line {}: {}", line, message)
+            }
+            MeshIoError::UnsupportedFormat(what) => write!(f, "unsupported mesh format: {}", what),
+            MeshIoError::MissingMetadata => {
+                write!(
+                    f,
+                    "mesh carries no biophysical metadata and no fallback was given"
+                )
+            }
+            MeshIoError::Geometry(errors) => {
+                write!(f, "imported mesh failed validation:")?;
+                for e in errors {
+                    write!(f, " {};", e)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for MeshIoError {}
+
+impl From<io::Error> for MeshIoError {
+    fn from(e: io::Error) -> Self {
+        MeshIoError::Io(e)
+    }
+}
+
+fn parse_error(line: usize, message: impl Into<String>) -> MeshIoError {
+    MeshIoError::Parse {
+        line,
+        message: message.into(),
+    }
+}
+
+/// Metadata picked up from comments or a sidecar while reading.
+#[derive(Clone, Debug, Default)]
+pub struct MeshMetadata {
+    pub id: Option<String>,
+    pub bio: Option<BiophysicalMetadata>,
+    pub unit: Option<LengthUnit>,
+}
+
+impl MeshMetadata {
+    /// Feed one comment/sidecar line; lines without a nanopoly key are ignored.
+    pub fn absorb_line(&mut self, line: &str, line_no: usize) -> Result<(), MeshIoError> {
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some(BIO_KEY) => {
+                let fields = key_values(tokens);
+                self.bio = Some(bio_from_fields(&fields, line_no)?);
+            }
+            Some(ID_KEY) => {
+                let id = line.trim_start()[ID_KEY.len()..].trim();
+                if id.is_empty() {
+                    return Err(parse_error(line_no, "id line without an id"));
+                }
+                self.id = Some(id.to_string());
+            }
+            Some(UNITS_KEY) => {
+                let symbol = tokens
+                    .next()
+                    .ok_or_else(|| parse_error(line_no, "units line without a unit"))?;
+                let unit = LengthUnit::from_symbol(symbol)
+                    .ok_or_else(|| parse_error(line_no, format!("unknown unit '{}'", symbol)))?;
+                self.unit = Some(unit);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+pub fn bio_line(bio: &BiophysicalMetadata) -> String {
+    let mut line = format!(
+        "{} target={:?} zeta_potential_mv={} hydrophobicity_index={} elastic_modulus_kpa={}",
+        BIO_KEY,
+        bio.target,
+        bio.zeta_potential_mv,
+        bio.hydrophobicity_index,
+        bio.elastic_modulus_kpa
+    );
+    // ligands=Peg:0.8,Amine:0.05
+    if !bio.ligands.is_empty() {
+        let ligands: Vec<String> = bio
+            .ligands
+            .iter()
+            .map(|l| format!("{:?}:{}", l.chemistry, l.density_per_nm2))
+            .collect();
+        line.push_str(&format!(" ligands={}", ligands.join(",")));
+    }
+    line
+}
+
+pub fn units_line(unit: LengthUnit) -> String {
+    format!("{} {}", UNITS_KEY, unit.symbol())
+}
+
+pub fn id_line(id: &str) -> String {
+    format!("{} {}", ID_KEY, id)
+}
+
+/// Sidecar for formats without comment support (STL): plain metadata lines.
+pub fn write_sidecar<W: Write>(
+    poly: &Nanopolygon,
+    unit: LengthUnit,
+    mut writer: W,
+) -> io::Result<()> {
+    writeln!(writer, "{}", id_line(&poly.id))?;
+    writeln!(writer, "{}", units_line(unit))?;
+    writeln!(writer, "{}", bio_line(&poly.bio))
+}
+
+pub fn read_sidecar<R: BufRead>(reader: R) -> Result<MeshMetadata, MeshIoError> {
+    let mut meta = MeshMetadata::default();
+    for (i, line) in reader.lines().enumerate() {
+        meta.absorb_line(line?.trim(), i + 1)?;
+    }
+    Ok(meta)
+}
+
+fn key_values<'a>(tokens: impl Iterator<Item = &'a str>) -> HashMap<&'a str, &'a str> {
+    tokens.filter_map(|t| t.split_once('=')).collect()
+}
+
+fn bio_from_fields(
+    fields: &HashMap<&str, &str>,
+    line_no: usize,
+) -> Result<BiophysicalMetadata, MeshIoError> {
+    let get = |key: &str| {
+        fields
+            .get(key)
+            .copied()
+            .ok_or_else(|| parse_error(line_no, format!("bio metadata missing '{}'", key)))
+    };
+    let number = |key: &str| -> Result<f32, MeshIoError> {
+        get(key)?
+            .parse::<f32>()
+            .map_err(|_| parse_error(line_no, format!("'{}' is not a number", key)))
+    };
+
+    let target = match get("target")? {
+        "NeuralMembrane" => BioAffinityTarget::NeuralMembrane,
+        "GlialCell" => BioAffinityTarget::GlialCell,
+        "EndothelialCell" => BioAffinityTarget::EndothelialCell,
+        "MuscleFiber" => BioAffinityTarget::MuscleFiber,
+        "ExtracellularMatrix" => BioAffinityTarget::ExtracellularMatrix,
+        other => return Err(parse_error(line_no, format!("unknown target '{}'", other))),
+    };
+    // Files written before zeta potentials only carry the charge class.
+    let zeta_potential_mv = match fields.get("surface_charge") {
+        Some(&class) if !fields.contains_key("zeta_potential_mv") => match class {
+            "Negative" => SurfaceCharge::Negative,
+            "Neutral" => SurfaceCharge::Neutral,
+            "Positive" => SurfaceCharge::Positive,
+            other => return Err(parse_error(line_no, format!("unknown charge '{}'", other))),
+        }
+        .representative_zeta_mv(),
+        _ => number("zeta_potential_mv")?,
+    };
+
+    Ok(BiophysicalMetadata {
+        target,
+        zeta_potential_mv,
+        hydrophobicity_index: number("hydrophobicity_index")?,
+        elastic_modulus_kpa: number("elastic_modulus_kpa")?,
+        ligands: match fields.get("ligands") {
+            Some(list) => ligands_from_field(list, line_no)?,
+            None => Vec::new(),
+        },
+    })
+}
+
+fn ligands_from_field(list: &str, line_no: usize) -> Result<Vec<SurfaceLigand>, MeshIoError> {
+    list.split(',')
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (name, density) = entry.split_once(':').ok_or_else(|| {
+                parse_error(line_no, format!("ligand '{}' has no density", entry))
+            })?;
+            let chemistry = match name {
+                "Peg" => SurfaceChemistry::Peg,
+                "Carboxyl" => SurfaceChemistry::Carboxyl,
+                "Amine" => SurfaceChemistry::Amine,
+                "Hydroxyl" => SurfaceChemistry::Hydroxyl,
+                "Alkyl" => SurfaceChemistry::Alkyl,
+                "RgdPeptide" => SurfaceChemistry::RgdPeptide,
+                "Transferrin" => SurfaceChemistry::Transferrin,
+                "ApolipoproteinE" => SurfaceChemistry::ApolipoproteinE,
+                "Hyaluronan" => SurfaceChemistry::Hyaluronan,
+                other => {
+                    return Err(parse_error(
+                        line_no,
+                        format!("unknown chemistry '{}'", other),
+                    ))
+                }
+            };
+            let density_per_nm2 = density.parse::<f32>().map_err(|_| {
+                parse_error(
+                    line_no,
+                    format!("ligand density '{}' is not a number", density),
+                )
+            })?;
+            Ok(SurfaceLigand {
+                chemistry,
+                density_per_nm2,
+            })
+        })
+        .collect()
+}
+
+struct RawMesh {
+    positions: Vec<[f64; 3]>,
+    faces: Vec<Face>,
+    edges: Vec<Edge>,
+}
+
+fn finish_import(
+    raw: RawMesh,
+    meta: MeshMetadata,
+    options: &MeshImportOptions,
+) -> Result<Nanopolygon, MeshIoError> {
+    let bio = meta
+        .bio
+        .or_else(|| options.fallback_bio.clone())
+        .ok_or(MeshIoError::MissingMetadata)?;
+    let scale = meta.unit.unwrap_or(options.unit).nm_per_unit();
+    let id = meta.id.as_deref().unwrap_or(&options.id);
+
+    let vertices: Vec<VertexNm> = raw
+        .positions
+        .iter()
+        .map(|p| VertexNm {
+            x_nm: p[0] * scale,
+            y_nm: p[1] * scale,
+            z_nm: p[2] * scale,
+        })
+        .collect();
+
+    if raw.faces.is_empty() {
+        return Nanopolygon::try_new(id, vertices, raw.edges, bio).map_err(MeshIoError::Geometry);
+    }
+
+    // Wires alongside the faces are kept as extra edges.
+    let mut poly =
+        Nanopolygon::try_from_faces(id, vertices, raw.faces, bio).map_err(MeshIoError::Geometry)?;
+    let mut known: HashSet<(usize, usize)> = poly
+        .edges
+        .iter()
+        .map(|e| geometry::edge_key(e.start_index, e.end_index))
+        .collect();
+    let before = poly.edges.len();
+    for e in raw.edges {
+        if known.insert(geometry::edge_key(e.start_index, e.end_index)) {
+            poly.edges.push(e);
+        }
+    }
+    if poly.edges.len() > before {
+        poly.validate().map_err(MeshIoError::Geometry)?;
+    }
+    Ok(poly)
+}
+
+fn to_unit(v: &VertexNm, unit: LengthUnit) -> [f64; 3] {
+    let s = 1.0 / unit.nm_per_unit();
+    [v.x_nm * s, v.y_nm * s, v.z_nm * s]
+}
+
+fn output_faces(poly: &Nanopolygon) -> Vec<Vec<usize>> {
+    if poly.faces.is_empty() {
+        poly.triangulate().into_iter().map(|t| t.to_vec()).collect()
+    } else {
+        poly.faces
+            .iter()
+            .map(|f| f.vertex_indices.clone())
+            .collect()
+    }
+}
+
+// Edges that no written face runs along, e.g. wires next to a surface.
+fn loose_edges<'a>(poly: &'a Nanopolygon, faces: &[Vec<usize>]) -> Vec<&'a Edge> {
+    let rings: Vec<Face> = faces
+        .iter()
+        .map(|f| Face {
+            vertex_indices: f.clone(),
+        })
+        .collect();
+    let on_faces = topology::edge_face_counts(&rings);
+    poly.edges
+        .iter()
+        .filter(|e| !on_faces.contains_key(&geometry::edge_key(e.start_index, e.end_index)))
+        .collect()
+}
+
+pub fn read_obj<R: BufRead>(
+    reader: R,
+    options: &MeshImportOptions,
+) -> Result<Nanopolygon, MeshIoError> {
+    let mut raw = RawMesh {
+        positions: Vec::new(),
+        faces: Vec::new(),
+        edges: Vec::new(),
+    };
+    let mut meta = MeshMetadata::default();
+
+    for (i, line) in reader.lines().enumerate() {
+        let line_no = i + 1;
+        let line = line?;
+        let line = line.trim();
+
+        if let Some(comment) = line.strip_prefix('#') {
+            meta.absorb_line(comment.trim(), line_no)?;
+            continue;
+        }
+
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some("v") => {
+                let mut p = [0.0_f64; 3];
+                for c in p.iter_mut() {
+                    *c = tokens
+                        .next()
+                        .and_then(|t| t.parse().ok())
+                        .ok_or_else(|| parse_error(line_no, "vertex needs three numbers"))?;
+                }
+                raw.positions.push(p);
+            }
+            Some("f") => {
+                let indices = tokens
+                    .map(|t| obj_index(t, raw.positions.len(), line_no))
+                    .collect::<Result<Vec<usize>, MeshIoError>>()?;
+                raw.faces.push(Face {
+                    vertex_indices: indices,
+                });
+            }
+            Some("l") => {
+                let indices = tokens
+                    .map(|t| obj_index(t, raw.positions.len(), line_no))
+                    .collect::<Result<Vec<usize>, MeshIoError>>()?;
+                for pair in indices.windows(2) {
+                    raw.edges.push(Edge {
+                        start_index: pair[0],
+                        end_index: pair[1],
+                    });
+                }
+            }
+            // Normals, texture coordinates, groups and materials carry no geometry we keep.
+            _ => {}
+        }
+    }
+
+    finish_import(raw, meta, options)
+}
+
+// OBJ indices are 1-based, negative values count back from the latest vertex.
+fn obj_index(token: &str, vertex_count: usize, line_no: usize) -> Result<usize, MeshIoError> {
+    let head = token.split('/').next().unwrap_or("");
+    let value: i64 = head
+        .parse()
+        .map_err(|_| parse_error(line_no, format!("bad vertex reference '{}'", token)))?;
+    let resolved = if value > 0 {
+        value - 1
+    } else {
+        vertex_count as i64 + value
+    };
+    if value == 0 || resolved < 0 {
+        return Err(parse_error(
+            line_no,
+            format!("bad vertex reference '{}'", token),
+        ));
+    }
+    Ok(resolved as usize)
+}
+
+pub fn write_obj<W: Write>(poly: &Nanopolygon, unit: LengthUnit, mut writer: W) -> io::Result<()> {
+    writeln!(writer, "# {}", id_line(&poly.id))?;
+    writeln!(writer, "# {}", units_line(unit))?;
+    writeln!(writer, "# {}", bio_line(&poly.bio))?;
+    writeln!(writer, "o {}", poly.id)?;
+    for v in &poly.vertices {
+        let p = to_unit(v, unit);
+        writeln!(writer, "v {} {} {}", p[0], p[1], p[2])?;
+    }
+
+    let faces = output_faces(poly);
+    for e in loose_edges(poly, &faces) {
+        writeln!(writer, "l {} {}", e.start_index + 1, e.end_index + 1)?;
+    }
+    for f in faces {
+        let refs: Vec<String> = f.iter().map(|i| (i + 1).to_string()).collect();
+        writeln!(writer, "f {}", refs.join(" "))?;
+    }
+    Ok(())
+}
+
+/// Read ASCII or binary STL; STL has no comments, so metadata comes from a sidecar.
+pub fn read_stl<R: Read>(
+    mut reader: R,
+    sidecar: Option<MeshMetadata>,
+    options: &MeshImportOptions,
+) -> Result<Nanopolygon, MeshIoError> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+
+    let triangles = if looks_like_ascii_stl(&bytes) {
+        parse_ascii_stl(&bytes)?
+    } else {
+        parse_binary_stl(&bytes)?
+    };
+
+    // STL is a triangle soup; weld exactly coincident corners back together.
+    let mut lookup: HashMap<[u64; 3], usize> = HashMap::new();
+    let mut raw = RawMesh {
+        positions: Vec::new(),
+        faces: Vec::with_capacity(triangles.len()),
+        edges: Vec::new(),
+    };
+    for tri in triangles {
+        let mut indices = Vec::with_capacity(3);
+        for p in tri {
+            // Adding 0.0 folds -0.0 into 0.0 so both weld to one vertex.
+            let p = [p[0] + 0.0, p[1] + 0.0, p[2] + 0.0];
+            let key = [p[0].to_bits(), p[1].to_bits(), p[2].to_bits()];
+            let next = raw.positions.len();
+            let index = *lookup.entry(key).or_insert(next);
+            if index == next {
+                raw.positions.push(p);
+            }
+            indices.push(index);
+        }
+        raw.faces.push(Face {
+            vertex_indices: indices,
+        });
+    }
+
+    finish_import(raw, sidecar.unwrap_or_default(), options)
+}
+
+fn looks_like_ascii_stl(bytes: &[u8]) -> bool {
+    if !bytes.starts_with(b"solid") {
+        return false;
+    }
+    // Binary files may also start with "solid"; trust the size formula first.
+    if bytes.len() >= 84 {
+        let count = u32::from_le_bytes([bytes[80], bytes[81], bytes[82], bytes[83]]) as usize;
+        if 84 + count * 50 == bytes.len() {
+            return false;
+        }
+    }
+    true
+}
+
+fn parse_ascii_stl(bytes: &[u8]) -> Result<Vec<[[f64; 3]; 3]>, MeshIoError> {
+    let text = std::str::from_utf8(bytes)
+        .map_err(|_| MeshIoError::UnsupportedFormat("ASCII STL is not valid UTF-8".to_string()))?;
+
+    let mut triangles = Vec::new();
+    let mut corners: Vec<[f64; 3]> = Vec::with_capacity(3);
+    for (i, line) in text.lines().enumerate() {
+        let line_no = i + 1;
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some("vertex") => {
+                let mut p = [0.0_f64; 3];
+                for c in p.iter_mut() {
+                    *c = tokens
+                        .next()
+                        .and_then(|t| t.parse().ok())
+                        .ok_or_else(|| parse_error(line_no, "vertex needs three numbers"))?;
+                }
+                corners.push(p);
+            }
+            Some("endloop") => {
+                if corners.len() != 3 {
+                    return Err(parse_error(line_no, "facet loop must have three vertices"));
+                }
+                triangles.push([corners[0], corners[1], corners[2]]);
+                corners.clear();
+            }
+            _ => {}
+        }
+    }
+    Ok(triangles)
+}
+
+fn parse_binary_stl(bytes: &[u8]) -> Result<Vec<[[f64; 3]; 3]>, MeshIoError> {
+    if bytes.len() < 84 {
+        return Err(MeshIoError::UnsupportedFormat(
+            "binary STL shorter than its header".to_string(),
+        ));
+    }
+    let count = u32::from_le_bytes([bytes[80], bytes[81], bytes[82], bytes[83]]) as usize;
+    if bytes.len() < 84 + count * 50 {
+        return Err(MeshIoError::UnsupportedFormat(format!(
+            "binary STL declares {} triangles but is truncated",
+            count
+        )));
+    }
+
+    let read_f32 = |at: usize| {
+        f32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]]) as f64
+    };
+    let mut triangles = Vec::with_capacity(count);
+    for t in 0..count {
+        // Skip the 12-byte facet normal; it is recomputed from the winding.
+        let base = 84 + t * 50 + 12;
+        let mut tri = [[0.0_f64; 3]; 3];
+        for (c, corner) in tri.iter_mut().enumerate() {
+            for (k, value) in corner.iter_mut().enumerate() {
+                *value = read_f32(base + c * 12 + k * 4);
+            }
+        }
+        triangles.push(tri);
+    }
+    Ok(triangles)
+}
+
+pub fn write_stl_ascii<W: Write>(
+    poly: &Nanopolygon,
+    unit: LengthUnit,
+    mut writer: W,
+) -> io::Result<()> {
+    writeln!(writer, "solid {}", poly.id)?;
+    for t in poly.triangulate() {
+        let c: Vec<[f64; 3]> = t
+            .iter()
+            .map(|&i| to_unit(&poly.vertices[i], unit))
+            .collect();
+        let n = stl_normal(&c);
+        writeln!(writer, "  facet normal {} {} {}", n[0], n[1], n[2])?;
+        writeln!(writer, "    outer loop")?;
+        for p in &c {
+            writeln!(writer, "      vertex {} {} {}", p[0], p[1], p[2])?;
+        }
+        writeln!(writer, "    endloop")?;
+        writeln!(writer, "  endfacet")?;
+    }
+    writeln!(writer, "endsolid {}", poly.id)
+}
+
+pub fn write_stl_binary<W: Write>(
+    poly: &Nanopolygon,
+    unit: LengthUnit,
+    mut writer: W,
+) -> io::Result<()> {
+    let mut header = [b' '; 80];
+    let label = format!("nanopoly {} units={}", poly.id, unit.symbol());
+    let len = label.len().min(80);
+    header[..len].copy_from_slice(&label.as_bytes()[..len]);
+    writer.write_all(&header)?;
+
+    let triangles = poly.triangulate();
+    writer.write_all(&(triangles.len() as u32).to_le_bytes())?;
+    for t in triangles {
+        let c: Vec<[f64; 3]> = t
+            .iter()
+            .map(|&i| to_unit(&poly.vertices[i], unit))
+            .collect();
+        let n = stl_normal(&c);
+        for value in n.iter().chain(c.iter().flatten()) {
+            writer.write_all(&(*value as f32).to_le_bytes())?;
+        }
+        writer.write_all(&0u16.to_le_bytes())?;
+    }
+    Ok(())
+}
+
+fn stl_normal(c: &[[f64; 3]]) -> [f64; 3] {
+    let n = geometry::cross(geometry::sub(c[1], c[0]), geometry::sub(c[2], c[0]));
+    let len = geometry::norm(n);
+    if len > 0.0 {
+        geometry::scale(n, 1.0 / len)
+    } else {
+        [0.0, 0.0, 0.0]
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum PlyFormat {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum PlyScalar {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+impl PlyScalar {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "char" | "int8" => Some(PlyScalar::I8),
+            "uchar" | "uint8" => Some(PlyScalar::U8),
+            "short" | "int16" => Some(PlyScalar::I16),
+            "ushort" | "uint16" => Some(PlyScalar::U16),
+            "int" | "int32" => Some(PlyScalar::I32),
+            "uint" | "uint32" => Some(PlyScalar::U32),
+            "float" | "float32" => Some(PlyScalar::F32),
+            "double" | "float64" => Some(PlyScalar::F64),
+            _ => None,
+        }
+    }
+
+    fn size(self) -> usize {
+        match self {
+            PlyScalar::I8 | PlyScalar::U8 => 1,
+            PlyScalar::I16 | PlyScalar::U16 => 2,
+            PlyScalar::I32 | PlyScalar::U32 | PlyScalar::F32 => 4,
+            PlyScalar::F64 => 8,
+        }
+    }
+
+    fn decode(self, b: &[u8], big_endian: bool) -> f64 {
+        macro_rules! num {
+            ($t:ty, $n:expr) => {{
+                let mut raw = [0u8; $n];
+                raw.copy_from_slice(&b[..$n]);
+                if big_endian {
+                    <$t>::from_be_bytes(raw) as f64
+                } else {
+                    <$t>::from_le_bytes(raw) as f64
+                }
+            }};
+        }
+        match self {
+            PlyScalar::I8 => b[0] as i8 as f64,
+            PlyScalar::U8 => b[0] as f64,
+            PlyScalar::I16 => num!(i16, 2),
+            PlyScalar::U16 => num!(u16, 2),
+            PlyScalar::I32 => num!(i32, 4),
+            PlyScalar::U32 => num!(u32, 4),
+            PlyScalar::F32 => num!(f32, 4),
+            PlyScalar::F64 => num!(f64, 8),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum PlyProperty {
+    Scalar {
+        name: String,
+        kind: PlyScalar,
+    },
+    List {
+        name: String,
+        count: PlyScalar,
+        item: PlyScalar,
+    },
+}
+
+#[derive(Clone, Debug)]
+struct PlyElement {
+    name: String,
+    count: usize,
+    properties: Vec<PlyProperty>,
+}
+
+/// Read ASCII or binary PLY; `comment nanopoly.*` header lines carry metadata.
+pub fn read_ply<R: BufRead>(
+    mut reader: R,
+    options: &MeshImportOptions,
+) -> Result<Nanopolygon, MeshIoError> {
+    let mut meta = MeshMetadata::default();
+    let mut format = None;
+    let mut elements: Vec<PlyElement> = Vec::new();
+    let mut line_no = 0;
+
+    loop {
+        let mut buf = Vec::new();
+        if reader.read_until(b'\n', &mut buf)? == 0 {
+            return Err(parse_error(line_no, "PLY header has no end_header"));
+        }
+        line_no += 1;
+        let line = String::from_utf8_lossy(&buf);
+        let line = line.trim();
+        if line_no == 1 {
+            if line != "ply" {
+                return Err(MeshIoError::UnsupportedFormat(
+                    "missing 'ply' magic".to_string(),
+                ));
+            }
+            continue;
+        }
+
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some("format") => {
+                format = Some(match tokens.next() {
+                    Some("ascii") => PlyFormat::Ascii,
+                    Some("binary_little_endian") => PlyFormat::BinaryLittleEndian,
+                    Some("binary_big_endian") => PlyFormat::BinaryBigEndian,
+                    other => {
+                        return Err(MeshIoError::UnsupportedFormat(format!(
+                            "PLY format {:?}",
+                            other
+                        )))
+                    }
+                });
+            }
+            Some("comment") => {
+                let rest = line.trim_start_matches("comment").trim();
+                meta.absorb_line(rest, line_no)?;
+            }
+            Some("element") => {
+                let name = tokens
+                    .next()
+                    .ok_or_else(|| parse_error(line_no, "element without a name"))?;
+                let count = tokens
+                    .next()
+                    .and_then(|t| t.parse().ok())
+                    .ok_or_else(|| parse_error(line_no, "element without a count"))?;
+                elements.push(PlyElement {
+                    name: name.to_string(),
+                    count,
+                    properties: Vec::new(),
+                });
+            }
+            Some("property") => {
+                let element = elements
+                    .last_mut()
+                    .ok_or_else(|| parse_error(line_no, "property before any element"))?;
+                let parts: Vec<&str> = tokens.collect();
+                let bad = || parse_error(line_no, "malformed property");
+                let property = if parts.first() == Some(&"list") {
+                    PlyProperty::List {
+                        count: parts
+                            .get(1)
+                            .and_then(|t| PlyScalar::parse(t))
+                            .ok_or_else(bad)?,
+                        item: parts
+                            .get(2)
+                            .and_then(|t| PlyScalar::parse(t))
+                            .ok_or_else(bad)?,
+                        name: parts.get(3).ok_or_else(bad)?.to_string(),
+                    }
+                } else {
+                    PlyProperty::Scalar {
+                        kind: parts
+                            .first()
+                            .and_then(|t| PlyScalar::parse(t))
+                            .ok_or_else(bad)?,
+                        name: parts.get(1).ok_or_else(bad)?.to_string(),
+                    }
+                };
+                element.properties.push(property);
+            }
+            Some("end_header") => break,
+            _ => {}
+        }
+    }
+
+    let format = format.ok_or_else(|| parse_error(line_no, "PLY header has no format line"))?;
+    let mut raw = RawMesh {
+        positions: Vec::new(),
+        faces: Vec::new(),
+        edges: Vec::new(),
+    };
+
+    let mut body = PlyBody::new(reader, format, line_no);
+    for element in &elements {
+        for _ in 0..element.count {
+            let mut scalars: HashMap<&str, f64> = HashMap::new();
+            let mut lists: HashMap<&str, Vec<f64>> = HashMap::new();
+            for property in &element.properties {
+                match property {
+                    PlyProperty::Scalar { name, kind } => {
+                        scalars.insert(name, body.value(*kind)?);
+                    }
+                    PlyProperty::List { name, count, item } => {
+                        // No preallocation: the count comes straight from the file.
+                        let n = ply_index(body.value(*count)?, body.line_no)?;
+                        let mut values = Vec::new();
+                        for _ in 0..n {
+                            values.push(body.value(*item)?);
+                        }
+                        lists.insert(name, values);
+                    }
+                }
+            }
+            body.end_record()?;
+
+            match element.name.as_str() {
+                "vertex" => {
+                    let coord = |k: &str| {
+                        scalars.get(k).copied().ok_or_else(|| {
+                            parse_error(body.line_no, format!("vertex missing {}", k))
+                        })
+                    };
+                    raw.positions.push([coord("x")?, coord("y")?, coord("z")?]);
+                }
+                "face" => {
+                    let indices = lists
+                        .get("vertex_indices")
+                        .or_else(|| lists.get("vertex_index"))
+                        .ok_or_else(|| parse_error(body.line_no, "face without vertex_indices"))?;
+                    let vertex_indices = indices
+                        .iter()
+                        .map(|&i| ply_index(i, body.line_no))
+                        .collect::<Result<_, _>>()?;
+                    raw.faces.push(Face { vertex_indices });
+                }
+                "edge" => {
+                    if let (Some(&a), Some(&b)) = (scalars.get("vertex1"), scalars.get("vertex2")) {
+                        raw.edges.push(Edge {
+                            start_index: ply_index(a, body.line_no)?,
+                            end_index: ply_index(b, body.line_no)?,
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    finish_import(raw, meta, options)
+}
+
+// Counts and indices arrive as f64; anything but a non-negative whole
+// number would otherwise saturate to 0 on the cast.
+fn ply_index(value: f64, line_no: usize) -> Result<usize, MeshIoError> {
+    if value.is_finite() && value >= 0.0 && value.fract() == 0.0 && value <= u32::MAX as f64 {
+        Ok(value as usize)
+    } else {
+        Err(parse_error(
+            line_no,
+            format!("bad count or index {}", value),
+        ))
+    }
+}
+
+// Pulls typed values from either whitespace-separated text or packed binary.
+struct PlyBody<R: BufRead> {
+    reader: R,
+    format: PlyFormat,
+    line_no: usize,
+    pending: Vec<String>,
+}
+
+impl<R: BufRead> PlyBody<R> {
+    fn new(reader: R, format: PlyFormat, header_lines: usize) -> Self {
+        Self {
+            reader,
+            format,
+            line_no: header_lines,
+            pending: Vec::new(),
+        }
+    }
+
+    fn value(&mut self, kind: PlyScalar) -> Result<f64, MeshIoError> {
+        match self.format {
+            PlyFormat::Ascii => {
+                while self.pending.is_empty() {
+                    let mut line = String::new();
+                    if self.reader.read_line(&mut line)? == 0 {
+                        return Err(parse_error(self.line_no, "PLY body ended early"));
+                    }
+                    self.line_no += 1;
+                    self.pending = line.split_whitespace().rev().map(str::to_string).collect();
+                }
+                let token = self.pending.pop().unwrap_or_default();
+                token
+                    .parse()
+                    .map_err(|_| parse_error(self.line_no, format!("bad number '{}'", token)))
+            }
+            PlyFormat::BinaryLittleEndian | PlyFormat::BinaryBigEndian => {
+                let mut buf = [0u8; 8];
+                self.reader.read_exact(&mut buf[..kind.size()])?;
+                Ok(kind.decode(&buf, self.format == PlyFormat::BinaryBigEndian))
+            }
+        }
+    }
+
+    // ASCII records are one per line; leftovers mean the header lied.
+    fn end_record(&mut self) -> Result<(), MeshIoError> {
+        if !self.pending.is_empty() {
+            return Err(parse_error(self.line_no, "extra values in PLY record"));
+        }
+        Ok(())
+    }
+}
+
+pub fn write_ply<W: Write>(poly: &Nanopolygon, unit: LengthUnit, mut writer: W) -> io::Result<()> {
+    let faces = output_faces(poly);
+    let edges = loose_edges(poly, &faces);
+    writeln!(writer, "ply")?;
+    writeln!(writer, "format ascii 1.0")?;
+    writeln!(writer, "comment {}", id_line(&poly.id))?;
+    writeln!(writer, "comment {}", units_line(unit))?;
+    writeln!(writer, "comment {}", bio_line(&poly.bio))?;
+    writeln!(writer, "element vertex {}", poly.vertices.len())?;
+    writeln!(writer, "property double x")?;
+    writeln!(writer, "property double y")?;
+    writeln!(writer, "property double z")?;
+    writeln!(writer, "element face {}", faces.len())?;
+    writeln!(writer, "property list uchar int vertex_indices")?;
+    if !edges.is_empty() {
+        writeln!(writer, "element edge {}", edges.len())?;
+        writeln!(writer, "property int vertex1")?;
+        writeln!(writer, "property int vertex2")?;
+    }
+    writeln!(writer, "end_header")?;
+
+    for v in &poly.vertices {
+        let p = to_unit(v, unit);
+        writeln!(writer, "{} {} {}", p[0], p[1], p[2])?;
+    }
+    for f in &faces {
+        let refs: Vec<String> = f.iter().map(|i| i.to_string()).collect();
+        writeln!(writer, "{} {}", f.len(), refs.join(" "))?;
+    }
+    for e in edges {
+        writeln!(writer, "{} {}", e.start_index, e.end_index)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xr_lab_grid::nanopoly::generators;
+
+    fn bio() -> BiophysicalMetadata {
+        BiophysicalMetadata {
+            target: BioAffinityTarget::EndothelialCell,
+            zeta_potential_mv: -12.5,
+            hydrophobicity_index: 0.35,
+            elastic_modulus_kpa: 8.0,
+            ligands: vec![
+                SurfaceLigand {
+                    chemistry: SurfaceChemistry::Peg,
+                    density_per_nm2: 0.75,
+                },
+                SurfaceLigand {
+                    chemistry: SurfaceChemistry::RgdPeptide,
+                    density_per_nm2: 0.05,
+                },
+            ],
+        }
+    }
+
+    // Files carry their own id, so this one must never show up.
+    fn options(unit: LengthUnit) -> MeshImportOptions {
+        MeshImportOptions::new("fallback", unit)
+    }
+
+    fn edge_keys(poly: &Nanopolygon) -> HashSet<(usize, usize)> {
+        poly.edges
+            .iter()
+            .map(|e| geometry::edge_key(e.start_index, e.end_index))
+            .collect()
+    }
+
+    fn assert_same_vertices(a: &[VertexNm], b: &[VertexNm], tolerance_nm: f64) {
+        assert_eq!(a.len(), b.len());
+        for (p, q) in a.iter().zip(b) {
+            let d = geometry::norm(geometry::sub(geometry::to_vec3(p), geometry::to_vec3(q)));
+            assert!(d <= tolerance_nm, "{:?} vs {:?}", p, q);
+        }
+    }
+
+    fn assert_same_polygon(original: &Nanopolygon, read: &Nanopolygon, tolerance_nm: f64) {
+        assert_eq!(read.id, original.id);
+        assert_eq!(read.bio, original.bio);
+        assert_same_vertices(&read.vertices, &original.vertices, tolerance_nm);
+        let rings = |p: &Nanopolygon| -> Vec<Vec<usize>> {
+            p.faces.iter().map(|f| f.vertex_indices.clone()).collect()
+        };
+        assert_eq!(rings(read), rings(original));
+        assert_eq!(edge_keys(read), edge_keys(original));
+    }
+
+    #[test]
+    fn obj_round_trip() {
+        // Quads around the side, octagons on the caps.
+        let rod = generators::cylinder("rod-7", 5.0, 20.0, 8, bio()).unwrap();
+        let mut text = Vec::new();
+        write_obj(&rod, LengthUnit::Nanometer, &mut text).unwrap();
+        let read = read_obj(text.as_slice(), &options(LengthUnit::Meter)).unwrap();
+        assert_same_polygon(&rod, &read, 0.0);
+    }
+
+    #[test]
+    fn ply_round_trip_in_micrometres() {
+        let rod = generators::cylinder("rod 8", 5.0, 20.0, 6, bio()).unwrap();
+        let mut text = Vec::new();
+        write_ply(&rod, LengthUnit::Micrometer, &mut text).unwrap();
+        let read = read_ply(text.as_slice(), &options(LengthUnit::Nanometer)).unwrap();
+        assert_same_polygon(&rod, &read, 1e-9);
+    }
+
+    #[test]
+    fn stl_round_trips_through_sidecar() {
+        let sphere = generators::icosphere("sphere-3", 10.0, 1, bio()).unwrap();
+        let mut sidecar = Vec::new();
+        write_sidecar(&sphere, LengthUnit::Angstrom, &mut sidecar).unwrap();
+
+        let mut ascii = Vec::new();
+        write_stl_ascii(&sphere, LengthUnit::Angstrom, &mut ascii).unwrap();
+        let mut binary = Vec::new();
+        write_stl_binary(&sphere, LengthUnit::Angstrom, &mut binary).unwrap();
+        assert_eq!(binary.len(), 84 + 50 * sphere.faces.len());
+
+        // STL welds corners in first-use order, so compare face by face.
+        for (bytes, tolerance_nm) in [(ascii, 1e-9), (binary, 1e-5)] {
+            let meta = read_sidecar(sidecar.as_slice()).unwrap();
+            let read = read_stl(bytes.as_slice(), Some(meta), &options(LengthUnit::Meter)).unwrap();
+            assert_eq!(read.id, sphere.id);
+            assert_eq!(read.bio, sphere.bio);
+            assert_eq!(read.vertices.len(), sphere.vertices.len());
+            assert_eq!(read.edges.len(), sphere.edges.len());
+            assert_eq!(read.faces.len(), sphere.faces.len());
+            for (f, g) in read.faces.iter().zip(&sphere.faces) {
+                let corners = |p: &Nanopolygon, face: &Face| -> Vec<VertexNm> {
+                    face.vertex_indices
+                        .iter()
+                        .map(|&i| p.vertices[i].clone())
+                        .collect()
+                };
+                assert_same_vertices(&corners(&read, f), &corners(&sphere, g), tolerance_nm);
+            }
+            assert!(read.is_closed());
+        }
+    }
+
+    #[test]
+    fn obj_keeps_wires_next_to_faces() {
+        let text = "# nanopoly.id tile with wire\n\
+                    v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nv 0 0 1\n\
+                    f 1 2 3 4\n\
+                    l 1 5\n";
+        let mut opts = options(LengthUnit::Nanometer);
+        opts.fallback_bio = Some(bio());
+        let tile = read_obj(text.as_bytes(), &opts).unwrap();
+        assert_eq!(tile.id, "tile with wire");
+        assert_eq!(tile.faces.len(), 1);
+        assert_eq!(tile.edges.len(), 5);
+        assert!(edge_keys(&tile).contains(&(0, 4)));
+
+        // The wire survives being written back out, in either format.
+        let mut obj = Vec::new();
+        write_obj(&tile, LengthUnit::Nanometer, &mut obj).unwrap();
+        assert_same_polygon(&tile, &read_obj(obj.as_slice(), &opts).unwrap(), 0.0);
+        let mut ply = Vec::new();
+        write_ply(&tile, LengthUnit::Nanometer, &mut ply).unwrap();
+        assert_same_polygon(&tile, &read_ply(ply.as_slice(), &opts).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn bad_face_index_is_rejected() {
+        let mut opts = options(LengthUnit::Nanometer);
+        opts.fallback_bio = Some(bio());
+        let vertices = "v 0 0 0\nv 1 0 0\nv 0 1 0\n";
+
+        let zero = format!("{}f 1 2 0\n", vertices);
+        assert!(matches!(
+            read_obj(zero.as_bytes(), &opts),
+            Err(MeshIoError::Parse { line: 4, .. })
+        ));
+
+        let past_end = format!("{}f 1 2 9\n", vertices);
+        match read_obj(past_end.as_bytes(), &opts) {
+            Err(MeshIoError::Geometry(errors)) => {
+                assert!(errors.contains(&GeometryError::FaceIndexOutOfRange {
+                    face: 0,
+                    index: 8,
+                    vertex_count: 3
+                }))
+            }
+            other => panic!("expected a geometry error, got {:?}", other),
+        }
+
+        let ply = "ply\nformat ascii 1.0\nelement vertex 3\nproperty float x\n\
+                   property float y\nproperty float z\nelement face 1\n\
+                   property list uchar int vertex_indices\nend_header\n\
+                   0 0 0\n1 0 0\n0 1 0\n3 0 1 -2\n";
+        assert!(matches!(
+            read_ply(ply.as_bytes(), &opts),
+            Err(MeshIoError::Parse { line: 13, .. })
+        ));
+    }
+
+    #[test]
+    fn truncated_binary_stl_is_rejected() {
+        let sphere = generators::icosphere("s", 10.0, 0, bio()).unwrap();
+        let mut binary = Vec::new();
+        write_stl_binary(&sphere, LengthUnit::Nanometer, &mut binary).unwrap();
+        binary.truncate(binary.len() - 10);
+        let mut opts = options(LengthUnit::Nanometer);
+        opts.fallback_bio = Some(bio());
+        assert!(matches!(
+            read_stl(binary.as_slice(), None, &opts),
+            Err(MeshIoError::UnsupportedFormat(_))
+        ));
+        assert!(matches!(
+            read_stl(&binary[..40], None, &opts),
+            Err(MeshIoError::UnsupportedFormat(_))
+        ));
+    }
+
+    #[test]
+    fn missing_ply_header_is_rejected() {
+        let mut opts = options(LengthUnit::Nanometer);
+        opts.fallback_bio = Some(bio());
+
+        let no_magic = "format ascii 1.0\nelement vertex 0\nend_header\n";
+        assert!(matches!(
+            read_ply(no_magic.as_bytes(), &opts),
+            Err(MeshIoError::UnsupportedFormat(_))
+        ));
+
+        let no_end = "ply\nformat ascii 1.0\nelement vertex 3\n";
+        assert!(matches!(
+            read_ply(no_end.as_bytes(), &opts),
+            Err(MeshIoError::Parse { .. })
+        ));
+
+        let no_format = "ply\nelement vertex 0\nend_header\n";
+        assert!(matches!(
+            read_ply(no_format.as_bytes(), &opts),
+            Err(MeshIoError::Parse { .. })
+        ));
+    }
+
+    #[test]
+    fn metadata_is_required_without_fallback() {
+        let text = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n";
+        assert!(matches!(
+            read_obj(text.as_bytes(), &options(LengthUnit::Nanometer)),
+            Err(MeshIoError::MissingMetadata)
+        ));
+    }
+}
@@ -1,3 +1,31 @@
+pub mod biocompat;
+pub mod bounds;
+pub mod canine_policy;
+pub mod charge;
+pub mod coating;
+pub mod comms;
+pub mod core;
+pub mod decimation;
+pub mod descriptors;
+pub mod diff;
+pub mod docking;
+pub mod drift;
+pub mod energy;
+pub mod generators;
+pub mod geometry;
+pub mod gltf_export;
+pub mod hazards;
+pub mod lifecycle;
+pub mod materials;
+pub mod mesh_io;
 pub mod nanopolygon;
 pub mod nanoswarm;
 pub mod nanosotin_polytope_tobacco;
+pub mod shape_descriptor;
+pub mod simulation;
+pub mod spatial;
+pub mod stiffness;
+pub mod swarm_policy;
+pub mod topology;
+pub mod transform;
+pub mod validation;
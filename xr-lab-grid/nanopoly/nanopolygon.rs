@@ -1,5 +1,10 @@
 use serde::{Deserialize, Serialize};
 
+use super::geometry;
+use super::stiffness::TOLERATED_MISMATCH_DECADES;
+use super::topology;
+use super::validation::{self, GeometryError};
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct VertexNm {
     pub x_nm: f64,
@@ -7,20 +12,27 @@ pub struct VertexNm {
     pub z_nm: f64,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Edge {
     pub start_index: usize,
     pub end_index: usize,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+/// Polygonal face as an ordered vertex ring; winding sets the outward side.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Face {
+    pub vertex_indices: Vec<usize>,
+}
+
+/// Charge class, ordered from most negative to most positive.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum SurfaceCharge {
     Negative,
     Neutral,
     Positive,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum BioAffinityTarget {
     NeuralMembrane,
     GlialCell,
@@ -29,12 +41,139 @@ pub enum BioAffinityTarget {
     ExtracellularMatrix,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+impl BioAffinityTarget {
+    pub const ALL: [BioAffinityTarget; 5] = [
+        BioAffinityTarget::NeuralMembrane,
+        BioAffinityTarget::GlialCell,
+        BioAffinityTarget::EndothelialCell,
+        BioAffinityTarget::MuscleFiber,
+        BioAffinityTarget::ExtracellularMatrix,
+    ];
+
+    /// Native elastic modulus range of the tissue, in kPa. Follows commonly
+    /// reported AFM/indentation ranges.
+    pub fn modulus_range_kpa(&self) -> (f32, f32) {
+        match self {
+            BioAffinityTarget::NeuralMembrane => (0.1, 1.0),
+            BioAffinityTarget::GlialCell => (0.1, 1.5),
+            BioAffinityTarget::EndothelialCell => (1.0, 10.0),
+            BioAffinityTarget::MuscleFiber => (8.0, 17.0),
+            BioAffinityTarget::ExtracellularMatrix => (1.0, 100.0),
+        }
+    }
+
+    /// Native range widened by `TOLERATED_MISMATCH_DECADES` on each side.
+    pub fn tolerated_modulus_kpa(&self) -> (f32, f32) {
+        let (lo, hi) = self.modulus_range_kpa();
+        let factor = 10.0_f32.powf(TOLERATED_MISMATCH_DECADES);
+        (lo / factor, hi * factor)
+    }
+
+    /// Resting blood flow through the tissue, in mL/100 g/min. Grey matter
+    /// for neural membranes, white matter for glia, vessel wall for
+    /// endothelium, resting skeletal muscle and dermal matrix.
+    pub fn perfusion_ml_per_100g_min(&self) -> f64 {
+        match self {
+            BioAffinityTarget::NeuralMembrane => 60.0,
+            BioAffinityTarget::GlialCell => 22.0,
+            BioAffinityTarget::EndothelialCell => 80.0,
+            BioAffinityTarget::MuscleFiber => 4.0,
+            BioAffinityTarget::ExtracellularMatrix => 5.0,
+        }
+    }
+
+    /// How directly activity at the attachment site reaches neural state:
+    /// 1 on neurons, partial through glia, little from vessels, muscle or matrix.
+    pub fn neural_exposure(&self) -> f32 {
+        match self {
+            BioAffinityTarget::NeuralMembrane => 1.0,
+            BioAffinityTarget::GlialCell => 0.6,
+            BioAffinityTarget::EndothelialCell => 0.2,
+            BioAffinityTarget::MuscleFiber => 0.1,
+            BioAffinityTarget::ExtracellularMatrix => 0.05,
+        }
+    }
+}
+
+/// Functional groups, polymers and targeting ligands grafted onto a surface.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SurfaceChemistry {
+    Peg,
+    Carboxyl,
+    Amine,
+    Hydroxyl,
+    Alkyl,
+    RgdPeptide,
+    Transferrin,
+    ApolipoproteinE,
+    Hyaluronan,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SurfaceLigand {
+    pub chemistry: SurfaceChemistry,
+    /// Grafting density in molecules per nm^2.
+    pub density_per_nm2: f32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "BiophysicalRecord")]
 pub struct BiophysicalMetadata {
     pub target: BioAffinityTarget,
-    pub surface_charge: SurfaceCharge,
+    /// Measured zeta potential; the charge class is derived from it.
+    pub zeta_potential_mv: f32,
+    /// Hydrophobicity of the bare core surface, before any coating.
     pub hydrophobicity_index: f32,
     pub elastic_modulus_kpa: f32,
+    pub ligands: Vec<SurfaceLigand>,
+}
+
+// Stored shape of BiophysicalMetadata. Records written before zeta
+// potentials carry only the charge class, read back at its typical zeta.
+#[derive(Deserialize)]
+struct BiophysicalRecord {
+    target: BioAffinityTarget,
+    #[serde(default)]
+    zeta_potential_mv: Option<f32>,
+    #[serde(default)]
+    surface_charge: Option<SurfaceCharge>,
+    hydrophobicity_index: f32,
+    elastic_modulus_kpa: f32,
+    #[serde(default)]
+    ligands: Vec<SurfaceLigand>,
+}
+
+impl TryFrom<BiophysicalRecord> for BiophysicalMetadata {
+    type Error = String;
+
+    fn try_from(record: BiophysicalRecord) -> Result<Self, String> {
+        let zeta_potential_mv = record
+            .zeta_potential_mv
+            .or_else(|| record.surface_charge.map(|c| c.representative_zeta_mv()))
+            .ok_or("missing field `zeta_potential_mv`")?;
+        Ok(Self {
+            target: record.target,
+            zeta_potential_mv,
+            hydrophobicity_index: record.hydrophobicity_index,
+            elastic_modulus_kpa: record.elastic_modulus_kpa,
+            ligands: record.ligands,
+        })
+    }
+}
+
+/// Whole-shape geometric factors linked to nanoparticle toxicity.
+/// Volume-based factors are `None` for open surfaces.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GeometricDescriptors {
+    /// Area of the equal-volume sphere over the actual area; 1.0 for a sphere.
+    pub sphericity: Option<f64>,
+    /// Longest over shortest oriented-box extent.
+    pub aspect_ratio: f64,
+    pub surface_to_volume_per_nm: Option<f64>,
+    /// Largest bend between adjacent triangles, in radians (0 flat, pi knife-edge).
+    pub max_edge_sharpness_rad: f64,
+    /// Share of surface area whose |H| exceeds the high-curvature threshold.
+    pub high_curvature_fraction: f64,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -42,6 +181,8 @@ pub struct Nanopolygon {
     pub id: String,
     pub vertices: Vec<VertexNm>,
     pub edges: Vec<Edge>,
+    #[serde(default)]
+    pub faces: Vec<Face>,
     pub surface_area_nm2: f64,
     pub mean_curvature: f64,
     pub bio: BiophysicalMetadata,
@@ -54,21 +195,134 @@ impl Nanopolygon {
         edges: Vec<Edge>,
         bio: BiophysicalMetadata,
     ) -> Self {
-        let (area, curvature) = Self::compute_geometry(&vertices, &edges);
+        Self::assemble(id, vertices, edges, Vec::new(), bio)
+    }
+
+    /// Build from explicit faces; edges are derived from the face boundaries.
+    pub fn from_faces(
+        id: &str,
+        vertices: Vec<VertexNm>,
+        faces: Vec<Face>,
+        bio: BiophysicalMetadata,
+    ) -> Self {
+        let edges = topology::edges_from_faces(&faces);
+        Self::assemble(id, vertices, edges, faces, bio)
+    }
+
+    /// Validating constructor: rejects the shape with every defect found.
+    pub fn try_new(
+        id: &str,
+        vertices: Vec<VertexNm>,
+        edges: Vec<Edge>,
+        bio: BiophysicalMetadata,
+    ) -> Result<Self, Vec<GeometryError>> {
+        let errors = validation::validate_geometry(&vertices, &edges, &[]);
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        Ok(Self::new(id, vertices, edges, bio))
+    }
+
+    /// Validating counterpart of `from_faces`.
+    pub fn try_from_faces(
+        id: &str,
+        vertices: Vec<VertexNm>,
+        faces: Vec<Face>,
+        bio: BiophysicalMetadata,
+    ) -> Result<Self, Vec<GeometryError>> {
+        let edges = topology::edges_from_faces(&faces);
+        let errors = validation::validate_geometry(&vertices, &edges, &faces);
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        Ok(Self::assemble(id, vertices, edges, faces, bio))
+    }
+
+    pub fn validate(&self) -> Result<(), Vec<GeometryError>> {
+        let errors = validation::validate_geometry(&self.vertices, &self.edges, &self.faces);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    pub fn points(&self) -> Vec<geometry::Vec3> {
+        self.vertices.iter().map(geometry::to_vec3).collect()
+    }
+
+    /// Triangles over the whole surface, winding preserved from the faces.
+    pub fn triangulate(&self) -> Vec<[usize; 3]> {
+        topology::triangulate(&self.points(), &self.edges, &self.faces)
+    }
+
+    /// True when every face edge is shared by exactly two faces.
+    /// Without explicit faces, the edge graph's 3-cycles are checked instead.
+    pub fn is_closed(&self) -> bool {
+        if self.faces.is_empty() {
+            let faces: Vec<Face> = self
+                .triangulate()
+                .into_iter()
+                .map(|t| Face {
+                    vertex_indices: t.to_vec(),
+                })
+                .collect();
+            topology::is_closed_surface(&faces)
+        } else {
+            topology::is_closed_surface(&self.faces)
+        }
+    }
+
+    /// Unit normal per face, following its winding.
+    pub fn face_normals(&self) -> Vec<geometry::Vec3> {
+        let points = self.points();
+        self.faces
+            .iter()
+            .map(|f| topology::unit_normal(&points, &f.vertex_indices))
+            .collect()
+    }
+
+    /// Make face winding consistent across shared edges.
+    pub fn orient_faces(&mut self) -> Result<(), GeometryError> {
+        topology::orient_faces(&mut self.faces)
+    }
+
+    /// Recompute area and curvature after the vertices or faces changed.
+    pub fn refresh_geometry(&mut self) {
+        let (area, curvature) = Self::compute_geometry(&self.vertices, &self.edges, &self.faces);
+        self.surface_area_nm2 = area;
+        self.mean_curvature = curvature;
+    }
+
+    fn assemble(
+        id: &str,
+        vertices: Vec<VertexNm>,
+        edges: Vec<Edge>,
+        faces: Vec<Face>,
+        bio: BiophysicalMetadata,
+    ) -> Self {
+        let (area, curvature) = Self::compute_geometry(&vertices, &edges, &faces);
         Self {
             id: id.to_string(),
             vertices,
             edges,
+            faces,
             surface_area_nm2: area,
             mean_curvature: curvature,
             bio,
         }
     }
 
-    fn compute_geometry(
-        _vertices: &Vec<VertexNm>,
-        _edges: &Vec<Edge>,
-    ) -> (f64, f64) {
-        (0.0_f64, 0.0_f64)
+    /// Returns (surface area in nm^2, mean curvature in 1/nm).
+    ///
+    /// Explicit faces are ear-clipped; without them, the edge graph's 3-cycles
+    /// are read as triangles and a single edge loop as a flat polygon.
+    fn compute_geometry(vertices: &[VertexNm], edges: &[Edge], faces: &[Face]) -> (f64, f64) {
+        let points: Vec<geometry::Vec3> = vertices.iter().map(geometry::to_vec3).collect();
+        let triangles = topology::triangulate(&points, edges, faces);
+
+        let area = geometry::surface_area_nm2(&points, &triangles);
+        let curvature = geometry::mean_curvature_per_nm(&points, &triangles);
+        (area, curvature)
     }
 }
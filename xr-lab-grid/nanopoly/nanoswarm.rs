@@ -1,10 +1,50 @@
+use serde::{Deserialize, Serialize};
+
 use crate::store::metrics::ResponseMetric;
+use super::comms::CommsSettings;
+use super::core::governance::GovernanceLayer;
+use super::core::nanopoly_object::NanopolyObject;
+use super::core::species::HostBudgetProfile;
+use super::drift::{DriftModel, DriftReport};
+use super::energy::{EnergyModel, EnergyReport};
+use super::geometry::Vec3;
+use super::lifecycle::{LifecycleError, LifecycleEvent, MemberLifecycle, MemberState};
 use super::nanopolygon::Nanopolygon;
+use super::spatial::{SpacingReport, SwarmIndex};
+use super::swarm_policy::PolicyVerdict;
+use super::transform::RigidTransform;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct NanoswarmMember {
-    pub poly: Nanopolygon,
-    pub basal_glucose_uW: f64,
+    pub object: NanopolyObject,
+    /// Placement of the member's local frame in the lab frame.
+    pub pose: RigidTransform,
+    #[serde(default)]
+    pub lifecycle: MemberLifecycle,
+}
+
+impl NanoswarmMember {
+    pub fn new(object: NanopolyObject, pose: RigidTransform) -> Self {
+        Self {
+            object,
+            pose,
+            lifecycle: MemberLifecycle::default(),
+        }
+    }
+
+    pub fn poly(&self) -> &Nanopolygon {
+        &self.object.polygon
+    }
+
+    /// Vertices in the lab frame.
+    pub fn world_points(&self) -> Vec<Vec3> {
+        self.object
+            .polygon
+            .points()
+            .into_iter()
+            .map(|p| self.pose.apply(p))
+            .collect()
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -13,39 +53,160 @@ pub struct Nanoswarm {
     pub members: Vec<NanoswarmMember>,
     pub max_energy_d: f32,
     pub max_dw: f32,
+    /// Closest two member surfaces may come without counting as too close.
+    pub min_separation_nm: f64,
+    /// Members within this surface distance count as neighbours.
+    pub crowding_radius_nm: f64,
+    pub max_neighbours: usize,
+    /// D/DW envelope of the host the swarm runs in.
+    pub host_budget: HostBudgetProfile,
+    /// Governance of the host; members are expected to carry the same host.
+    pub governance: GovernanceLayer,
+    pub energy_model: EnergyModel,
+    pub drift_model: DriftModel,
+    pub comms: CommsSettings,
+    /// Lifecycle transitions of members, oldest first.
+    pub events: Vec<LifecycleEvent>,
 }
 
 impl Nanoswarm {
+    /// Swarm in a human host owned by `id`.
     pub fn new(id: &str) -> Self {
+        Self::for_host(id, GovernanceLayer::human(id))
+    }
+
+    /// Swarm with the default budget for the host `governance` describes.
+    pub fn for_host(id: &str, governance: GovernanceLayer) -> Self {
         Self {
             id: id.to_string(),
             members: Vec::new(),
             max_energy_d: 1.0,
             max_dw: 1.0,
+            min_separation_nm: 2.0,
+            crowding_radius_nm: 25.0,
+            max_neighbours: 6,
+            host_budget: governance.host_budget(),
+            governance,
+            energy_model: EnergyModel::default(),
+            drift_model: DriftModel::default(),
+            comms: CommsSettings::default(),
+            events: Vec::new(),
         }
     }
 
-    pub fn add_member(&mut self, member: NanoswarmMember) {
+    /// Add without recording an event; `admit_member` also logs the admission.
+    /// Members with defective geometry or an id already in the swarm are
+    /// rejected. The first member added becomes the Rollback source if none
+    /// is set.
+    pub fn add_member(&mut self, member: NanoswarmMember) -> Result<(), LifecycleError> {
+        if self.member_index(&member.object.id).is_some() {
+            return Err(LifecycleError::DuplicateMember {
+                id: member.object.id.clone(),
+            });
+        }
+        member
+            .poly()
+            .validate()
+            .map_err(|errors| LifecycleError::InvalidGeometry {
+                id: member.object.id.clone(),
+                errors,
+            })?;
+        self.comms
+            .rollback_source
+            .get_or_insert_with(|| member.object.id.clone());
         self.members.push(member);
+        Ok(())
     }
 
+    /// Basal draw of members that are not retired.
+    #[allow(non_snake_case)]
     pub fn total_energy_uW(&self) -> f64 {
+        self.counted_members()
+            .map(|m| m.object.energy.basal_glucose_uW)
+            .sum()
+    }
+
+    /// Metabolic load of each member against the host budget.
+    pub fn energy_report(&self) -> EnergyReport {
+        self.energy_model.evaluate(&self.members, &self.host_budget)
+    }
+
+    pub fn drift_report(&self) -> DriftReport {
+        self.drift_model
+            .evaluate(&self.members, self.host_budget.duty_cycle_max)
+    }
+
+    pub fn spatial_index(&self) -> SwarmIndex {
+        SwarmIndex::build(self)
+    }
+
+    pub fn spacing_report(&self) -> SpacingReport {
+        self.spatial_index().spacing_report(
+            self.min_separation_nm,
+            self.crowding_radius_nm,
+            self.max_neighbours,
+        )
+    }
+
+    /// Members that still count toward swarm load: all but the retired.
+    pub fn counted_members(&self) -> impl Iterator<Item = &NanoswarmMember> {
         self.members
             .iter()
-            .map(|m| m.basal_glucose_uW)
-            .sum()
+            .filter(|m| m.lifecycle.state != MemberState::Retired)
     }
 
-    pub fn check_policy(&self) -> ResponseMetric {
-        let total_energy = self.total_energy_uW();
-        let approximate_d = (total_energy / 1_000_000.0_f64).min(1.0) as f32;
-        let approximate_dw = (approximate_d * 0.5).min(1.0);
+    /// Mean geometric toxicity risk over counted members; 0.0 if there are none.
+    pub fn geometric_risk(&self) -> f32 {
+        let risks: Vec<f32> = self
+            .counted_members()
+            .map(|m| m.poly().geometric_descriptors().toxicity_risk())
+            .collect();
+        if risks.is_empty() {
+            return 0.0;
+        }
+        risks.iter().sum::<f32>() / risks.len() as f32
+    }
 
-        ResponseMetric::new(
-            0.85,
-            approximate_d,
-            approximate_dw,
-            "Nanoswarm aggregate energy and psych-compliance estimate.",
+    /// D and DW added by poor spacing. Overlapping members load the same
+    /// tissue twice; crowding raises local demand.
+    pub fn spacing_load(&self, spacing: &SpacingReport) -> (f32, f32) {
+        let n = self.counted_members().count().max(1) as f32;
+        let overlap = SpacingReport::involved_members(&spacing.overlapping).len() as f32 / n;
+        let close = SpacingReport::involved_members(&spacing.too_close).len() as f32 / n;
+        let crowded = spacing.crowded.len() as f32 / n;
+        (
+            0.5 * overlap + 0.25 * crowded,
+            0.5 * overlap + 0.25 * close + 0.25 * crowded,
         )
     }
+
+    /// Species-aware policy check of the aggregate metric and governance.
+    pub fn check_policy(&self) -> PolicyVerdict {
+        PolicyVerdict::evaluate(self)
+    }
+
+    pub fn aggregate_metric(&self) -> ResponseMetric {
+        let energy_d = self.energy_report().demand_d;
+        let drift_dw = self.drift_report().dw;
+
+        let spacing = self.spacing_report();
+        let (spacing_d, spacing_dw) = self.spacing_load(&spacing);
+        let d = energy_d + spacing_d;
+        let dw = drift_dw + spacing_dw + 0.2 * self.geometric_risk();
+
+        let notes = if spacing.is_clear() {
+            "Nanoswarm aggregate energy and psych-compliance estimate.".to_string()
+        } else {
+            format!(
+                "Nanoswarm aggregate energy and psych-compliance estimate; \
+                 {} overlapping pair(s), {} pair(s) closer than {} nm, {} crowded member(s).",
+                spacing.overlapping.len(),
+                spacing.too_close.len(),
+                self.min_separation_nm,
+                spacing.crowded.len()
+            )
+        };
+
+        ResponseMetric::new(0.85, d, dw, &notes)
+    }
 }
@@ -0,0 +1,379 @@
+use serde::{Deserialize, Serialize};
+
+use super::geometry::{self, Vec3};
+use super::nanopolygon::Nanopolygon;
+
+pub const DEFAULT_BINS: usize = 32;
+
+// Surface samples used for the D2 distribution; pairs grow quadratically.
+const D2_SAMPLES: usize = 256;
+const D2_SEED: u64 = 0x5eed_d2d2_0f0f_1234;
+
+// Dimensionless curvature (|H| times characteristic radius) covered by the histogram.
+// A sphere sits at 1.0; anything sharper than the range lands in the last bin.
+const CURVATURE_RANGE: f64 = 4.0;
+
+/// Rotation- and translation-invariant shape signature.
+/// Both histograms are scale-normalised and sum to 1.0, so shapes of
+/// different size compare on form alone; `scale_nm` keeps the size.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ShapeDescriptor {
+    /// Pairwise surface distances divided by the largest one (D2 distribution).
+    pub d2_histogram: Vec<f64>,
+    /// Area-weighted |H| times the characteristic radius.
+    pub curvature_histogram: Vec<f64>,
+    /// Largest sampled pairwise distance in nm.
+    pub scale_nm: f64,
+}
+
+impl ShapeDescriptor {
+    pub fn from_nanopolygon(poly: &Nanopolygon, bins: usize) -> Self {
+        let points = poly.points();
+        let triangles = poly.triangulate();
+        let bins = bins.max(1);
+
+        let samples = if triangles.is_empty() {
+            points.clone()
+        } else {
+            sample_surface(&points, &triangles, D2_SAMPLES, D2_SEED)
+        };
+        let (d2_histogram, scale_nm) = d2_histogram(&samples, bins);
+
+        let curvature_histogram = curvature_histogram(&points, &triangles, bins);
+
+        Self {
+            d2_histogram,
+            curvature_histogram,
+            scale_nm,
+        }
+    }
+
+    /// Flattened form stored as `curvature_signature` on `NanoPolygon` records.
+    pub fn signature(&self) -> Vec<f64> {
+        let mut out = self.d2_histogram.clone();
+        out.extend_from_slice(&self.curvature_histogram);
+        out
+    }
+
+    /// Inverse of `signature`; the scale is not part of the stored signature.
+    pub fn from_signature(signature: &[f64], scale_nm: f64) -> Option<Self> {
+        if signature.is_empty() || !signature.len().is_multiple_of(2) {
+            return None;
+        }
+        let (d2, curvature) = signature.split_at(signature.len() / 2);
+        Some(Self {
+            d2_histogram: d2.to_vec(),
+            curvature_histogram: curvature.to_vec(),
+            scale_nm,
+        })
+    }
+
+    /// Shape distance in [0, 1]: mean of the two histograms' total-variation distances.
+    pub fn distance(&self, other: &ShapeDescriptor) -> f64 {
+        0.5 * (total_variation(&self.d2_histogram, &other.d2_histogram)
+            + total_variation(&self.curvature_histogram, &other.curvature_histogram))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ShapeMatch {
+    pub poly_id: String,
+    pub distance: f64,
+}
+
+/// In-memory design library ranked by shape distance.
+#[derive(Clone, Debug)]
+pub struct ShapeIndex {
+    pub bins: usize,
+    pub entries: Vec<(String, ShapeDescriptor)>,
+}
+
+impl ShapeIndex {
+    pub fn new(bins: usize) -> Self {
+        Self {
+            bins: bins.max(1),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Index a polygon, replacing any earlier entry with the same id.
+    pub fn insert(&mut self, poly: &Nanopolygon) {
+        let descriptor = ShapeDescriptor::from_nanopolygon(poly, self.bins);
+        self.entries.retain(|(id, _)| id != &poly.id);
+        self.entries.push((poly.id.clone(), descriptor));
+    }
+
+    pub fn remove(&mut self, poly_id: &str) {
+        self.entries.retain(|(id, _)| id != poly_id);
+    }
+
+    /// Closest stored shapes first, at most `limit` of them.
+    pub fn rank(&self, query: &Nanopolygon, limit: usize) -> Vec<ShapeMatch> {
+        let descriptor = ShapeDescriptor::from_nanopolygon(query, self.bins);
+        self.rank_descriptor(&descriptor, limit)
+    }
+
+    pub fn rank_descriptor(&self, query: &ShapeDescriptor, limit: usize) -> Vec<ShapeMatch> {
+        let mut matches: Vec<ShapeMatch> = self
+            .entries
+            .iter()
+            .map(|(id, d)| ShapeMatch {
+                poly_id: id.clone(),
+                distance: query.distance(d),
+            })
+            .collect();
+        matches.sort_by(|a, b| {
+            a.distance
+                .total_cmp(&b.distance)
+                .then_with(|| a.poly_id.cmp(&b.poly_id))
+        });
+        matches.truncate(limit);
+        matches
+    }
+
+    /// Stored shapes within `max_distance` of the query, closest first.
+    pub fn near_duplicates(&self, query: &Nanopolygon, max_distance: f64) -> Vec<ShapeMatch> {
+        let mut matches = self.rank(query, self.entries.len());
+        matches.retain(|m| m.distance <= max_distance);
+        matches
+    }
+}
+
+/// D2 histogram over a point set; returns (normalised histogram, max distance in nm).
+pub fn d2_histogram(points: &[Vec3], bins: usize) -> (Vec<f64>, f64) {
+    let mut distances = Vec::with_capacity(points.len() * points.len().saturating_sub(1) / 2);
+    for (i, &a) in points.iter().enumerate() {
+        for &b in &points[i + 1..] {
+            distances.push(geometry::norm(geometry::sub(a, b)));
+        }
+    }
+
+    let max = distances.iter().cloned().fold(0.0_f64, f64::max);
+    let mut histogram = vec![0.0_f64; bins];
+    if max <= 0.0 {
+        return (histogram, 0.0);
+    }
+
+    for d in &distances {
+        histogram[bin_of(d / max, 1.0, bins)] += 1.0;
+    }
+    normalise(&mut histogram);
+    (histogram, max)
+}
+
+fn curvature_histogram(points: &[Vec3], triangles: &[[usize; 3]], bins: usize) -> Vec<f64> {
+    let mut histogram = vec![0.0_f64; bins];
+    let area = geometry::surface_area_nm2(points, triangles);
+    if area <= 0.0 {
+        return histogram;
+    }
+
+    // Radius of the sphere with the same area makes |H| dimensionless.
+    let radius = (area / (4.0 * std::f64::consts::PI)).sqrt();
+
+    let mut vertex_area = vec![0.0_f64; points.len()];
+    for t in triangles {
+        let a = geometry::triangle_area(points[t[0]], points[t[1]], points[t[2]]);
+        for &i in t {
+            vertex_area[i] += a / 3.0;
+        }
+    }
+
+    for (i, h) in geometry::vertex_mean_curvatures(points, triangles)
+        .into_iter()
+        .enumerate()
+    {
+        // Boundary vertices count as flat so open patches still get a signature.
+        let h = h.unwrap_or(0.0);
+        histogram[bin_of(h * radius, CURVATURE_RANGE, bins)] += vertex_area[i];
+    }
+    normalise(&mut histogram);
+    histogram
+}
+
+/// Area-weighted, deterministic point samples on a triangle mesh.
+pub fn sample_surface(
+    points: &[Vec3],
+    triangles: &[[usize; 3]],
+    count: usize,
+    seed: u64,
+) -> Vec<Vec3> {
+    let mut cumulative = Vec::with_capacity(triangles.len());
+    let mut total = 0.0_f64;
+    for t in triangles {
+        total += geometry::triangle_area(points[t[0]], points[t[1]], points[t[2]]);
+        cumulative.push(total);
+    }
+    if total <= 0.0 {
+        return triangles.iter().map(|t| points[t[0]]).collect();
+    }
+
+    let mut state = seed;
+    let mut samples = Vec::with_capacity(count);
+    for _ in 0..count {
+        let pick = unit_random(&mut state) * total;
+        let idx = cumulative
+            .partition_point(|&c| c < pick)
+            .min(triangles.len() - 1);
+        let t = triangles[idx];
+
+        // Uniform barycentric sample.
+        let r1 = unit_random(&mut state).sqrt();
+        let r2 = unit_random(&mut state);
+        let a = geometry::scale(points[t[0]], 1.0 - r1);
+        let b = geometry::scale(points[t[1]], r1 * (1.0 - r2));
+        let c = geometry::scale(points[t[2]], r1 * r2);
+        samples.push(geometry::add(geometry::add(a, b), c));
+    }
+    samples
+}
+
+fn total_variation(a: &[f64], b: &[f64]) -> f64 {
+    let n = a.len().max(b.len());
+    let sum: f64 = (0..n)
+        .map(|i| (a.get(i).unwrap_or(&0.0) - b.get(i).unwrap_or(&0.0)).abs())
+        .sum();
+    (0.5 * sum).clamp(0.0, 1.0)
+}
+
+fn bin_of(value: f64, range: f64, bins: usize) -> usize {
+    let scaled = (value / range * bins as f64).floor();
+    if scaled.is_finite() && scaled > 0.0 {
+        (scaled as usize).min(bins - 1)
+    } else {
+        0
+    }
+}
+
+fn normalise(histogram: &mut [f64]) {
+    let total: f64 = histogram.iter().sum();
+    if total > 0.0 {
+        for h in histogram.iter_mut() {
+            *h /= total;
+        }
+    }
+}
+
+// SplitMix64 step mapped to [0, 1).
+fn unit_random(state: &mut u64) -> f64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^= z >> 31;
+    (z >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xr_lab_grid::nanopoly::generators::{self, PlatonicSolid};
+    use crate::xr_lab_grid::nanopoly::nanopolygon::{BioAffinityTarget, BiophysicalMetadata};
+
+    fn bio() -> BiophysicalMetadata {
+        BiophysicalMetadata {
+            target: BioAffinityTarget::GlialCell,
+            zeta_potential_mv: -20.0,
+            hydrophobicity_index: 0.3,
+            elastic_modulus_kpa: 1.0,
+            ligands: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn d2_of_square_corners() {
+        // Four sides of 1 and two diagonals of sqrt(2); sides land at 1/sqrt(2).
+        let corners = [
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [0.0, 1.0, 0.0],
+        ];
+        let (histogram, scale) = d2_histogram(&corners, 4);
+        assert!((scale - 2f64.sqrt()).abs() < 1e-12);
+        assert_eq!(histogram, vec![0.0, 0.0, 4.0 / 6.0, 2.0 / 6.0]);
+
+        let (empty, zero) = d2_histogram(&[[1.0, 2.0, 3.0]], 4);
+        assert_eq!((empty, zero), (vec![0.0; 4], 0.0));
+    }
+
+    #[test]
+    fn sphere_curvature_sits_at_one() {
+        let sphere = generators::icosphere("s", 25.0, 3, bio()).unwrap();
+        let d = ShapeDescriptor::from_nanopolygon(&sphere, DEFAULT_BINS);
+        assert!((d.curvature_histogram.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!((d.d2_histogram.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        // |H| r = 1 is the lower edge of bin 8 of 32 over [0, 4); the
+        // faceted sphere reads just under it.
+        assert!(
+            d.curvature_histogram[7] > 0.95,
+            "{:?}",
+            d.curvature_histogram
+        );
+        let near_one: f64 = d.curvature_histogram[7..=9].iter().sum();
+        assert!((near_one - 1.0).abs() < 1e-12);
+        // Sampled diameter, slightly under 50 nm.
+        assert!(d.scale_nm > 45.0 && d.scale_nm <= 50.0);
+    }
+
+    #[test]
+    fn descriptor_ignores_size() {
+        let small = generators::capped_rod("a", 2.0, 10.0, 12, bio()).unwrap();
+        let large = generators::capped_rod("b", 6.0, 30.0, 12, bio()).unwrap();
+        let a = ShapeDescriptor::from_nanopolygon(&small, DEFAULT_BINS);
+        let b = ShapeDescriptor::from_nanopolygon(&large, DEFAULT_BINS);
+        assert!(a.distance(&b) < 1e-9);
+        assert!((b.scale_nm / a.scale_nm - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn signature_round_trip() {
+        let cube = generators::platonic("c", PlatonicSolid::Cube, 10.0, bio()).unwrap();
+        let d = ShapeDescriptor::from_nanopolygon(&cube, 8);
+        let signature = d.signature();
+        assert_eq!(signature.len(), 16);
+        let back = ShapeDescriptor::from_signature(&signature, d.scale_nm).unwrap();
+        assert_eq!(back.d2_histogram, d.d2_histogram);
+        assert_eq!(back.curvature_histogram, d.curvature_histogram);
+        assert_eq!(d.distance(&back), 0.0);
+        assert!(ShapeDescriptor::from_signature(&signature[1..], 1.0).is_none());
+        assert!(ShapeDescriptor::from_signature(&[], 1.0).is_none());
+    }
+
+    #[test]
+    fn disjoint_histograms_are_distance_one() {
+        let a = ShapeDescriptor {
+            d2_histogram: vec![1.0, 0.0],
+            curvature_histogram: vec![1.0, 0.0],
+            scale_nm: 1.0,
+        };
+        let b = ShapeDescriptor {
+            d2_histogram: vec![0.0, 1.0],
+            curvature_histogram: vec![0.0, 1.0],
+            scale_nm: 1.0,
+        };
+        assert_eq!(a.distance(&b), 1.0);
+    }
+
+    #[test]
+    fn index_ranks_closest_shape_first() {
+        let mut index = ShapeIndex::new(DEFAULT_BINS);
+        index.insert(&generators::platonic("cube", PlatonicSolid::Cube, 10.0, bio()).unwrap());
+        index.insert(&generators::icosphere("ball", 10.0, 2, bio()).unwrap());
+        index.insert(&generators::capped_rod("rod", 2.0, 30.0, 12, bio()).unwrap());
+        // Re-inserting an id replaces the entry.
+        index.insert(&generators::icosphere("ball", 12.0, 2, bio()).unwrap());
+        assert_eq!(index.entries.len(), 3);
+
+        let query = generators::icosphere("q", 40.0, 2, bio()).unwrap();
+        let ranked = index.rank(&query, 2);
+        assert_eq!(ranked.len(), 2);
+        assert_eq!(ranked[0].poly_id, "ball");
+        assert!(ranked[0].distance < 1e-9);
+
+        let near = index.near_duplicates(&query, 0.05);
+        assert_eq!(near.len(), 1);
+        index.remove("ball");
+        assert!(index.near_duplicates(&query, 0.05).is_empty());
+    }
+}
@@ -0,0 +1,588 @@
+use std::f64::consts::PI;
+use std::fmt::Write as _;
+
+use safety_core::policy::HardLimits;
+use safety_core::tsafe_cortex_gate::TsafeCortexGate;
+use safety_core::types::{AggregatedSafetyState, BioLoadFlag, SafetyState, SwarmMode};
+
+use super::energy::EnergyModel;
+use super::geometry::{self, Vec3};
+use super::lifecycle::MemberState;
+use super::nanopolygon::GeometricDescriptors;
+use super::nanoswarm::Nanoswarm;
+
+const BOLTZMANN_J_PER_K: f64 = 1.380649e-23;
+
+#[derive(Clone, Debug)]
+pub struct SimulationSettings {
+    /// Runs with the same seed, settings and swarm produce identical timelines.
+    pub seed: u64,
+    pub dt_s: f64,
+    pub temperature_k: f64,
+    /// Viscosity of the surrounding fluid (interstitial fluid ~3 mPa s).
+    pub viscosity_pa_s: f64,
+    /// Share of free Stokes-Einstein diffusion left after binding and
+    /// tissue hindrance.
+    pub mobility: f64,
+    pub initial_lifeforce: f32,
+    /// Rate at which lifeforce recovers toward 1 when unstrained.
+    pub lifeforce_recovery_per_s: f32,
+    /// Lifeforce lost per second per unit of D/DW above the host's warning levels.
+    pub lifeforce_strain_per_s: f32,
+    pub k: f32,
+    /// Rights-of-Humanity pressure of the session, held constant.
+    pub roh: f32,
+    pub limits: HardLimits,
+    /// Ticks every member is held at basal draw after the gate enforces
+    /// rollback; 0 leaves the swarm running.
+    pub rollback_hold_ticks: u64,
+}
+
+impl Default for SimulationSettings {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            dt_s: 0.01,
+            temperature_k: 310.15,
+            viscosity_pa_s: 3.0e-3,
+            mobility: 0.01,
+            initial_lifeforce: 0.9,
+            lifeforce_recovery_per_s: 0.05,
+            lifeforce_strain_per_s: 2.0,
+            k: 0.85,
+            roh: 0.1,
+            limits: HardLimits::clinical_default(),
+            rollback_hold_ticks: 50,
+        }
+    }
+}
+
+/// Swarm state after one step.
+#[derive(Clone, Debug)]
+pub struct TickRecord {
+    pub tick: u64,
+    pub time_s: f64,
+    /// Draw of each member this tick, before perfusion weighting; 0 once retired.
+    pub member_draw_uw: Vec<f64>,
+    /// Members running at peak draw this tick.
+    pub member_active: Vec<bool>,
+    /// Member translations in the lab frame.
+    pub positions_nm: Vec<Vec3>,
+    /// Swarm D, DW and lifeforce as seen by the gate.
+    pub d: f32,
+    pub dw: f32,
+    pub lifeforce: f32,
+    pub mode: SwarmMode,
+    pub reason: &'static str,
+}
+
+#[derive(Clone, Debug)]
+pub struct SimulationTimeline {
+    pub seed: u64,
+    pub dt_s: f64,
+    pub member_ids: Vec<String>,
+    pub ticks: Vec<TickRecord>,
+}
+
+impl SimulationTimeline {
+    pub fn d_series(&self) -> Vec<(f64, f32)> {
+        self.ticks.iter().map(|t| (t.time_s, t.d)).collect()
+    }
+
+    pub fn dw_series(&self) -> Vec<(f64, f32)> {
+        self.ticks.iter().map(|t| (t.time_s, t.dw)).collect()
+    }
+
+    pub fn lifeforce_series(&self) -> Vec<(f64, f32)> {
+        self.ticks.iter().map(|t| (t.time_s, t.lifeforce)).collect()
+    }
+
+    pub fn first_rollback(&self) -> Option<&TickRecord> {
+        self.ticks.iter().find(|t| t.mode == SwarmMode::Rollback)
+    }
+
+    pub fn rollback_ticks(&self) -> usize {
+        self.ticks
+            .iter()
+            .filter(|t| t.mode == SwarmMode::Rollback)
+            .count()
+    }
+
+    /// One row per tick: time, D, DW, lifeforce, total draw and gate decision.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("tick,time_s,d,dw,lifeforce,total_draw_uW,mode,reason\n");
+        for t in &self.ticks {
+            let total: f64 = t.member_draw_uw.iter().sum();
+            let _ = writeln!(
+                out,
+                "{},{},{},{},{},{},{:?},{}",
+                t.tick, t.time_s, t.d, t.dw, t.lifeforce, total, t.mode, t.reason
+            );
+        }
+        out
+    }
+}
+
+/// Advances a swarm in fixed steps: Brownian member motion, duty-cycled
+/// draw, D/DW from the swarm's energy and drift models, lifeforce strain
+/// and recovery, and a Tsafe gate decision every tick.
+pub struct NanoswarmSimulator {
+    pub swarm: Nanoswarm,
+    pub settings: SimulationSettings,
+    gate: TsafeCortexGate,
+    rng: SplitMix64,
+    tick: u64,
+    lifeforce: f32,
+    mode: SwarmMode,
+    throttled_until: u64,
+    // Geometry does not change during a run; one entry per member,
+    // retired or not.
+    descriptors: Vec<GeometricDescriptors>,
+    step_sigma_nm: Vec<f64>,
+}
+
+impl NanoswarmSimulator {
+    pub fn new(swarm: Nanoswarm, settings: SimulationSettings) -> Self {
+        let descriptors = swarm
+            .members
+            .iter()
+            .map(|m| m.poly().geometric_descriptors())
+            .collect();
+        let step_sigma_nm = swarm
+            .members
+            .iter()
+            .map(|m| step_sigma_nm(&m.poly().points(), &settings))
+            .collect();
+        Self {
+            gate: TsafeCortexGate::new(settings.limits.clone()),
+            rng: SplitMix64::new(settings.seed),
+            tick: 0,
+            lifeforce: settings.initial_lifeforce.clamp(0.0, 1.0),
+            mode: SwarmMode::Normal,
+            throttled_until: 0,
+            descriptors,
+            step_sigma_nm,
+            swarm,
+            settings,
+        }
+    }
+
+    pub fn step(&mut self) -> TickRecord {
+        self.tick += 1;
+        let dt = self.settings.dt_s;
+
+        for (member, &sigma) in self.swarm.members.iter_mut().zip(&self.step_sigma_nm) {
+            // Retired members are inert and stay where they were left.
+            if member.lifecycle.state == MemberState::Retired {
+                continue;
+            }
+            let jump = [
+                sigma * self.rng.normal(),
+                sigma * self.rng.normal(),
+                sigma * self.rng.normal(),
+            ];
+            member.pose.translation_nm = geometry::add(member.pose.translation_nm, jump);
+        }
+
+        let throttled = self.tick <= self.throttled_until;
+        let duty = if throttled {
+            0.0
+        } else {
+            self.swarm.host_budget.duty_cycle_max
+        };
+        let member_active: Vec<bool> = self
+            .swarm
+            .members
+            .iter()
+            .map(|m| {
+                let draw = self.rng.next_f64();
+                m.lifecycle.is_active() && draw < duty as f64
+            })
+            .collect();
+
+        let mut member_draw_uw = Vec::with_capacity(member_active.len());
+        let mut total_load_uw = 0.0;
+        let mut member_dw = Vec::with_capacity(member_active.len());
+        for (member, &active) in self.swarm.members.iter().zip(&member_active) {
+            if member.lifecycle.state == MemberState::Retired {
+                member_draw_uw.push(0.0);
+                continue;
+            }
+            // This tick the member runs either at peak or at basal.
+            let energy = self
+                .swarm
+                .energy_model
+                .member_at_duty(member, if active { 1.0 } else { 0.0 });
+            total_load_uw += energy.load_uw;
+            member_draw_uw.push(energy.mean_draw_uw);
+            // Drift follows sustained activity rather than single bursts.
+            if member.lifecycle.is_active() {
+                member_dw.push(self.swarm.drift_model.member(&member.object, duty).dw);
+            }
+        }
+
+        let budget = &self.swarm.host_budget;
+        let energy_d = EnergyModel::demand_d(total_load_uw, budget);
+        let drift_dw = 1.0 - member_dw.iter().fold(1.0, |acc, dw| acc * (1.0 - dw));
+        let (spacing_d, spacing_dw) = self.swarm.spacing_load(&self.swarm.spacing_report());
+        let d = (energy_d + spacing_d).clamp(0.0, 1.0);
+        let dw = (drift_dw + spacing_dw).clamp(0.0, 1.0);
+
+        let strain = (d - budget.d_warn).max(0.0) + (dw - budget.dw_warn).max(0.0);
+        let recovery = self.settings.lifeforce_recovery_per_s * (1.0 - self.lifeforce);
+        self.lifeforce = (self.lifeforce
+            + dt as f32 * (recovery - self.settings.lifeforce_strain_per_s * strain))
+            .clamp(0.0, 1.0);
+
+        // Same combined ceilings as the policy verdict.
+        let d_max = budget.d_max.min(self.swarm.max_energy_d);
+        let dw_max = budget.dw_max.min(self.swarm.max_dw);
+        let bio_flag = if d > d_max || dw > dw_max {
+            BioLoadFlag::Violation
+        } else if d > budget.d_warn.min(d_max) || dw > budget.dw_warn.min(dw_max) {
+            BioLoadFlag::Caution
+        } else {
+            BioLoadFlag::Normal
+        };
+        let base = SafetyState::new(
+            self.settings.k,
+            d,
+            dw,
+            self.lifeforce,
+            self.settings.roh,
+            bio_flag,
+            self.mode.clone(),
+        );
+        // Retired geometry no longer reaches the gate.
+        let states: Vec<SafetyState> = self
+            .swarm
+            .members
+            .iter()
+            .zip(&self.descriptors)
+            .filter(|(m, _)| m.lifecycle.state != MemberState::Retired)
+            .map(|(_, g)| g.adjust_safety(&base))
+            .collect();
+        let agg = if states.is_empty() {
+            AggregatedSafetyState::from_instances(&[base])
+        } else {
+            AggregatedSafetyState::from_instances(&states)
+        };
+        let decision = self.gate.evaluate(&agg);
+        self.mode = decision.enforced_mode.clone();
+        if self.mode == SwarmMode::Rollback {
+            self.throttled_until = self.tick + self.settings.rollback_hold_ticks;
+        }
+
+        TickRecord {
+            tick: self.tick,
+            time_s: self.tick as f64 * dt,
+            member_draw_uw,
+            member_active,
+            positions_nm: self
+                .swarm
+                .members
+                .iter()
+                .map(|m| m.pose.translation_nm)
+                .collect(),
+            d: agg.avg_d,
+            dw: agg.avg_dw,
+            lifeforce: agg.min_lifeforce.0,
+            mode: decision.enforced_mode,
+            reason: decision.reason,
+        }
+    }
+
+    pub fn run(&mut self, ticks: u64) -> SimulationTimeline {
+        SimulationTimeline {
+            seed: self.settings.seed,
+            dt_s: self.settings.dt_s,
+            member_ids: self
+                .swarm
+                .members
+                .iter()
+                .map(|m| m.object.id.clone())
+                .collect(),
+            ticks: (0..ticks).map(|_| self.step()).collect(),
+        }
+    }
+}
+
+impl Nanoswarm {
+    /// Run a copy of the swarm for `ticks` steps; the swarm itself is untouched.
+    pub fn simulate(&self, ticks: u64, settings: SimulationSettings) -> SimulationTimeline {
+        NanoswarmSimulator::new(self.clone(), settings).run(ticks)
+    }
+}
+
+// Per-axis standard deviation of one Brownian step, from the Stokes-Einstein
+// diffusion coefficient of the member's bounding sphere.
+fn step_sigma_nm(points: &[Vec3], settings: &SimulationSettings) -> f64 {
+    let n = points.len().max(1) as f64;
+    let centroid = geometry::scale(
+        points
+            .iter()
+            .fold([0.0; 3], |acc, &p| geometry::add(acc, p)),
+        1.0 / n,
+    );
+    let radius_nm = points
+        .iter()
+        .map(|&p| geometry::norm(geometry::sub(p, centroid)))
+        .fold(0.0, f64::max);
+    if radius_nm <= 0.0 || settings.viscosity_pa_s <= 0.0 {
+        return 0.0;
+    }
+    let diffusion_m2_s = BOLTZMANN_J_PER_K * settings.temperature_k
+        / (6.0 * PI * settings.viscosity_pa_s * radius_nm * 1e-9);
+    let diffusion_nm2_s = diffusion_m2_s * 1e18 * settings.mobility.max(0.0);
+    (2.0 * diffusion_nm2_s * settings.dt_s.max(0.0)).sqrt()
+}
+
+// SplitMix64: small, fast and reproducible across platforms.
+#[derive(Clone, Debug)]
+struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in [0, 1).
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Standard normal by Box-Muller.
+    fn normal(&mut self) -> f64 {
+        let u1 = 1.0 - self.next_f64();
+        let u2 = self.next_f64();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xr_lab_grid::nanopoly::core::nanopoly_object::{
+        BciInterface, EnergeticProfile, NanopolyObject,
+    };
+    use crate::xr_lab_grid::nanopoly::generators;
+    use crate::xr_lab_grid::nanopoly::nanopolygon::{BioAffinityTarget, BiophysicalMetadata};
+    use crate::xr_lab_grid::nanopoly::nanoswarm::NanoswarmMember;
+    use crate::xr_lab_grid::nanopoly::transform::{Quaternion, RigidTransform};
+
+    const BASAL_UW: f64 = 1000.0;
+    const TURNOVER_UW: f64 = 100.0;
+
+    // Five 10 nm spheres on neural membrane, 100 nm apart along x.
+    fn swarm(peak_uw: f64) -> Nanoswarm {
+        let bio = BiophysicalMetadata {
+            target: BioAffinityTarget::NeuralMembrane,
+            zeta_potential_mv: -30.0,
+            hydrophobicity_index: 0.25,
+            elastic_modulus_kpa: 0.5,
+            ligands: Vec::new(),
+        };
+        let mut swarm = Nanoswarm::new("sim");
+        for i in 0..5 {
+            let id = format!("m{}", i);
+            let poly = generators::icosphere(&id, 10.0, 1, bio.clone()).unwrap();
+            let energy = EnergeticProfile {
+                basal_glucose_uW: BASAL_UW,
+                peak_glucose_uW: peak_uw,
+                protein_turnover_uW: TURNOVER_UW,
+                hemodynamic_coupling: 0.3,
+            };
+            let object = NanopolyObject::from_parts(
+                &id,
+                poly,
+                energy,
+                BciInterface::none(),
+                swarm.governance.clone(),
+            );
+            let pose = RigidTransform::new(Quaternion::identity(), [100.0 * i as f64, 0.0, 0.0]);
+            swarm
+                .add_member(NanoswarmMember::new(object, pose))
+                .unwrap();
+        }
+        swarm
+    }
+
+    fn seeded(seed: u64) -> SimulationSettings {
+        SimulationSettings {
+            seed,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn same_seed_same_timeline() {
+        let swarm = swarm(1500.0);
+        let a = swarm.simulate(200, seeded(7));
+        let b = swarm.simulate(200, seeded(7));
+        let c = swarm.simulate(200, seeded(8));
+
+        assert_eq!(a.to_csv(), b.to_csv());
+        for (x, y) in a.ticks.iter().zip(&b.ticks) {
+            assert_eq!(x.positions_nm, y.positions_nm);
+            assert_eq!(x.member_active, y.member_active);
+        }
+        assert_ne!(a.to_csv(), c.to_csv());
+        assert_ne!(
+            a.ticks.last().unwrap().positions_nm,
+            c.ticks.last().unwrap().positions_nm
+        );
+    }
+
+    #[test]
+    fn rollback_holds_members_at_basal_draw() {
+        let settings = seeded(1);
+        let hold = settings.rollback_hold_ticks as usize;
+        let timeline = swarm(400_000.0).simulate(300, settings);
+
+        let first = timeline
+            .first_rollback()
+            .expect("heavy swarm should roll back");
+        // Ticks are numbered from 1, so index `tick` is the step after.
+        let start = first.tick as usize;
+        let held = &timeline.ticks[start..(start + hold).min(timeline.ticks.len())];
+        assert!(!held.is_empty());
+        for t in held {
+            assert!(t.member_active.iter().all(|&a| !a));
+            for &draw in &t.member_draw_uw {
+                assert_eq!(draw, BASAL_UW + TURNOVER_UW);
+            }
+        }
+        // The rollback tick itself was drawn before the gate spoke.
+        assert!(timeline.ticks[..start]
+            .iter()
+            .any(|t| t.member_active.iter().any(|&a| a)));
+    }
+
+    #[test]
+    fn no_hold_lets_members_run_after_rollback() {
+        let settings = SimulationSettings {
+            rollback_hold_ticks: 0,
+            ..seeded(1)
+        };
+        let timeline = swarm(400_000.0).simulate(300, settings);
+        let start = timeline.first_rollback().unwrap().tick as usize;
+        assert!(timeline.ticks[start..]
+            .iter()
+            .any(|t| t.member_active.iter().any(|&a| a)));
+    }
+
+    #[test]
+    fn quarantined_member_stays_at_basal_draw() {
+        let mut swarm = swarm(1500.0);
+        swarm.quarantine_member("m2", 0, "test").unwrap();
+        let timeline = swarm.simulate(200, seeded(3));
+
+        assert!(timeline
+            .ticks
+            .iter()
+            .any(|t| t.member_active.iter().any(|&a| a)));
+        for t in &timeline.ticks {
+            assert!(!t.member_active[2]);
+            assert_eq!(t.member_draw_uw[2], BASAL_UW + TURNOVER_UW);
+        }
+    }
+
+    // Same swarm with the duty cycle cut to zero, so every member sits at
+    // basal draw and each tick is deterministic apart from motion.
+    fn idle(peak_uw: f64) -> Nanoswarm {
+        let mut swarm = swarm(peak_uw);
+        swarm.host_budget.duty_cycle_max = 0.0;
+        swarm
+    }
+
+    #[test]
+    fn tick_demand_matches_the_energy_model() {
+        let swarm = idle(1500.0);
+        let expected = swarm.energy_report().demand_d;
+        assert!(expected > 0.0);
+        let timeline = swarm.simulate(20, seeded(5));
+        for t in &timeline.ticks {
+            assert!((t.d - expected).abs() < 1e-6, "{} vs {}", t.d, expected);
+            assert!(t.member_active.iter().all(|&a| !a));
+        }
+        assert_eq!(timeline.rollback_ticks(), 0);
+    }
+
+    #[test]
+    fn retired_member_is_inert() {
+        let mut swarm = swarm(1500.0);
+        swarm.retire_member("m4", 0, "test").unwrap();
+        let start = swarm.members[4].pose.translation_nm;
+        let timeline = swarm.simulate(100, seeded(9));
+
+        for t in &timeline.ticks {
+            assert_eq!(t.positions_nm[4], start);
+            assert_eq!(t.member_draw_uw[4], 0.0);
+            assert!(!t.member_active[4]);
+        }
+        // The others keep diffusing.
+        assert_ne!(timeline.ticks.last().unwrap().positions_nm[0], [0.0; 3]);
+    }
+
+    #[test]
+    fn retired_geometry_does_not_reach_the_gate() {
+        let bio = BiophysicalMetadata {
+            target: BioAffinityTarget::NeuralMembrane,
+            zeta_potential_mv: -30.0,
+            hydrophobicity_index: 0.25,
+            elastic_modulus_kpa: 0.5,
+            ligands: Vec::new(),
+        };
+        let needle = generators::capped_rod("n", 1.0, 40.0, 12, bio.clone()).unwrap();
+        let risk = needle.geometric_descriptors().toxicity_risk();
+        let mut swarm = idle(1500.0);
+        let object = NanopolyObject::from_parts(
+            "n",
+            needle,
+            EnergeticProfile::constant(BASAL_UW),
+            BciInterface::none(),
+            swarm.governance.clone(),
+        );
+        let pose = RigidTransform::new(Quaternion::identity(), [0.0, 1000.0, 0.0]);
+        swarm
+            .add_member(NanoswarmMember::new(object, pose))
+            .unwrap();
+
+        let with_needle = swarm.simulate(1, seeded(2)).ticks[0].clone();
+        swarm.retire_member("n", 0, "test").unwrap();
+        let retired = swarm.simulate(1, seeded(2)).ticks[0].clone();
+        let without = idle(1500.0).simulate(1, seeded(2)).ticks[0].clone();
+
+        // Counted, the needle adds 0.2 x risk of DW to one of six states.
+        assert!(with_needle.dw > without.dw);
+        assert!(with_needle.lifeforce < without.lifeforce);
+        assert!(risk > 0.0);
+        // Retired, it neither draws nor shifts the averages.
+        assert!((retired.d - without.d).abs() < 1e-6);
+        assert!((retired.dw - without.dw).abs() < 1e-6);
+        assert_eq!(retired.lifeforce, without.lifeforce);
+    }
+
+    #[test]
+    fn swarm_ceiling_tightens_the_bio_flag() {
+        let mut swarm = idle(1500.0);
+        let d = swarm.simulate(1, seeded(4)).ticks[0].d;
+        assert_eq!(swarm.simulate(50, seeded(4)).rollback_ticks(), 0);
+
+        // Well inside the host budget, but over the swarm's own limit.
+        swarm.max_energy_d = 0.5 * d;
+        let timeline = swarm.simulate(50, seeded(4));
+        assert_eq!(timeline.rollback_ticks(), 50);
+        assert_eq!(timeline.ticks[0].reason, "member_violation_flag");
+    }
+}
@@ -0,0 +1,903 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::bounds::Aabb;
+use super::geometry::{self, Vec3};
+use super::lifecycle::MemberState;
+use super::nanopolygon::Nanopolygon;
+use super::nanoswarm::{Nanoswarm, NanoswarmMember};
+use super::transform::RigidTransform;
+
+// Items per BVH leaf.
+const LEAF_SIZE: usize = 4;
+
+// Separations below this (nm) count as contact.
+const CONTACT_TOLERANCE_NM: f64 = 1e-9;
+
+/// Bounding volume hierarchy over a set of boxes, split at the median of the
+/// longest centroid axis.
+#[derive(Clone, Debug)]
+pub struct Bvh {
+    pub nodes: Vec<BvhNode>,
+    /// Item indices, grouped so each leaf owns a contiguous run.
+    pub items: Vec<usize>,
+    /// Box of each item, by item index.
+    pub item_bounds: Vec<Aabb>,
+}
+
+#[derive(Clone, Debug)]
+pub struct BvhNode {
+    pub bounds: Aabb,
+    pub kind: BvhNodeKind,
+}
+
+#[derive(Clone, Debug)]
+pub enum BvhNodeKind {
+    Leaf { start: usize, count: usize },
+    Branch { left: usize, right: usize },
+}
+
+impl Bvh {
+    pub fn build(boxes: &[Aabb]) -> Self {
+        let mut bvh = Self {
+            nodes: Vec::new(),
+            items: (0..boxes.len()).collect(),
+            item_bounds: boxes.to_vec(),
+        };
+        if !boxes.is_empty() {
+            bvh.build_node(boxes, 0, boxes.len());
+        }
+        bvh
+    }
+
+    fn build_node(&mut self, boxes: &[Aabb], start: usize, end: usize) -> usize {
+        let slice = &mut self.items[start..end];
+        let bounds = slice[1..]
+            .iter()
+            .fold(boxes[slice[0]], |acc, &i| acc.union(&boxes[i]));
+
+        let node = self.nodes.len();
+        self.nodes.push(BvhNode {
+            bounds,
+            kind: BvhNodeKind::Leaf {
+                start,
+                count: end - start,
+            },
+        });
+        if end - start <= LEAF_SIZE {
+            return node;
+        }
+
+        let centres: Vec<Vec3> = slice.iter().map(|&i| boxes[i].center_nm()).collect();
+        let spread = Aabb::from_points(&centres)
+            .map(|b| b.size_nm())
+            .unwrap_or([0.0; 3]);
+        let axis = (0..3)
+            .max_by(|&a, &b| spread[a].total_cmp(&spread[b]))
+            .unwrap_or(0);
+
+        let mid = (end - start) / 2;
+        slice.select_nth_unstable_by(mid, |&a, &b| {
+            boxes[a].center_nm()[axis]
+                .total_cmp(&boxes[b].center_nm()[axis])
+                .then_with(|| a.cmp(&b))
+        });
+
+        let left = self.build_node(boxes, start, start + mid);
+        let right = self.build_node(boxes, start + mid, end);
+        self.nodes[node].kind = BvhNodeKind::Branch { left, right };
+        node
+    }
+
+    pub fn bounds(&self) -> Option<Aabb> {
+        self.nodes.first().map(|n| n.bounds)
+    }
+
+    /// Items whose box intersects `region`, in ascending order.
+    pub fn query(&self, region: &Aabb) -> Vec<usize> {
+        let mut out = Vec::new();
+        let mut stack = Vec::new();
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+        while let Some(n) = stack.pop() {
+            let node = &self.nodes[n];
+            if !node.bounds.intersects(region) {
+                continue;
+            }
+            match node.kind {
+                BvhNodeKind::Leaf { start, count } => out.extend(
+                    self.items[start..start + count]
+                        .iter()
+                        .filter(|&&i| self.item_bounds[i].intersects(region)),
+                ),
+                BvhNodeKind::Branch { left, right } => {
+                    stack.push(left);
+                    stack.push(right);
+                }
+            }
+        }
+        out.sort_unstable();
+        out
+    }
+
+    /// Branch-and-bound nearest search. `lower_bound` must never exceed the exact
+    /// distance of any item inside a node; `exact` gives an item's distance.
+    /// Returns the nearest item closer than `within`, if any.
+    pub fn nearest<L, E>(&self, lower_bound: L, mut exact: E, within: f64) -> Option<(usize, f64)>
+    where
+        L: Fn(&Aabb) -> f64,
+        E: FnMut(usize) -> f64,
+    {
+        let mut best: Option<(usize, f64)> = None;
+        let mut limit = within;
+        let mut stack = Vec::new();
+        if let Some(root) = self.nodes.first() {
+            stack.push((0, lower_bound(&root.bounds)));
+        }
+        while let Some((n, bound)) = stack.pop() {
+            if bound >= limit {
+                continue;
+            }
+            match self.nodes[n].kind {
+                BvhNodeKind::Leaf { start, count } => {
+                    for &item in &self.items[start..start + count] {
+                        let d = exact(item);
+                        if d < limit {
+                            limit = d;
+                            best = Some((item, d));
+                        }
+                    }
+                }
+                BvhNodeKind::Branch { left, right } => {
+                    let bl = lower_bound(&self.nodes[left].bounds);
+                    let br = lower_bound(&self.nodes[right].bounds);
+                    // Push the farther child first so the nearer one is searched first.
+                    if bl <= br {
+                        stack.push((right, br));
+                        stack.push((left, bl));
+                    } else {
+                        stack.push((left, bl));
+                        stack.push((right, br));
+                    }
+                }
+            }
+        }
+        best
+    }
+}
+
+/// A member's surface placed in the lab frame, with its own triangle BVH.
+/// Members without triangles keep their edges (or bare vertices) as
+/// degenerate triangles so they still take part in distance queries.
+#[derive(Clone, Debug)]
+pub struct PlacedMesh {
+    pub points: Vec<Vec3>,
+    pub triangles: Vec<[usize; 3]>,
+    pub closed: bool,
+    pub bounds: Aabb,
+    pub bvh: Bvh,
+}
+
+impl PlacedMesh {
+    pub fn from_member(member: &NanoswarmMember) -> Option<Self> {
+        Self::from_nanopolygon(member.poly(), &member.pose)
+    }
+
+    pub fn from_nanopolygon(poly: &Nanopolygon, pose: &RigidTransform) -> Option<Self> {
+        let points: Vec<Vec3> = poly.points().into_iter().map(|p| pose.apply(p)).collect();
+        let mut triangles = poly.triangulate();
+        let closed = !triangles.is_empty() && poly.is_closed();
+        if triangles.is_empty() {
+            triangles = poly
+                .edges
+                .iter()
+                .filter(|e| e.start_index < points.len() && e.end_index < points.len())
+                .map(|e| [e.start_index, e.end_index, e.end_index])
+                .collect();
+        }
+        if triangles.is_empty() {
+            triangles = (0..points.len()).map(|i| [i, i, i]).collect();
+        }
+
+        let bounds = Aabb::from_points(&points)?;
+        let boxes: Vec<Aabb> = triangles
+            .iter()
+            .filter_map(|t| Aabb::from_points(&[points[t[0]], points[t[1]], points[t[2]]]))
+            .collect();
+        let bvh = Bvh::build(&boxes);
+        Some(Self {
+            points,
+            triangles,
+            closed,
+            bounds,
+            bvh,
+        })
+    }
+
+    fn corners(&self, t: usize) -> [Vec3; 3] {
+        let t = self.triangles[t];
+        [self.points[t[0]], self.points[t[1]], self.points[t[2]]]
+    }
+
+    fn triangle_bounds(&self, t: usize) -> Aabb {
+        let [a, b, c] = self.corners(t);
+        Aabb {
+            min_nm: [
+                a[0].min(b[0]).min(c[0]),
+                a[1].min(b[1]).min(c[1]),
+                a[2].min(b[2]).min(c[2]),
+            ],
+            max_nm: [
+                a[0].max(b[0]).max(c[0]),
+                a[1].max(b[1]).max(c[1]),
+                a[2].max(b[2]).max(c[2]),
+            ],
+        }
+    }
+
+    /// Distance from `p` to the nearest point of the surface, in nm.
+    pub fn distance_to_point(&self, p: Vec3) -> f64 {
+        let probe = Aabb {
+            min_nm: p,
+            max_nm: p,
+        };
+        self.bvh
+            .nearest(
+                |b| b.distance_to(&probe),
+                |t| {
+                    let [a, b, c] = self.corners(t);
+                    let q = geometry::closest_point_on_triangle(p, a, b, c);
+                    geometry::norm(geometry::sub(p, q))
+                },
+                f64::INFINITY,
+            )
+            .map_or(f64::INFINITY, |(_, d)| d)
+    }
+
+    /// True when `p` lies inside this closed surface.
+    pub fn contains_point(&self, p: Vec3) -> bool {
+        self.closed && winding_number(p, &self.points, &self.triangles).abs() > 0.5
+    }
+
+    /// Narrow phase: any surface contact, or one mesh enclosed by the other.
+    pub fn intersects(&self, other: &PlacedMesh) -> bool {
+        if !self.bounds.intersects(&other.bounds) {
+            return false;
+        }
+        let (small, large) = if self.triangles.len() <= other.triangles.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        for t in 0..small.triangles.len() {
+            let tri = small.corners(t);
+            for u in large.bvh.query(&small.triangle_bounds(t)) {
+                if triangles_intersect(tri, large.corners(u)) {
+                    return true;
+                }
+            }
+        }
+        large.contains_point(small.points[small.triangles[0][0]])
+            || small.contains_point(large.points[large.triangles[0][0]])
+    }
+
+    /// Smallest surface-to-surface distance in nm; 0.0 when the meshes overlap.
+    pub fn separation_nm(&self, other: &PlacedMesh) -> f64 {
+        self.separation_within(other, f64::INFINITY)
+            .unwrap_or(f64::INFINITY)
+    }
+
+    /// Separation if it is below `within`, without searching farther than that.
+    pub fn separation_within(&self, other: &PlacedMesh, within: f64) -> Option<f64> {
+        if self.bounds.distance_to(&other.bounds) >= within {
+            return None;
+        }
+        if self.intersects(other) {
+            return Some(0.0);
+        }
+        let mut best = within;
+        let mut found = None;
+        for t in 0..self.triangles.len() {
+            let tri = self.corners(t);
+            let tri_bounds = self.triangle_bounds(t);
+            if tri_bounds.distance_to(&other.bounds) >= best {
+                continue;
+            }
+            let hit = other.bvh.nearest(
+                |b| tri_bounds.distance_to(b),
+                |u| triangle_distance(tri, other.corners(u)),
+                best,
+            );
+            if let Some((_, d)) = hit {
+                best = d;
+                found = Some(d);
+            }
+        }
+        found
+    }
+}
+
+/// Distance between two members, as reported by a spacing query.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MemberPair {
+    pub a: usize,
+    pub b: usize,
+    pub separation_nm: f64,
+}
+
+/// Overlap and crowding of a swarm, by member index.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SpacingReport {
+    /// Pairs whose surfaces touch, cross or enclose one another.
+    pub overlapping: Vec<MemberPair>,
+    /// Non-overlapping pairs closer than the minimum separation.
+    pub too_close: Vec<MemberPair>,
+    /// (member, neighbours within the crowding radius) above the allowed count.
+    pub crowded: Vec<(usize, usize)>,
+    /// Smallest separation found between any two members, if within the crowding radius.
+    pub min_separation_nm: Option<f64>,
+}
+
+impl SpacingReport {
+    pub fn is_clear(&self) -> bool {
+        self.overlapping.is_empty() && self.too_close.is_empty() && self.crowded.is_empty()
+    }
+
+    /// Distinct members taking part in any of `pairs`.
+    pub fn involved_members(pairs: &[MemberPair]) -> Vec<usize> {
+        let mut members: Vec<usize> = pairs.iter().flat_map(|p| [p.a, p.b]).collect();
+        members.sort_unstable();
+        members.dedup();
+        members
+    }
+}
+
+/// Broad phase over member bounds plus per-member triangle BVHs for the narrow phase.
+/// Indices match `Nanoswarm::members`; retired members and members without
+/// vertices are skipped.
+#[derive(Clone, Debug)]
+pub struct SwarmIndex {
+    pub meshes: Vec<Option<PlacedMesh>>,
+    pub bvh: Bvh,
+    // BVH item -> member index.
+    slots: Vec<usize>,
+}
+
+impl SwarmIndex {
+    pub fn build(swarm: &Nanoswarm) -> Self {
+        let meshes: Vec<Option<PlacedMesh>> = swarm
+            .members
+            .iter()
+            .map(|m| match m.lifecycle.state {
+                MemberState::Retired => None,
+                _ => PlacedMesh::from_member(m),
+            })
+            .collect();
+        let slots: Vec<usize> = (0..meshes.len()).filter(|&i| meshes[i].is_some()).collect();
+        let boxes: Vec<Aabb> = slots
+            .iter()
+            .filter_map(|&i| meshes[i].as_ref().map(|m| m.bounds))
+            .collect();
+        let bvh = Bvh::build(&boxes);
+        Self { meshes, bvh, slots }
+    }
+
+    /// Broad phase: member pairs (a < b) whose bounds come within `margin_nm`.
+    pub fn candidate_pairs(&self, margin_nm: f64) -> Vec<(usize, usize)> {
+        let mut pairs = Vec::new();
+        for &a in &self.slots {
+            let Some(mesh) = &self.meshes[a] else {
+                continue;
+            };
+            for item in self.bvh.query(&mesh.bounds.expanded(margin_nm.max(0.0))) {
+                let b = self.slots[item];
+                if a < b {
+                    pairs.push((a, b));
+                }
+            }
+        }
+        pairs.sort_unstable();
+        pairs
+    }
+
+    /// Members whose bounds intersect `region`.
+    pub fn members_in(&self, region: &Aabb) -> Vec<usize> {
+        self.bvh
+            .query(region)
+            .into_iter()
+            .map(|item| self.slots[item])
+            .collect()
+    }
+
+    pub fn overlap(&self, a: usize, b: usize) -> bool {
+        match (self.mesh(a), self.mesh(b)) {
+            (Some(x), Some(y)) => x.intersects(y),
+            _ => false,
+        }
+    }
+
+    pub fn separation_nm(&self, a: usize, b: usize) -> Option<f64> {
+        Some(self.mesh(a)?.separation_nm(self.mesh(b)?))
+    }
+
+    /// Closest other member to `a` and the gap to it.
+    pub fn nearest_member(&self, a: usize) -> Option<(usize, f64)> {
+        let mesh = self.mesh(a)?;
+        self.bvh
+            .nearest(
+                |b| mesh.bounds.distance_to(b),
+                |item| {
+                    let b = self.slots[item];
+                    if b == a {
+                        f64::INFINITY
+                    } else {
+                        self.meshes[b]
+                            .as_ref()
+                            .map_or(f64::INFINITY, |m| mesh.separation_nm(m))
+                    }
+                },
+                f64::INFINITY,
+            )
+            .map(|(item, d)| (self.slots[item], d))
+    }
+
+    /// Overlaps, separations below `min_separation_nm`, and members with more
+    /// than `max_neighbours` others within `crowding_radius_nm`.
+    pub fn spacing_report(
+        &self,
+        min_separation_nm: f64,
+        crowding_radius_nm: f64,
+        max_neighbours: usize,
+    ) -> SpacingReport {
+        let reach = min_separation_nm.max(crowding_radius_nm).max(0.0);
+        let mut report = SpacingReport::default();
+        let mut neighbours: BTreeMap<usize, usize> = BTreeMap::new();
+
+        for (a, b) in self.candidate_pairs(reach) {
+            let (Some(x), Some(y)) = (self.mesh(a), self.mesh(b)) else {
+                continue;
+            };
+            let Some(d) = x.separation_within(y, reach + CONTACT_TOLERANCE_NM) else {
+                continue;
+            };
+            report.min_separation_nm = Some(report.min_separation_nm.map_or(d, |m| m.min(d)));
+
+            let pair = MemberPair {
+                a,
+                b,
+                separation_nm: d,
+            };
+            if d <= CONTACT_TOLERANCE_NM {
+                report.overlapping.push(pair);
+            } else if d < min_separation_nm {
+                report.too_close.push(pair);
+            }
+            if d <= crowding_radius_nm {
+                *neighbours.entry(a).or_insert(0) += 1;
+                *neighbours.entry(b).or_insert(0) += 1;
+            }
+        }
+
+        report.crowded = neighbours
+            .into_iter()
+            .filter(|&(_, n)| n > max_neighbours)
+            .collect();
+        report
+    }
+
+    fn mesh(&self, i: usize) -> Option<&PlacedMesh> {
+        self.meshes.get(i)?.as_ref()
+    }
+}
+
+/// Separating-axis test for two triangles, either of which may be degenerate
+/// (a segment or a point). Touching counts as intersecting.
+pub fn triangles_intersect(a: [Vec3; 3], b: [Vec3; 3]) -> bool {
+    let ea = [
+        geometry::sub(a[1], a[0]),
+        geometry::sub(a[2], a[1]),
+        geometry::sub(a[0], a[2]),
+    ];
+    let eb = [
+        geometry::sub(b[1], b[0]),
+        geometry::sub(b[2], b[1]),
+        geometry::sub(b[0], b[2]),
+    ];
+    let na = geometry::cross(ea[0], geometry::sub(a[2], a[0]));
+    let nb = geometry::cross(eb[0], geometry::sub(b[2], b[0]));
+
+    let longest = ea
+        .iter()
+        .chain(eb.iter())
+        .map(|&e| geometry::norm(e))
+        .fold(0.0_f64, f64::max);
+    let min_axis = 1e-12 * longest * longest;
+    let flat_a = geometry::norm(na) <= min_axis;
+    let flat_b = geometry::norm(nb) <= min_axis;
+    if flat_a && flat_b {
+        // Two segments or points: no face normal to separate along.
+        return triangle_distance_unchecked(a, b) <= CONTACT_TOLERANCE_NM;
+    }
+
+    let mut axes = vec![na, nb];
+    for &e in &ea {
+        axes.push(geometry::cross(na, e));
+        axes.push(geometry::cross(nb, e));
+        for &f in &eb {
+            axes.push(geometry::cross(e, f));
+        }
+    }
+    for &f in &eb {
+        axes.push(geometry::cross(na, f));
+        axes.push(geometry::cross(nb, f));
+    }
+
+    for axis in axes {
+        if geometry::norm(axis) <= min_axis {
+            continue;
+        }
+        let (a_min, a_max) = project(&a, axis);
+        let (b_min, b_max) = project(&b, axis);
+        if a_max < b_min || b_max < a_min {
+            return false;
+        }
+    }
+    true
+}
+
+/// Closest distance between two triangles in nm; 0.0 when they intersect.
+pub fn triangle_distance(a: [Vec3; 3], b: [Vec3; 3]) -> f64 {
+    if triangles_intersect(a, b) {
+        0.0
+    } else {
+        triangle_distance_unchecked(a, b)
+    }
+}
+
+// Valid for disjoint triangles: the closest pair always involves a vertex or two edges.
+fn triangle_distance_unchecked(a: [Vec3; 3], b: [Vec3; 3]) -> f64 {
+    let mut best = f64::INFINITY;
+    for &p in &a {
+        let q = geometry::closest_point_on_triangle(p, b[0], b[1], b[2]);
+        best = best.min(geometry::norm(geometry::sub(p, q)));
+    }
+    for &p in &b {
+        let q = geometry::closest_point_on_triangle(p, a[0], a[1], a[2]);
+        best = best.min(geometry::norm(geometry::sub(p, q)));
+    }
+    for i in 0..3 {
+        for j in 0..3 {
+            let d = segment_distance(a[i], a[(i + 1) % 3], b[j], b[(j + 1) % 3]);
+            best = best.min(d);
+        }
+    }
+    best
+}
+
+/// Closest distance between segments p1-q1 and p2-q2.
+pub fn segment_distance(p1: Vec3, q1: Vec3, p2: Vec3, q2: Vec3) -> f64 {
+    let d1 = geometry::sub(q1, p1);
+    let d2 = geometry::sub(q2, p2);
+    let r = geometry::sub(p1, p2);
+    let a = geometry::dot(d1, d1);
+    let e = geometry::dot(d2, d2);
+    let f = geometry::dot(d2, r);
+
+    let (s, t) = if a <= f64::EPSILON && e <= f64::EPSILON {
+        (0.0, 0.0)
+    } else if a <= f64::EPSILON {
+        (0.0, (f / e).clamp(0.0, 1.0))
+    } else {
+        let c = geometry::dot(d1, r);
+        if e <= f64::EPSILON {
+            ((-c / a).clamp(0.0, 1.0), 0.0)
+        } else {
+            let b = geometry::dot(d1, d2);
+            let denom = a * e - b * b;
+            let mut s = if denom > 0.0 {
+                ((b * f - c * e) / denom).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            let mut t = (b * s + f) / e;
+            if t < 0.0 {
+                t = 0.0;
+                s = (-c / a).clamp(0.0, 1.0);
+            } else if t > 1.0 {
+                t = 1.0;
+                s = ((b - c) / a).clamp(0.0, 1.0);
+            }
+            (s, t)
+        }
+    };
+
+    let c1 = geometry::add(p1, geometry::scale(d1, s));
+    let c2 = geometry::add(p2, geometry::scale(d2, t));
+    geometry::norm(geometry::sub(c1, c2))
+}
+
+/// Generalised winding number of a closed surface around `p`:
+/// about ±1 inside, 0 outside, whatever the face orientation convention.
+pub fn winding_number(p: Vec3, points: &[Vec3], triangles: &[[usize; 3]]) -> f64 {
+    let mut total = 0.0_f64;
+    for t in triangles {
+        let a = geometry::sub(points[t[0]], p);
+        let b = geometry::sub(points[t[1]], p);
+        let c = geometry::sub(points[t[2]], p);
+        let (la, lb, lc) = (geometry::norm(a), geometry::norm(b), geometry::norm(c));
+        // Van Oosterom-Strackee solid angle.
+        let numerator = geometry::dot(a, geometry::cross(b, c));
+        let denominator = la * lb * lc
+            + geometry::dot(a, b) * lc
+            + geometry::dot(a, c) * lb
+            + geometry::dot(b, c) * la;
+        total += 2.0 * numerator.atan2(denominator);
+    }
+    total / (4.0 * std::f64::consts::PI)
+}
+
+fn project(tri: &[Vec3; 3], axis: Vec3) -> (f64, f64) {
+    let d = tri.map(|p| geometry::dot(p, axis));
+    (d[0].min(d[1]).min(d[2]), d[0].max(d[1]).max(d[2]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xr_lab_grid::nanopoly::core::nanopoly_object::{
+        BciInterface, EnergeticProfile, NanopolyObject,
+    };
+    use crate::xr_lab_grid::nanopoly::generators::{self, PlatonicSolid};
+    use crate::xr_lab_grid::nanopoly::nanopolygon::{BioAffinityTarget, BiophysicalMetadata};
+    use crate::xr_lab_grid::nanopoly::transform::Quaternion;
+
+    fn bio() -> BiophysicalMetadata {
+        BiophysicalMetadata {
+            target: BioAffinityTarget::ExtracellularMatrix,
+            zeta_potential_mv: -10.0,
+            hydrophobicity_index: 0.3,
+            elastic_modulus_kpa: 1.0,
+            ligands: Vec::new(),
+        }
+    }
+
+    // Axis-aligned cube of half-edge 1 nm centred on `at`.
+    fn cube_at(at: Vec3) -> PlacedMesh {
+        let cube = generators::platonic("c", PlatonicSolid::Cube, 3f64.sqrt(), bio()).unwrap();
+        let pose = RigidTransform::new(Quaternion::identity(), at);
+        PlacedMesh::from_nanopolygon(&cube, &pose).unwrap()
+    }
+
+    // Unit boxes scattered over a 20 nm cube by a fixed LCG.
+    fn scattered_boxes(n: usize) -> Vec<Aabb> {
+        let mut state = 12345_u64;
+        let mut next = || {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+            (state >> 11) as f64 / (1u64 << 53) as f64 * 20.0
+        };
+        (0..n)
+            .map(|_| {
+                let min = [next(), next(), next()];
+                Aabb {
+                    min_nm: min,
+                    max_nm: geometry::add(min, [1.0, 1.0, 1.0]),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn query_matches_brute_force() {
+        let boxes = scattered_boxes(200);
+        let bvh = Bvh::build(&boxes);
+        for region in scattered_boxes(20).iter().map(|b| b.expanded(2.0)) {
+            let expected: Vec<usize> = (0..boxes.len())
+                .filter(|&i| boxes[i].intersects(&region))
+                .collect();
+            assert_eq!(bvh.query(&region), expected);
+        }
+        assert!(Bvh::build(&[]).query(&boxes[0]).is_empty());
+    }
+
+    #[test]
+    fn nearest_matches_brute_force() {
+        let boxes = scattered_boxes(200);
+        let bvh = Bvh::build(&boxes);
+        for probe in scattered_boxes(20) {
+            let p = probe.center_nm();
+            let point = Aabb {
+                min_nm: p,
+                max_nm: p,
+            };
+            let exact = |i: usize| geometry::norm(geometry::sub(boxes[i].center_nm(), p));
+            let brute = (0..boxes.len()).map(exact).fold(f64::INFINITY, f64::min);
+            let (_, d) = bvh
+                .nearest(|b| b.distance_to(&point), exact, f64::INFINITY)
+                .unwrap();
+            assert!((d - brute).abs() < 1e-12);
+            assert!(bvh
+                .nearest(|b| b.distance_to(&point), exact, brute)
+                .is_none());
+        }
+    }
+
+    #[test]
+    fn placed_cubes_separation_and_overlap() {
+        let a = cube_at([0.0, 0.0, 0.0]);
+        let b = cube_at([5.0, 0.0, 0.0]);
+        assert!(!a.intersects(&b));
+        assert!((a.separation_nm(&b) - 3.0).abs() < 1e-9);
+        assert!(a.separation_within(&b, 2.0).is_none());
+
+        let touching = cube_at([1.5, 0.5, 0.0]);
+        assert!(a.intersects(&touching));
+        assert_eq!(a.separation_nm(&touching), 0.0);
+
+        assert!(a.contains_point([0.2, -0.3, 0.9]));
+        assert!(!a.contains_point([1.2, 0.0, 0.0]));
+        assert!((a.distance_to_point([0.0, 0.0, 4.0]) - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn enclosed_mesh_counts_as_overlap() {
+        let outer = generators::platonic("o", PlatonicSolid::Cube, 10.0, bio()).unwrap();
+        let outer = PlacedMesh::from_nanopolygon(&outer, &RigidTransform::identity()).unwrap();
+        let inner = cube_at([0.5, 0.0, 0.0]);
+        assert!(outer.intersects(&inner));
+        assert!(inner.intersects(&outer));
+    }
+
+    #[test]
+    fn segment_and_triangle_distances() {
+        let o = [0.0, 0.0, 0.0];
+        // Parallel, offset along z, with overlapping spans.
+        let d = segment_distance(o, [2.0, 0.0, 0.0], [1.0, 0.0, 3.0], [4.0, 0.0, 3.0]);
+        assert!((d - 3.0).abs() < 1e-12);
+        // Skew lines crossing over each other.
+        let d = segment_distance(
+            [-1.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, -1.0, 2.0],
+            [0.0, 1.0, 2.0],
+        );
+        assert!((d - 2.0).abs() < 1e-12);
+        // Collinear, end to end with a gap; and a point against a segment.
+        let d = segment_distance(o, [1.0, 0.0, 0.0], [3.5, 0.0, 0.0], [5.0, 0.0, 0.0]);
+        assert!((d - 2.5).abs() < 1e-12);
+        let d = segment_distance([1.0, 4.0, 0.0], [1.0, 4.0, 0.0], o, [2.0, 0.0, 0.0]);
+        assert!((d - 4.0).abs() < 1e-12);
+
+        let floor = [o, [4.0, 0.0, 0.0], [0.0, 4.0, 0.0]];
+        let lifted = floor.map(|p| geometry::add(p, [0.0, 0.0, 1.5]));
+        assert!(!triangles_intersect(floor, lifted));
+        assert!((triangle_distance(floor, lifted) - 1.5).abs() < 1e-12);
+
+        // A vertical triangle piercing the floor, and one resting on it.
+        let piercing = [[1.0, 1.0, -1.0], [1.0, 1.0, 1.0], [2.0, 1.0, 1.0]];
+        assert!(triangles_intersect(floor, piercing));
+        assert_eq!(triangle_distance(floor, piercing), 0.0);
+        let resting = [[1.0, 1.0, 0.0], [1.0, 1.0, 2.0], [2.0, 1.0, 2.0]];
+        assert!(triangles_intersect(floor, resting));
+
+        // Degenerate triangles: a point on the face, and a segment beside it.
+        let point = [[1.0, 1.0, 0.0]; 3];
+        assert!(triangles_intersect(floor, point));
+        let segment = [[3.0, 3.0, 0.0], [5.0, 5.0, 0.0], [5.0, 5.0, 0.0]];
+        assert!(!triangles_intersect(floor, segment));
+        let gap = triangle_distance(floor, segment);
+        assert!((gap - 2.0 / 2f64.sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn winding_number_separates_inside_from_outside() {
+        let cube = cube_at([3.0, -2.0, 0.5]);
+        let w = |p: Vec3| winding_number(p, &cube.points, &cube.triangles).abs();
+        assert!((w([3.0, -2.0, 0.5]) - 1.0).abs() < 1e-9);
+        assert!((w([3.9, -1.2, 1.4]) - 1.0).abs() < 1e-9);
+        assert!(w([0.0, 0.0, 0.0]) < 1e-9);
+        assert!(w([3.0, -2.0, 2.0]) < 1e-9);
+
+        // Flipping every face changes the sign, not the magnitude.
+        let flipped: Vec<[usize; 3]> = cube.triangles.iter().map(|t| [t[0], t[2], t[1]]).collect();
+        let inside = winding_number([3.0, -2.0, 0.5], &cube.points, &cube.triangles);
+        let reversed = winding_number([3.0, -2.0, 0.5], &cube.points, &flipped);
+        assert!((inside + reversed).abs() < 1e-9);
+    }
+
+    // Swarm of cubes of half-edge 1 nm, centred along x.
+    fn cube_swarm(xs: &[f64]) -> Nanoswarm {
+        let mut swarm = Nanoswarm::new("did:example:owner");
+        for (i, &x) in xs.iter().enumerate() {
+            let id = format!("c{}", i);
+            let cube = generators::platonic(&id, PlatonicSolid::Cube, 3f64.sqrt(), bio()).unwrap();
+            let object = NanopolyObject::from_parts(
+                &id,
+                cube,
+                EnergeticProfile::constant(100.0),
+                BciInterface::none(),
+                swarm.governance.clone(),
+            );
+            let pose = RigidTransform::new(Quaternion::identity(), [x, 0.0, 0.0]);
+            swarm
+                .admit_member(NanoswarmMember::new(object, pose), 0)
+                .unwrap();
+        }
+        swarm
+    }
+
+    #[test]
+    fn swarm_spacing_overlap_proximity_and_crowding() {
+        // Gaps between neighbours: touching, 0.7, 3.8 and 28 nm.
+        let mut swarm = cube_swarm(&[0.0, 1.5, 4.2, 10.0, 40.0]);
+        let index = SwarmIndex::build(&swarm);
+
+        assert_eq!(index.candidate_pairs(0.0), [(0, 1)]);
+        assert_eq!(index.candidate_pairs(1.0), [(0, 1), (1, 2)]);
+        let wide = index.candidate_pairs(5.0);
+        for a in 0..5 {
+            for b in a + 1..5 {
+                let d = index.separation_nm(a, b).unwrap();
+                assert_eq!(wide.contains(&(a, b)), d <= 5.0, "pair {} {}", a, b);
+            }
+        }
+
+        assert!(index.overlap(0, 1));
+        assert!(!index.overlap(1, 2));
+        assert!((index.separation_nm(0, 2).unwrap() - 2.2).abs() < 1e-9);
+        let (nearest, gap) = index.nearest_member(4).unwrap();
+        assert_eq!(nearest, 3);
+        assert!((gap - 28.0).abs() < 1e-9);
+        let region = Aabb {
+            min_nm: [4.8, -0.1, -0.1],
+            max_nm: [9.5, 0.1, 0.1],
+        };
+        assert_eq!(index.members_in(&region), [2, 3]);
+
+        let report = index.spacing_report(2.0, 5.0, 2);
+        let pairs = |ps: &[MemberPair]| ps.iter().map(|p| (p.a, p.b)).collect::<Vec<_>>();
+        assert_eq!(pairs(&report.overlapping), [(0, 1)]);
+        assert_eq!(pairs(&report.too_close), [(1, 2)]);
+        assert_eq!(report.crowded, [(2, 3)]);
+        assert_eq!(report.min_separation_nm, Some(0.0));
+        assert!(!report.is_clear());
+        assert_eq!(
+            SpacingReport::involved_members(&[report.overlapping, report.too_close].concat()),
+            [0, 1, 2]
+        );
+
+        // Retiring the crowded member drops it from every query.
+        swarm.retire_member("c2", 1, "spent").unwrap();
+        let index = SwarmIndex::build(&swarm);
+        assert!(index.meshes[2].is_none());
+        assert_eq!(index.separation_nm(1, 2), None);
+        assert!(!index.overlap(2, 3));
+        assert_eq!(index.nearest_member(3).map(|(b, _)| b), Some(1));
+        let report = index.spacing_report(2.0, 5.0, 2);
+        assert_eq!(pairs(&report.overlapping), [(0, 1)]);
+        assert!(report.too_close.is_empty() && report.crowded.is_empty());
+
+        // Two of the four counted members overlap.
+        assert_eq!(swarm.spacing_load(&report), (0.25, 0.25));
+    }
+
+    #[test]
+    fn empty_and_spread_swarms_are_clear() {
+        let index = SwarmIndex::build(&Nanoswarm::new("did:example:owner"));
+        assert!(index.candidate_pairs(100.0).is_empty());
+        assert!(index.nearest_member(0).is_none());
+        let report = index.spacing_report(2.0, 25.0, 0);
+        assert!(report.is_clear());
+        assert_eq!(report.min_separation_nm, None);
+
+        // Beyond the crowding radius nothing is measured at all.
+        let index = SwarmIndex::build(&cube_swarm(&[0.0, 50.0, 100.0]));
+        let report = index.spacing_report(2.0, 25.0, 0);
+        assert!(report.is_clear());
+        assert_eq!(report.min_separation_nm, None);
+        let report = index.spacing_report(2.0, 48.5, 0);
+        assert_eq!(report.crowded, [(0, 1), (1, 2), (2, 1)]);
+        assert_eq!(report.min_separation_nm.map(|d| d.round()), Some(48.0));
+    }
+}
@@ -0,0 +1,211 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::nanopolygon::{BioAffinityTarget, Nanopolygon};
+
+/// Mismatch tolerated either side of the native range, in decades of
+/// modulus (half a decade is roughly a factor of 3).
+pub const TOLERATED_MISMATCH_DECADES: f32 = 0.5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StiffnessMismatch {
+    Matched,
+    /// Outside the native range but within tolerance.
+    Softer,
+    Stiffer,
+    TooSoft,
+    TooStiff,
+    /// Modulus missing, zero or negative.
+    Unmeasured,
+}
+
+impl StiffnessMismatch {
+    pub fn is_tolerated(&self) -> bool {
+        matches!(
+            self,
+            StiffnessMismatch::Matched | StiffnessMismatch::Softer | StiffnessMismatch::Stiffer
+        )
+    }
+}
+
+impl fmt::Display for StiffnessMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            StiffnessMismatch::Matched => "matched",
+            StiffnessMismatch::Softer => "softer than native",
+            StiffnessMismatch::Stiffer => "stiffer than native",
+            StiffnessMismatch::TooSoft => "too soft",
+            StiffnessMismatch::TooStiff => "too stiff",
+            StiffnessMismatch::Unmeasured => "unmeasured",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StiffnessAnalysis {
+    pub poly_id: String,
+    pub target: BioAffinityTarget,
+    pub modulus_kpa: f32,
+    pub native_range_kpa: (f32, f32),
+    pub tolerated_range_kpa: (f32, f32),
+    /// Decades beyond the nearest end of the native range; infinite when unmeasured.
+    pub mismatch_decades: f32,
+    pub mismatch: StiffnessMismatch,
+}
+
+impl StiffnessAnalysis {
+    pub fn for_target(poly: &Nanopolygon, target: &BioAffinityTarget) -> Self {
+        let modulus = poly.bio.elastic_modulus_kpa;
+        let native = target.modulus_range_kpa();
+        let decades = mismatch_decades(modulus, native);
+
+        let mismatch = if !decades.is_finite() {
+            StiffnessMismatch::Unmeasured
+        } else if decades == 0.0 {
+            StiffnessMismatch::Matched
+        } else {
+            let softer = modulus < native.0;
+            match (softer, decades <= TOLERATED_MISMATCH_DECADES) {
+                (true, true) => StiffnessMismatch::Softer,
+                (false, true) => StiffnessMismatch::Stiffer,
+                (true, false) => StiffnessMismatch::TooSoft,
+                (false, false) => StiffnessMismatch::TooStiff,
+            }
+        };
+
+        Self {
+            poly_id: poly.id.clone(),
+            target: target.clone(),
+            modulus_kpa: modulus,
+            native_range_kpa: native,
+            tolerated_range_kpa: target.tolerated_modulus_kpa(),
+            mismatch_decades: decades,
+            mismatch,
+        }
+    }
+
+    pub fn is_tolerated(&self) -> bool {
+        self.mismatch.is_tolerated()
+    }
+
+    /// Mismatch scaled to [0, 1]: 0 inside the native range, 1 at a full decade out.
+    pub fn penalty(&self) -> f32 {
+        self.mismatch_decades.clamp(0.0, 1.0)
+    }
+
+    pub fn describe(&self) -> String {
+        format!(
+            "Elastic modulus {:.2} kPa is {} for {:?} (native {:.2}-{:.2} kPa, tolerated {:.2}-{:.2} kPa).",
+            self.modulus_kpa,
+            self.mismatch,
+            self.target,
+            self.native_range_kpa.0,
+            self.native_range_kpa.1,
+            self.tolerated_range_kpa.0,
+            self.tolerated_range_kpa.1
+        )
+    }
+}
+
+impl Nanopolygon {
+    /// Stiffness mismatch against the declared target.
+    pub fn stiffness_analysis(&self) -> StiffnessAnalysis {
+        StiffnessAnalysis::for_target(self, &self.bio.target)
+    }
+}
+
+/// Decades of modulus beyond the nearest end of `(lo, hi)`; infinite for a
+/// missing or non-positive modulus.
+pub fn mismatch_decades(modulus_kpa: f32, (lo, hi): (f32, f32)) -> f32 {
+    if modulus_kpa.is_nan() || modulus_kpa <= 0.0 {
+        return f32::INFINITY;
+    }
+    if modulus_kpa < lo {
+        (lo / modulus_kpa).log10()
+    } else if modulus_kpa > hi {
+        (modulus_kpa / hi).log10()
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xr_lab_grid::nanopoly::generators::{self, PlatonicSolid};
+    use crate::xr_lab_grid::nanopoly::nanopolygon::BiophysicalMetadata;
+
+    fn with_modulus(modulus_kpa: f32) -> Nanopolygon {
+        let bio = BiophysicalMetadata {
+            target: BioAffinityTarget::NeuralMembrane,
+            zeta_potential_mv: -20.0,
+            hydrophobicity_index: 0.3,
+            elastic_modulus_kpa: modulus_kpa,
+            ligands: Vec::new(),
+        };
+        generators::platonic("m", PlatonicSolid::Tetrahedron, 5.0, bio).unwrap()
+    }
+
+    #[test]
+    fn mismatch_is_measured_in_decades() {
+        let native = (0.1, 1.0);
+        assert_eq!(mismatch_decades(0.5, native), 0.0);
+        assert_eq!(mismatch_decades(0.1, native), 0.0);
+        assert!((mismatch_decades(0.01, native) - 1.0).abs() < 1e-6);
+        assert!((mismatch_decades(100.0, native) - 2.0).abs() < 1e-6);
+        assert_eq!(mismatch_decades(0.0, native), f32::INFINITY);
+        assert_eq!(mismatch_decades(-1.0, native), f32::INFINITY);
+        assert_eq!(mismatch_decades(f32::NAN, native), f32::INFINITY);
+    }
+
+    #[test]
+    fn tolerated_range_is_half_a_decade_wider() {
+        let (lo, hi) = BioAffinityTarget::NeuralMembrane.tolerated_modulus_kpa();
+        assert!((lo - 0.1 / 10f32.sqrt()).abs() < 1e-6);
+        assert!((hi - 10f32.sqrt()).abs() < 1e-5);
+    }
+
+    #[test]
+    fn classification_against_neural_membrane() {
+        // Native 0.1-1.0 kPa, tolerated about 0.032-3.16 kPa.
+        let cases = [
+            (0.5, StiffnessMismatch::Matched),
+            (0.05, StiffnessMismatch::Softer),
+            (3.0, StiffnessMismatch::Stiffer),
+            (0.02, StiffnessMismatch::TooSoft),
+            (4.0, StiffnessMismatch::TooStiff),
+            (0.0, StiffnessMismatch::Unmeasured),
+        ];
+        for (modulus, expected) in cases {
+            let analysis = with_modulus(modulus).stiffness_analysis();
+            assert_eq!(analysis.mismatch, expected, "{modulus} kPa");
+            assert_eq!(
+                analysis.is_tolerated(),
+                matches!(
+                    expected,
+                    StiffnessMismatch::Matched
+                        | StiffnessMismatch::Softer
+                        | StiffnessMismatch::Stiffer
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn penalty_saturates_at_one_decade() {
+        let stiff = with_modulus(3.0).stiffness_analysis();
+        assert!((stiff.penalty() - 3f32.log10()).abs() < 1e-6);
+        assert_eq!(with_modulus(50.0).stiffness_analysis().penalty(), 1.0);
+        assert_eq!(with_modulus(0.0).stiffness_analysis().penalty(), 1.0);
+
+        // The same modulus is matched for muscle.
+        let poly = with_modulus(10.0);
+        let muscle = StiffnessAnalysis::for_target(&poly, &BioAffinityTarget::MuscleFiber);
+        assert_eq!(muscle.mismatch, StiffnessMismatch::Matched);
+        assert!(muscle
+            .describe()
+            .starts_with("Elastic modulus 10.00 kPa is matched for MuscleFiber"));
+    }
+}
@@ -0,0 +1,485 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::store::metrics::ResponseMetric;
+
+use super::core::governance::{ConsentState, GovernanceLayer};
+use super::core::species::{HostType, SpeciesId};
+use super::nanoswarm::Nanoswarm;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum PolicyFinding {
+    DemandNearLimit {
+        d: f32,
+        warn: f32,
+    },
+    DemandOverLimit {
+        d: f32,
+        max: f32,
+    },
+    DriftNearLimit {
+        dw: f32,
+        warn: f32,
+    },
+    DriftOverLimit {
+        dw: f32,
+        max: f32,
+    },
+    ConsentNotActive {
+        state: ConsentState,
+    },
+    /// Non-human host whose governance permits commercial use.
+    CommercialUsePermitted,
+    /// Non-human host whose governance permits entertainment use.
+    EntertainmentUsePermitted,
+    /// Non-human host with nobody to speak for it.
+    NoGuardian,
+    MemberConsentNotActive {
+        member_id: String,
+        state: ConsentState,
+    },
+    /// Member governed for a different host than the swarm it sits in.
+    MemberHostMismatch {
+        member_id: String,
+        species: SpeciesId,
+        host_type: HostType,
+    },
+    /// Members in a different partition from the Rollback source.
+    RollbackUnreachable {
+        member_ids: Vec<String>,
+    },
+    /// Relay whose loss would leave members beyond Rollback's reach.
+    RollbackSingleRelay {
+        relay_id: String,
+        stranded: usize,
+    },
+}
+
+impl PolicyFinding {
+    /// Near-limit and single-relay findings are warnings; everything else
+    /// blocks the swarm.
+    pub fn is_blocking(&self) -> bool {
+        !matches!(
+            self,
+            PolicyFinding::DemandNearLimit { .. }
+                | PolicyFinding::DriftNearLimit { .. }
+                | PolicyFinding::RollbackSingleRelay { .. }
+        )
+    }
+}
+
+impl fmt::Display for PolicyFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolicyFinding::DemandNearLimit { d, warn } => {
+                write!(f, "D {:.3} is above the warning level {:.3}", d, warn)
+            }
+            PolicyFinding::DemandOverLimit { d, max } => {
+                write!(f, "D {:.3} exceeds the ceiling {:.3}", d, max)
+            }
+            PolicyFinding::DriftNearLimit { dw, warn } => {
+                write!(f, "DW {:.3} is above the warning level {:.3}", dw, warn)
+            }
+            PolicyFinding::DriftOverLimit { dw, max } => {
+                write!(f, "DW {:.3} exceeds the ceiling {:.3}", dw, max)
+            }
+            PolicyFinding::ConsentNotActive { state } => {
+                write!(f, "swarm consent is {:?}", state)
+            }
+            PolicyFinding::CommercialUsePermitted => {
+                write!(f, "non-human host is not protected from commercial use")
+            }
+            PolicyFinding::EntertainmentUsePermitted => {
+                write!(f, "non-human host is not protected from entertainment use")
+            }
+            PolicyFinding::NoGuardian => write!(f, "non-human host has no guardian"),
+            PolicyFinding::MemberConsentNotActive { member_id, state } => {
+                write!(f, "member '{}' consent is {:?}", member_id, state)
+            }
+            PolicyFinding::MemberHostMismatch {
+                member_id,
+                species,
+                host_type,
+            } => write!(
+                f,
+                "member '{}' is governed for {:?} ({:?})",
+                member_id, species, host_type
+            ),
+            PolicyFinding::RollbackUnreachable { member_ids } => write!(
+                f,
+                "Rollback cannot reach {} member(s): {}",
+                member_ids.len(),
+                member_ids.join(", ")
+            ),
+            PolicyFinding::RollbackSingleRelay { relay_id, stranded } => write!(
+                f,
+                "losing relay '{}' would cut {} member(s) off from Rollback",
+                relay_id, stranded
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum PolicyStatus {
+    Clear,
+    Warn,
+    Blocked,
+}
+
+/// Outcome of a swarm policy check: the aggregate metric, the envelope it
+/// was held against and everything that fell outside it.
+#[derive(Clone, Debug)]
+pub struct PolicyVerdict {
+    pub swarm_id: String,
+    pub metric: ResponseMetric,
+    pub species: SpeciesId,
+    pub host_type: HostType,
+    /// Ceilings after combining the host budget with the swarm's own limits.
+    pub d_max: f32,
+    pub dw_max: f32,
+    pub findings: Vec<PolicyFinding>,
+}
+
+impl PolicyVerdict {
+    pub fn evaluate(swarm: &Nanoswarm) -> Self {
+        let metric = swarm.aggregate_metric();
+        let budget = &swarm.host_budget;
+        let gov = &swarm.governance;
+        let d_max = budget.d_max.min(swarm.max_energy_d);
+        let dw_max = budget.dw_max.min(swarm.max_dw);
+
+        let mut findings = Vec::new();
+        findings.extend(band_finding(
+            metric.demand_d,
+            budget.d_warn.min(d_max),
+            d_max,
+            |d, warn| PolicyFinding::DemandNearLimit { d, warn },
+            |d, max| PolicyFinding::DemandOverLimit { d, max },
+        ));
+        findings.extend(band_finding(
+            metric.dracula_wave_dw,
+            budget.dw_warn.min(dw_max),
+            dw_max,
+            |dw, warn| PolicyFinding::DriftNearLimit { dw, warn },
+            |dw, max| PolicyFinding::DriftOverLimit { dw, max },
+        ));
+        findings.extend(governance_findings(gov));
+
+        for member in swarm.counted_members() {
+            let object = &member.object;
+            if object.gov.consent_state != ConsentState::Active {
+                findings.push(PolicyFinding::MemberConsentNotActive {
+                    member_id: object.id.clone(),
+                    state: object.gov.consent_state.clone(),
+                });
+            }
+            if object.gov.species != gov.species || object.gov.host_type != gov.host_type {
+                findings.push(PolicyFinding::MemberHostMismatch {
+                    member_id: object.id.clone(),
+                    species: object.gov.species.clone(),
+                    host_type: object.gov.host_type.clone(),
+                });
+            }
+        }
+
+        let reach = swarm.rollback_reach();
+        let member_id = |i: usize| swarm.members[i].object.id.clone();
+        if !reach.all_reachable() {
+            findings.push(PolicyFinding::RollbackUnreachable {
+                member_ids: reach.unreachable.iter().map(|&i| member_id(i)).collect(),
+            });
+        }
+        for critical in &reach.critical_relays {
+            findings.push(PolicyFinding::RollbackSingleRelay {
+                relay_id: member_id(critical.relay),
+                stranded: critical.stranded.len(),
+            });
+        }
+
+        Self {
+            swarm_id: swarm.id.clone(),
+            metric,
+            species: budget.species.clone(),
+            host_type: budget.host_type.clone(),
+            d_max,
+            dw_max,
+            findings,
+        }
+    }
+
+    pub fn status(&self) -> PolicyStatus {
+        if self.findings.iter().any(|f| f.is_blocking()) {
+            PolicyStatus::Blocked
+        } else if self.findings.is_empty() {
+            PolicyStatus::Clear
+        } else {
+            PolicyStatus::Warn
+        }
+    }
+
+    pub fn allowed(&self) -> bool {
+        self.status() != PolicyStatus::Blocked
+    }
+
+    pub fn violations(&self) -> Vec<&PolicyFinding> {
+        self.findings.iter().filter(|f| f.is_blocking()).collect()
+    }
+
+    pub fn warnings(&self) -> Vec<&PolicyFinding> {
+        self.findings.iter().filter(|f| !f.is_blocking()).collect()
+    }
+
+    pub fn notes(&self) -> Vec<String> {
+        self.findings.iter().map(|f| f.to_string()).collect()
+    }
+}
+
+// Governance rules for the host as a whole. Non-human hosts cannot consent
+// to commercial or entertainment use, so both must be excluded outright.
+fn governance_findings(gov: &GovernanceLayer) -> Vec<PolicyFinding> {
+    let mut findings = Vec::new();
+    if gov.consent_state != ConsentState::Active {
+        findings.push(PolicyFinding::ConsentNotActive {
+            state: gov.consent_state.clone(),
+        });
+    }
+    if gov.host_type != HostType::Human {
+        if !gov.non_commercial {
+            findings.push(PolicyFinding::CommercialUsePermitted);
+        }
+        if !gov.no_entertainment {
+            findings.push(PolicyFinding::EntertainmentUsePermitted);
+        }
+        if gov.guardian_did.is_none() {
+            findings.push(PolicyFinding::NoGuardian);
+        }
+    }
+    findings
+}
+
+fn band_finding(
+    value: f32,
+    warn: f32,
+    max: f32,
+    near: impl Fn(f32, f32) -> PolicyFinding,
+    over: impl Fn(f32, f32) -> PolicyFinding,
+) -> Option<PolicyFinding> {
+    if value > max {
+        Some(over(value, max))
+    } else if value > warn {
+        Some(near(value, warn))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xr_lab_grid::nanopoly::core::nanopoly_object::{
+        BciInterface, EnergeticProfile, NanopolyObject,
+    };
+    use crate::xr_lab_grid::nanopoly::generators;
+    use crate::xr_lab_grid::nanopoly::nanopolygon::{BioAffinityTarget, BiophysicalMetadata};
+    use crate::xr_lab_grid::nanopoly::nanoswarm::NanoswarmMember;
+    use crate::xr_lab_grid::nanopoly::transform::RigidTransform;
+
+    fn guarded(species: SpeciesId, host_type: HostType) -> GovernanceLayer {
+        GovernanceLayer {
+            host_type,
+            species,
+            guardian_did: Some("did:example:guardian".to_string()),
+            veterinary_did: Some("did:example:vet".to_string()),
+            non_commercial: true,
+            no_entertainment: true,
+            ..GovernanceLayer::human("did:example:owner")
+        }
+    }
+
+    // One large, low-risk member on muscle drawing `draw_uw` with no blood
+    // coupling, so D is draw over the host's metabolic budget.
+    fn member(id: &str, gov: GovernanceLayer, draw_uw: f64) -> NanoswarmMember {
+        let bio = BiophysicalMetadata {
+            target: BioAffinityTarget::MuscleFiber,
+            zeta_potential_mv: -20.0,
+            hydrophobicity_index: 0.3,
+            elastic_modulus_kpa: 10.0,
+            ligands: Vec::new(),
+        };
+        let poly = generators::icosphere(id, 100.0, 2, bio).unwrap();
+        let object = NanopolyObject::from_parts(
+            id,
+            poly,
+            EnergeticProfile::constant(draw_uw),
+            BciInterface::none(),
+            gov,
+        );
+        NanoswarmMember::new(object, RigidTransform::identity())
+    }
+
+    fn swarm_at_demand(gov: GovernanceLayer, d: f64) -> Nanoswarm {
+        let mut swarm = Nanoswarm::for_host("swarm", gov.clone());
+        let draw = d * swarm.host_budget.metabolic_budget_uw;
+        swarm.add_member(member("m0", gov, draw)).unwrap();
+        swarm
+    }
+
+    fn dog() -> GovernanceLayer {
+        guarded(SpeciesId::CanisLupusFamiliaris, HostType::NonHumanCompanion)
+    }
+
+    #[test]
+    fn demand_bands_against_the_canine_budget() {
+        // Canine D warns above 0.28 and blocks above 0.35.
+        let verdict = swarm_at_demand(dog(), 0.2).check_policy();
+        assert_eq!(verdict.status(), PolicyStatus::Clear);
+        assert_eq!(verdict.d_max, 0.35);
+
+        let verdict = swarm_at_demand(dog(), 0.3).check_policy();
+        assert!(matches!(
+            verdict.findings[..],
+            [PolicyFinding::DemandNearLimit { d, warn }] if (d - 0.3).abs() < 1e-5 && (warn - 0.28).abs() < 1e-6
+        ));
+        assert_eq!(verdict.status(), PolicyStatus::Warn);
+        assert!(verdict.allowed());
+
+        let verdict = swarm_at_demand(dog(), 0.4).check_policy();
+        assert!(matches!(
+            verdict.findings[..],
+            [PolicyFinding::DemandOverLimit { max, .. }] if max == 0.35
+        ));
+        assert!(!verdict.allowed());
+
+        // The same draw is unremarkable for a human host.
+        let human = GovernanceLayer::human("did:example:owner");
+        assert_eq!(
+            swarm_at_demand(human, 0.4).check_policy().status(),
+            PolicyStatus::Clear
+        );
+    }
+
+    #[test]
+    fn wildlife_bands_are_tighter_than_companion_bands() {
+        // Wild dog: D ceiling 0.28, warning at 0.224.
+        let wild = guarded(SpeciesId::CanisLupusFamiliaris, HostType::Wildlife);
+        let verdict = swarm_at_demand(wild.clone(), 0.25).check_policy();
+        assert!(matches!(
+            verdict.findings[..],
+            [PolicyFinding::DemandNearLimit { .. }]
+        ));
+        assert!((verdict.d_max - 0.28).abs() < 1e-6);
+        assert_eq!(
+            swarm_at_demand(dog(), 0.25).check_policy().status(),
+            PolicyStatus::Clear
+        );
+
+        let verdict = swarm_at_demand(wild, 0.3).check_policy();
+        assert!(matches!(
+            verdict.findings[..],
+            [PolicyFinding::DemandOverLimit { .. }]
+        ));
+    }
+
+    #[test]
+    fn drift_bands_against_the_canine_budget() {
+        // A neural BCI member adds 0.6 * rate / 1000 to DW on top of the
+        // geometric term; pick rates that land in each canine DW band.
+        let mut swarm = swarm_at_demand(dog(), 0.1);
+        let base = swarm.aggregate_metric().dracula_wave_dw;
+        assert!(base < 0.08);
+
+        let mut verdicts = Vec::new();
+        for target_dw in [0.09_f32, 0.12] {
+            let object = &mut swarm.members[0].object;
+            object.polygon.bio.target = BioAffinityTarget::NeuralMembrane;
+            object.bci = BciInterface {
+                input_bands_hz: vec![(8.0, 12.0)],
+                output_bands_hz: vec![(8.0, 12.0)],
+                max_bit_rate_bps: (target_dw - base) / 0.6 * 1000.0,
+            };
+            verdicts.push(swarm.check_policy());
+        }
+        assert!(matches!(
+            verdicts[0].findings[..],
+            [PolicyFinding::DriftNearLimit { dw, warn }] if (dw - 0.09).abs() < 1e-4 && (warn - 0.08).abs() < 1e-6
+        ));
+        assert!(matches!(
+            verdicts[1].findings[..],
+            [PolicyFinding::DriftOverLimit { max, .. }] if max == 0.10
+        ));
+    }
+
+    #[test]
+    fn swarm_limits_tighten_the_host_budget() {
+        let mut swarm = swarm_at_demand(dog(), 0.2);
+        swarm.max_energy_d = 0.15;
+        let verdict = swarm.check_policy();
+        assert_eq!(verdict.d_max, 0.15);
+        // The warning level never sits above the combined ceiling.
+        assert!(matches!(
+            verdict.findings[..],
+            [PolicyFinding::DemandOverLimit { max, .. }] if max == 0.15
+        ));
+    }
+
+    #[test]
+    fn unprotected_canine_host_is_blocked() {
+        let gov = GovernanceLayer {
+            guardian_did: None,
+            non_commercial: false,
+            no_entertainment: false,
+            ..dog()
+        };
+        let verdict = swarm_at_demand(gov, 0.1).check_policy();
+        assert_eq!(
+            verdict.findings,
+            vec![
+                PolicyFinding::CommercialUsePermitted,
+                PolicyFinding::EntertainmentUsePermitted,
+                PolicyFinding::NoGuardian,
+            ]
+        );
+        assert_eq!(verdict.violations().len(), 3);
+
+        // Humans may opt into either use.
+        let human = GovernanceLayer::human("did:example:owner");
+        assert!(governance_findings(&human).is_empty());
+    }
+
+    #[test]
+    fn wildlife_needs_a_guardian_too() {
+        let raven = GovernanceLayer {
+            guardian_did: None,
+            ..guarded(SpeciesId::CorvusCorax, HostType::Wildlife)
+        };
+        assert_eq!(governance_findings(&raven), vec![PolicyFinding::NoGuardian]);
+        assert!(
+            governance_findings(&guarded(SpeciesId::CorvusCorax, HostType::Wildlife)).is_empty()
+        );
+    }
+
+    #[test]
+    fn human_member_in_a_canine_swarm_is_a_mismatch() {
+        let mut swarm = swarm_at_demand(dog(), 0.1);
+        let stray = member("stray", GovernanceLayer::human("did:example:owner"), 1.0);
+        swarm.add_member(stray).unwrap();
+        let verdict = swarm.check_policy();
+        let mismatches: Vec<&PolicyFinding> = verdict
+            .findings
+            .iter()
+            .filter(|f| matches!(f, PolicyFinding::MemberHostMismatch { .. }))
+            .collect();
+        assert_eq!(
+            mismatches,
+            vec![&PolicyFinding::MemberHostMismatch {
+                member_id: "stray".to_string(),
+                species: SpeciesId::HomoSapiens,
+                host_type: HostType::Human,
+            }]
+        );
+        assert!(!verdict.allowed());
+        assert_eq!(verdict.host_type, HostType::NonHumanCompanion);
+    }
+}
@@ -0,0 +1,434 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use super::geometry::{self, Vec3};
+use super::nanopolygon::{Edge, Face};
+use super::validation::GeometryError;
+
+/// Un-normalised polygon normal (Newell's method); its length is twice the area.
+pub fn newell_normal(points: &[Vec3], ring: &[usize]) -> Vec3 {
+    let mut normal = [0.0_f64; 3];
+    for (i, &a) in ring.iter().enumerate() {
+        let b = ring[(i + 1) % ring.len()];
+        normal = geometry::add(normal, geometry::cross(points[a], points[b]));
+    }
+    normal
+}
+
+pub fn unit_normal(points: &[Vec3], ring: &[usize]) -> Vec3 {
+    let n = newell_normal(points, ring);
+    let len = geometry::norm(n);
+    if len > 0.0 {
+        geometry::scale(n, 1.0 / len)
+    } else {
+        [0.0, 0.0, 0.0]
+    }
+}
+
+/// Ear-clip one planar polygon, keeping its winding in every output triangle.
+/// Collinear or self-touching rings run out of ears and are rejected.
+pub fn ear_clip(points: &[Vec3], ring: &[usize]) -> Result<Vec<[usize; 3]>, GeometryError> {
+    if ring.len() < 3 {
+        return Ok(Vec::new());
+    }
+    if ring.len() == 3 {
+        return Ok(vec![[ring[0], ring[1], ring[2]]]);
+    }
+
+    // Project onto the polygon plane so the ring runs counter-clockwise.
+    let n = unit_normal(points, ring);
+    let seed = if n[0].abs() < 0.9 {
+        [1.0, 0.0, 0.0]
+    } else {
+        [0.0, 1.0, 0.0]
+    };
+    let u = geometry::cross(n, seed);
+    let u = geometry::scale(u, 1.0 / geometry::norm(u).max(f64::MIN_POSITIVE));
+    let v = geometry::cross(n, u);
+    let flat: HashMap<usize, [f64; 2]> = ring
+        .iter()
+        .map(|&i| {
+            (
+                i,
+                [geometry::dot(points[i], u), geometry::dot(points[i], v)],
+            )
+        })
+        .collect();
+
+    let mut remaining: Vec<usize> = ring.to_vec();
+    let mut triangles = Vec::with_capacity(ring.len() - 2);
+
+    while remaining.len() > 3 {
+        let count = remaining.len();
+        let ear = (0..count).find(|&k| {
+            let prev = remaining[(k + count - 1) % count];
+            let curr = remaining[k];
+            let next = remaining[(k + 1) % count];
+            let (a, b, c) = (flat[&prev], flat[&curr], flat[&next]);
+            if cross_2d(a, b, c) <= 0.0 {
+                return false;
+            }
+            remaining
+                .iter()
+                .filter(|&&p| p != prev && p != curr && p != next)
+                .all(|p| !point_in_triangle(flat[p], a, b, c))
+        });
+
+        let Some(k) = ear else {
+            return Err(GeometryError::DegenerateRing {
+                vertices: remaining,
+            });
+        };
+        let prev = remaining[(k + count - 1) % count];
+        let next = remaining[(k + 1) % count];
+        triangles.push([prev, remaining[k], next]);
+        remaining.remove(k);
+    }
+
+    triangles.push([remaining[0], remaining[1], remaining[2]]);
+    Ok(triangles)
+}
+
+/// Triangles for area, curvature and rendering.
+/// Explicit faces win; otherwise the edge graph's 3-cycles or its single loop are used.
+/// Rings that cannot be ear-clipped are left out; validation reports them.
+pub fn triangulate(points: &[Vec3], edges: &[Edge], faces: &[Face]) -> Vec<[usize; 3]> {
+    if !faces.is_empty() {
+        return faces
+            .iter()
+            .filter(|f| f.vertex_indices.iter().all(|&i| i < points.len()))
+            .filter_map(|f| ear_clip(points, &f.vertex_indices).ok())
+            .flatten()
+            .collect();
+    }
+
+    let triangles = geometry::triangles_from_edges(points.len(), edges);
+    if !triangles.is_empty() {
+        return triangles;
+    }
+
+    match geometry::boundary_loop(points.len(), edges) {
+        Some(ring) => ear_clip(points, &ring).unwrap_or_default(),
+        None => Vec::new(),
+    }
+}
+
+/// Unique undirected edges along face boundaries, in first-seen order.
+pub fn edges_from_faces(faces: &[Face]) -> Vec<Edge> {
+    let mut seen = HashMap::new();
+    let mut edges = Vec::new();
+    for face in faces {
+        let ring = &face.vertex_indices;
+        for (i, &a) in ring.iter().enumerate() {
+            let b = ring[(i + 1) % ring.len()];
+            if seen.insert(geometry::edge_key(a, b), ()).is_none() {
+                edges.push(Edge {
+                    start_index: a,
+                    end_index: b,
+                });
+            }
+        }
+    }
+    edges
+}
+
+/// How many faces use each undirected edge.
+pub fn edge_face_counts(faces: &[Face]) -> HashMap<(usize, usize), usize> {
+    let mut counts = HashMap::new();
+    for face in faces {
+        let ring = &face.vertex_indices;
+        for (i, &a) in ring.iter().enumerate() {
+            let b = ring[(i + 1) % ring.len()];
+            *counts.entry(geometry::edge_key(a, b)).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+/// A closed surface has every edge shared by exactly two faces.
+pub fn is_closed_surface(faces: &[Face]) -> bool {
+    let counts = edge_face_counts(faces);
+    !counts.is_empty() && counts.values().all(|&n| n == 2)
+}
+
+/// Pairs of faces that run a shared edge in the same direction.
+pub fn winding_conflicts(faces: &[Face]) -> Vec<(usize, usize)> {
+    let mut directed: HashMap<(usize, usize), usize> = HashMap::new();
+    let mut conflicts = Vec::new();
+    for (f, face) in faces.iter().enumerate() {
+        let ring = &face.vertex_indices;
+        for (i, &a) in ring.iter().enumerate() {
+            let b = ring[(i + 1) % ring.len()];
+            if let Some(&first) = directed.get(&(a, b)) {
+                conflicts.push((first, f));
+            } else {
+                directed.insert((a, b), f);
+            }
+        }
+    }
+    conflicts
+}
+
+/// V - E + F over the vertices, edges and faces the faces use.
+pub fn euler_characteristic(faces: &[Face]) -> i64 {
+    let vertices: HashSet<usize> = faces
+        .iter()
+        .flat_map(|f| f.vertex_indices.iter().copied())
+        .collect();
+    vertices.len() as i64 - edge_face_counts(faces).len() as i64 + faces.len() as i64
+}
+
+/// Number of handles of a closed, connected, orientable surface, from
+/// chi = 2 - 2g; None for any other surface.
+pub fn genus(faces: &[Face]) -> Option<usize> {
+    if !is_closed_surface(faces) || face_components(faces).iter().any(|&c| c != 0) {
+        return None;
+    }
+    orient_faces(&mut faces.to_vec()).ok()?;
+    usize::try_from((2 - euler_characteristic(faces)) / 2).ok()
+}
+
+/// Flip faces so neighbours traverse each shared edge in opposite directions.
+/// Each connected patch keeps the winding of its lowest-numbered face.
+pub fn orient_faces(faces: &mut [Face]) -> Result<(), GeometryError> {
+    let mut by_edge: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+    for (f, face) in faces.iter().enumerate() {
+        let ring = &face.vertex_indices;
+        for (i, &a) in ring.iter().enumerate() {
+            let b = ring[(i + 1) % ring.len()];
+            by_edge.entry(geometry::edge_key(a, b)).or_default().push(f);
+        }
+    }
+
+    let mut visited = vec![false; faces.len()];
+    for seed in 0..faces.len() {
+        if visited[seed] {
+            continue;
+        }
+        visited[seed] = true;
+        let mut queue = VecDeque::from([seed]);
+
+        while let Some(f) = queue.pop_front() {
+            let ring = faces[f].vertex_indices.clone();
+            for (i, &a) in ring.iter().enumerate() {
+                let b = ring[(i + 1) % ring.len()];
+                for &g in &by_edge[&geometry::edge_key(a, b)] {
+                    if g == f {
+                        continue;
+                    }
+                    let same_direction = runs_edge(&faces[g], a, b);
+                    if visited[g] {
+                        if same_direction {
+                            return Err(GeometryError::NonOrientableSurface { face: g });
+                        }
+                    } else {
+                        if same_direction {
+                            faces[g].vertex_indices.reverse();
+                        }
+                        visited[g] = true;
+                        queue.push_back(g);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Label each face with the edge-connected patch it belongs to, numbered
+/// from 0 in order of each patch's lowest face.
+pub fn face_components(faces: &[Face]) -> Vec<usize> {
+    let mut by_edge: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+    for (f, face) in faces.iter().enumerate() {
+        let ring = &face.vertex_indices;
+        for (i, &a) in ring.iter().enumerate() {
+            let b = ring[(i + 1) % ring.len()];
+            by_edge.entry(geometry::edge_key(a, b)).or_default().push(f);
+        }
+    }
+
+    let mut labels = vec![usize::MAX; faces.len()];
+    let mut next = 0;
+    for seed in 0..faces.len() {
+        if labels[seed] != usize::MAX {
+            continue;
+        }
+        labels[seed] = next;
+        let mut queue = VecDeque::from([seed]);
+        while let Some(f) = queue.pop_front() {
+            let ring = &faces[f].vertex_indices;
+            for (i, &a) in ring.iter().enumerate() {
+                let b = ring[(i + 1) % ring.len()];
+                for &g in &by_edge[&geometry::edge_key(a, b)] {
+                    if labels[g] == usize::MAX {
+                        labels[g] = next;
+                        queue.push_back(g);
+                    }
+                }
+            }
+        }
+        next += 1;
+    }
+    labels
+}
+
+fn runs_edge(face: &Face, a: usize, b: usize) -> bool {
+    let ring = &face.vertex_indices;
+    (0..ring.len()).any(|i| ring[i] == a && ring[(i + 1) % ring.len()] == b)
+}
+
+fn cross_2d(a: [f64; 2], b: [f64; 2], c: [f64; 2]) -> f64 {
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
+
+fn point_in_triangle(p: [f64; 2], a: [f64; 2], b: [f64; 2], c: [f64; 2]) -> bool {
+    cross_2d(a, b, p) >= 0.0 && cross_2d(b, c, p) >= 0.0 && cross_2d(c, a, p) >= 0.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rings(faces: &[&[usize]]) -> Vec<Face> {
+        faces
+            .iter()
+            .map(|r| Face {
+                vertex_indices: r.to_vec(),
+            })
+            .collect()
+    }
+
+    fn area(points: &[Vec3], triangles: &[[usize; 3]]) -> Vec3 {
+        triangles.iter().fold([0.0; 3], |acc, t| {
+            geometry::add(acc, newell_normal(points, t))
+        })
+    }
+
+    fn cube() -> Vec<Face> {
+        rings(&[
+            &[0, 3, 2, 1],
+            &[4, 5, 6, 7],
+            &[0, 1, 5, 4],
+            &[1, 2, 6, 5],
+            &[2, 3, 7, 6],
+            &[3, 0, 4, 7],
+        ])
+    }
+
+    // 3 x 3 grid with both sides wrapped, each cell split in two.
+    fn torus() -> Vec<Face> {
+        let at = |i: usize, j: usize| (i % 3) * 3 + j % 3;
+        let mut faces = Vec::new();
+        for i in 0..3 {
+            for j in 0..3 {
+                faces.push(Face {
+                    vertex_indices: vec![at(i, j), at(i + 1, j), at(i + 1, j + 1)],
+                });
+                faces.push(Face {
+                    vertex_indices: vec![at(i, j), at(i + 1, j + 1), at(i, j + 1)],
+                });
+            }
+        }
+        faces
+    }
+
+    #[test]
+    fn ear_clip_keeps_winding_and_area_of_concave_ring() {
+        // L-shaped hexagon, counter-clockwise seen from +z; area 3.
+        let points: Vec<Vec3> = [
+            [0.0, 0.0],
+            [2.0, 0.0],
+            [2.0, 1.0],
+            [1.0, 1.0],
+            [1.0, 2.0],
+            [0.0, 2.0],
+        ]
+        .iter()
+        .map(|p| [p[0], p[1], 0.0])
+        .collect();
+        let ring: Vec<usize> = (0..6).collect();
+        let triangles = ear_clip(&points, &ring).unwrap();
+        assert_eq!(triangles.len(), 4);
+        for t in &triangles {
+            assert!(newell_normal(&points, t)[2] > 0.0);
+        }
+        assert!((area(&points, &triangles)[2] / 2.0 - 3.0).abs() < 1e-12);
+
+        let reversed: Vec<usize> = ring.iter().rev().copied().collect();
+        let flipped = ear_clip(&points, &reversed).unwrap();
+        assert!((area(&points, &flipped)[2] / 2.0 + 3.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn ear_clip_rejects_collinear_ring() {
+        let points: Vec<Vec3> = (0..4).map(|i| [i as f64, 0.0, 0.0]).collect();
+        assert_eq!(
+            ear_clip(&points, &[0, 1, 2, 3]),
+            Err(GeometryError::DegenerateRing {
+                vertices: vec![0, 1, 2, 3]
+            })
+        );
+        assert_eq!(ear_clip(&points, &[0, 1]), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn orient_faces_repairs_flipped_cube_faces() {
+        let mut faces = cube();
+        faces[2].vertex_indices.reverse();
+        faces[4].vertex_indices.reverse();
+        assert!(!winding_conflicts(&faces).is_empty());
+
+        orient_faces(&mut faces).unwrap();
+        assert!(winding_conflicts(&faces).is_empty());
+        // The lowest face keeps its winding.
+        assert_eq!(faces[0].vertex_indices, vec![0, 3, 2, 1]);
+        assert_eq!(faces[2].vertex_indices, vec![0, 1, 5, 4]);
+    }
+
+    #[test]
+    fn moebius_strip_cannot_be_oriented() {
+        let mut faces: Vec<Face> = (0..5)
+            .map(|i| Face {
+                vertex_indices: vec![i, (i + 1) % 5, (i + 2) % 5],
+            })
+            .collect();
+        assert!(matches!(
+            orient_faces(&mut faces),
+            Err(GeometryError::NonOrientableSurface { .. })
+        ));
+        assert!(!is_closed_surface(&faces));
+        assert_eq!(genus(&faces), None);
+    }
+
+    #[test]
+    fn euler_characteristic_and_genus() {
+        let tetra = rings(&[&[0, 2, 1], &[0, 1, 3], &[0, 3, 2], &[1, 2, 3]]);
+        assert_eq!(euler_characteristic(&tetra), 2);
+        assert_eq!(genus(&tetra), Some(0));
+
+        assert_eq!(euler_characteristic(&cube()), 2);
+        assert_eq!(genus(&cube()), Some(0));
+
+        let torus = torus();
+        assert!(is_closed_surface(&torus));
+        assert_eq!(euler_characteristic(&torus), 0);
+        assert_eq!(genus(&torus), Some(1));
+
+        // Open, or closed but in two pieces: no single genus.
+        let open = rings(&[&[0, 1, 2], &[0, 2, 3]]);
+        assert_eq!(euler_characteristic(&open), 1);
+        assert_eq!(genus(&open), None);
+        let mut two = tetra.clone();
+        two.extend(rings(&[&[4, 6, 5], &[4, 5, 7], &[4, 7, 6], &[5, 6, 7]]));
+        assert_eq!(euler_characteristic(&two), 4);
+        assert_eq!(genus(&two), None);
+    }
+
+    #[test]
+    fn edges_and_counts_follow_face_boundaries() {
+        let faces = cube();
+        assert_eq!(edges_from_faces(&faces).len(), 12);
+        assert!(edge_face_counts(&faces).values().all(|&n| n == 2));
+        assert_eq!(face_components(&faces), vec![0; 6]);
+    }
+}
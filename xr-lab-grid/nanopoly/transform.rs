@@ -0,0 +1,357 @@
+use serde::{Deserialize, Serialize};
+
+use super::geometry::{self, Vec3};
+use super::nanopolygon::{Nanopolygon, VertexNm};
+
+/// Unit quaternion rotation (w + xi + yj + zk).
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Quaternion {
+    pub w: f64,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Quaternion {
+    pub fn identity() -> Self {
+        Self {
+            w: 1.0,
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        }
+    }
+
+    /// Rotation of `angle_rad` about `axis` (need not be unit length).
+    pub fn from_axis_angle(axis: Vec3, angle_rad: f64) -> Self {
+        let len = geometry::norm(axis);
+        if len <= 0.0 {
+            return Self::identity();
+        }
+        let (s, c) = (0.5 * angle_rad).sin_cos();
+        let k = s / len;
+        Self {
+            w: c,
+            x: axis[0] * k,
+            y: axis[1] * k,
+            z: axis[2] * k,
+        }
+    }
+
+    pub fn normalized(&self) -> Self {
+        let len = (self.w * self.w + self.x * self.x + self.y * self.y + self.z * self.z).sqrt();
+        if len <= 0.0 {
+            return Self::identity();
+        }
+        Self {
+            w: self.w / len,
+            x: self.x / len,
+            y: self.y / len,
+            z: self.z / len,
+        }
+    }
+
+    pub fn conjugate(&self) -> Self {
+        Self {
+            w: self.w,
+            x: -self.x,
+            y: -self.y,
+            z: -self.z,
+        }
+    }
+
+    /// Hamilton product: applying the result rotates by `other` first, then `self`.
+    pub fn multiply(&self, other: &Quaternion) -> Self {
+        Self {
+            w: self.w * other.w - self.x * other.x - self.y * other.y - self.z * other.z,
+            x: self.w * other.x + self.x * other.w + self.y * other.z - self.z * other.y,
+            y: self.w * other.y - self.x * other.z + self.y * other.w + self.z * other.x,
+            z: self.w * other.z + self.x * other.y - self.y * other.x + self.z * other.w,
+        }
+    }
+
+    pub fn rotate(&self, v: Vec3) -> Vec3 {
+        // v' = v + 2w(q x v) + 2 q x (q x v), with q the vector part.
+        let q = [self.x, self.y, self.z];
+        let t = geometry::scale(geometry::cross(q, v), 2.0);
+        geometry::add(
+            geometry::add(v, geometry::scale(t, self.w)),
+            geometry::cross(q, t),
+        )
+    }
+
+    /// Row-major 3x3 rotation matrix.
+    pub fn to_matrix(&self) -> [[f64; 3]; 3] {
+        let (w, x, y, z) = (self.w, self.x, self.y, self.z);
+        [
+            [
+                1.0 - 2.0 * (y * y + z * z),
+                2.0 * (x * y - w * z),
+                2.0 * (x * z + w * y),
+            ],
+            [
+                2.0 * (x * y + w * z),
+                1.0 - 2.0 * (x * x + z * z),
+                2.0 * (y * z - w * x),
+            ],
+            [
+                2.0 * (x * z - w * y),
+                2.0 * (y * z + w * x),
+                1.0 - 2.0 * (x * x + y * y),
+            ],
+        ]
+    }
+}
+
+/// Rotation followed by translation, in nm.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RigidTransform {
+    pub rotation: Quaternion,
+    pub translation_nm: Vec3,
+}
+
+impl RigidTransform {
+    pub fn identity() -> Self {
+        Self {
+            rotation: Quaternion::identity(),
+            translation_nm: [0.0, 0.0, 0.0],
+        }
+    }
+
+    pub fn new(rotation: Quaternion, translation_nm: Vec3) -> Self {
+        Self {
+            rotation: rotation.normalized(),
+            translation_nm,
+        }
+    }
+
+    pub fn apply(&self, p: Vec3) -> Vec3 {
+        geometry::add(self.rotation.rotate(p), self.translation_nm)
+    }
+
+    /// `self` after `first`.
+    pub fn compose(&self, first: &RigidTransform) -> Self {
+        Self {
+            rotation: self.rotation.multiply(&first.rotation).normalized(),
+            translation_nm: self.apply(first.translation_nm),
+        }
+    }
+
+    pub fn inverse(&self) -> Self {
+        let inv = self.rotation.conjugate();
+        Self {
+            rotation: inv,
+            translation_nm: geometry::scale(inv.rotate(self.translation_nm), -1.0),
+        }
+    }
+}
+
+impl VertexNm {
+    pub fn from_vec3(p: Vec3) -> Self {
+        Self {
+            x_nm: p[0],
+            y_nm: p[1],
+            z_nm: p[2],
+        }
+    }
+
+    pub fn to_vec3(&self) -> Vec3 {
+        geometry::to_vec3(self)
+    }
+
+    pub fn translated(&self, offset_nm: Vec3) -> Self {
+        Self::from_vec3(geometry::add(self.to_vec3(), offset_nm))
+    }
+
+    /// Rotate about the origin.
+    pub fn rotated(&self, rotation: &Quaternion) -> Self {
+        Self::from_vec3(rotation.rotate(self.to_vec3()))
+    }
+
+    /// Scale about `center_nm`.
+    pub fn scaled(&self, factor: f64, center_nm: Vec3) -> Self {
+        let offset = geometry::sub(self.to_vec3(), center_nm);
+        Self::from_vec3(geometry::add(center_nm, geometry::scale(offset, factor)))
+    }
+
+    pub fn transformed(&self, transform: &RigidTransform) -> Self {
+        Self::from_vec3(transform.apply(self.to_vec3()))
+    }
+
+    pub fn distance_to(&self, other: &VertexNm) -> f64 {
+        geometry::norm(geometry::sub(self.to_vec3(), other.to_vec3()))
+    }
+}
+
+impl Nanopolygon {
+    pub fn translate(&mut self, offset_nm: Vec3) {
+        for v in self.vertices.iter_mut() {
+            *v = v.translated(offset_nm);
+        }
+    }
+
+    /// Rotate about the origin of the lab frame.
+    pub fn rotate(&mut self, rotation: &Quaternion) {
+        self.rotate_about(rotation, [0.0, 0.0, 0.0]);
+    }
+
+    /// Rotate about `pivot_nm`; area and curvature are unchanged.
+    pub fn rotate_about(&mut self, rotation: &Quaternion, pivot_nm: Vec3) {
+        let q = rotation.normalized();
+        for v in self.vertices.iter_mut() {
+            let local = geometry::sub(v.to_vec3(), pivot_nm);
+            *v = VertexNm::from_vec3(geometry::add(q.rotate(local), pivot_nm));
+        }
+    }
+
+    pub fn apply_transform(&mut self, transform: &RigidTransform) {
+        for v in self.vertices.iter_mut() {
+            *v = v.transformed(transform);
+        }
+    }
+
+    /// Uniform scale about `center_nm`. Area and curvature are recomputed;
+    /// a negative factor mirrors the shape, so face winding is reversed too.
+    pub fn scale_about(&mut self, factor: f64, center_nm: Vec3) {
+        for v in self.vertices.iter_mut() {
+            *v = v.scaled(factor, center_nm);
+        }
+        if factor < 0.0 {
+            for f in self.faces.iter_mut() {
+                f.vertex_indices.reverse();
+            }
+        }
+        self.refresh_geometry();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xr_lab_grid::nanopoly::generators::{self, PlatonicSolid};
+    use crate::xr_lab_grid::nanopoly::nanopolygon::{BioAffinityTarget, BiophysicalMetadata};
+    use std::f64::consts::FRAC_PI_2;
+
+    fn close(a: Vec3, b: Vec3) -> bool {
+        geometry::norm(geometry::sub(a, b)) < 1e-9
+    }
+
+    fn bio() -> BiophysicalMetadata {
+        BiophysicalMetadata {
+            target: BioAffinityTarget::EndothelialCell,
+            zeta_potential_mv: -15.0,
+            hydrophobicity_index: 0.4,
+            elastic_modulus_kpa: 5.0,
+            ligands: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn quarter_turns_follow_the_right_hand_rule() {
+        let about_z = Quaternion::from_axis_angle([0.0, 0.0, 3.0], FRAC_PI_2);
+        assert!(close(about_z.rotate([1.0, 0.0, 0.0]), [0.0, 1.0, 0.0]));
+        let about_x = Quaternion::from_axis_angle([1.0, 0.0, 0.0], FRAC_PI_2);
+        assert!(close(about_x.rotate([0.0, 1.0, 0.0]), [0.0, 0.0, 1.0]));
+
+        // `multiply` applies its argument first: z then x sends x to z.
+        let both = about_x.multiply(&about_z);
+        assert!(close(both.rotate([1.0, 0.0, 0.0]), [0.0, 0.0, 1.0]));
+        assert!(close(
+            about_z.conjugate().rotate([0.0, 1.0, 0.0]),
+            [1.0, 0.0, 0.0]
+        ));
+
+        // A zero axis is no rotation.
+        assert_eq!(
+            Quaternion::from_axis_angle([0.0; 3], 1.0),
+            Quaternion::identity()
+        );
+    }
+
+    #[test]
+    fn matrix_agrees_with_rotate() {
+        let q = Quaternion {
+            w: 0.3,
+            x: -1.2,
+            y: 0.5,
+            z: 2.0,
+        }
+        .normalized();
+        let m = q.to_matrix();
+        for v in [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.3, -2.0, 5.0]] {
+            let by_matrix = [0, 1, 2].map(|r| geometry::dot(m[r], v));
+            assert!(close(by_matrix, q.rotate(v)));
+        }
+        // Rotations keep lengths.
+        assert!((geometry::norm(q.rotate([3.0, 4.0, 12.0])) - 13.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn compose_and_inverse_round_trip() {
+        let first = RigidTransform::new(
+            Quaternion::from_axis_angle([1.0, 1.0, 0.0], 0.7),
+            [5.0, -2.0, 1.0],
+        );
+        let second = RigidTransform::new(
+            Quaternion::from_axis_angle([0.0, 0.0, 1.0], -1.1),
+            [0.0, 10.0, 0.0],
+        );
+        let p = [2.0, 3.0, -4.0];
+        let composed = second.compose(&first);
+        assert!(close(composed.apply(p), second.apply(first.apply(p))));
+        assert!(close(first.inverse().apply(first.apply(p)), p));
+        assert!(close(composed.inverse().apply(composed.apply(p)), p));
+
+        // `new` normalises the rotation it is given.
+        let scaled = Quaternion {
+            w: 2.0,
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        };
+        assert_eq!(
+            RigidTransform::new(scaled, [0.0; 3]).rotation,
+            Quaternion::identity()
+        );
+    }
+
+    #[test]
+    fn rigid_motion_keeps_area_and_curvature() {
+        let original = generators::platonic("d", PlatonicSolid::Dodecahedron, 6.0, bio()).unwrap();
+        let pivot = [6.0, 0.0, 0.0];
+        let mut turned = original.clone();
+        turned.rotate_about(&Quaternion::from_axis_angle([0.0, 1.0, 1.0], 0.9), pivot);
+        turned.refresh_geometry();
+        assert!((turned.surface_area_nm2 - original.surface_area_nm2).abs() < 1e-9);
+        assert!((turned.mean_curvature - original.mean_curvature).abs() < 1e-12);
+
+        // Every vertex keeps its distance to the pivot, but not to the origin.
+        let p = VertexNm::from_vec3(pivot);
+        let origin = VertexNm::from_vec3([0.0; 3]);
+        let mut moved = false;
+        for (a, b) in original.vertices.iter().zip(&turned.vertices) {
+            assert!((a.distance_to(&p) - b.distance_to(&p)).abs() < 1e-9);
+            moved |= (a.distance_to(&origin) - b.distance_to(&origin)).abs() > 1e-3;
+        }
+        assert!(moved);
+    }
+
+    #[test]
+    fn scaling_rescales_area_and_curvature_and_mirroring_keeps_volume() {
+        let cube = generators::platonic("c", PlatonicSolid::Cube, 3.0, bio()).unwrap();
+        let volume = cube.volume_nm3().unwrap();
+
+        let mut doubled = cube.clone();
+        doubled.scale_about(2.0, [1.0, 1.0, 1.0]);
+        assert!((doubled.surface_area_nm2 - 4.0 * cube.surface_area_nm2).abs() < 1e-9);
+        assert!((doubled.mean_curvature - 0.5 * cube.mean_curvature).abs() < 1e-12);
+        assert!((doubled.volume_nm3().unwrap() - 8.0 * volume).abs() < 1e-9);
+
+        let mut mirrored = cube.clone();
+        mirrored.scale_about(-1.0, [0.0; 3]);
+        let mut reversed = cube.faces[0].vertex_indices.clone();
+        reversed.reverse();
+        assert_eq!(mirrored.faces[0].vertex_indices, reversed);
+        assert!((mirrored.volume_nm3().unwrap() - volume).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,537 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use super::geometry;
+use super::nanopolygon::{Edge, Face, VertexNm};
+use super::topology;
+
+/// Two vertices closer than this are treated as the same point.
+pub const DUPLICATE_VERTEX_TOLERANCE_NM: f64 = 1e-6;
+
+/// One geometric defect found while validating a nanopolygon.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GeometryError {
+    TooFewVertices {
+        count: usize,
+    },
+    NonFiniteCoordinate {
+        vertex: usize,
+    },
+    DuplicateVertex {
+        first: usize,
+        second: usize,
+    },
+    EdgeIndexOutOfRange {
+        edge: usize,
+        index: usize,
+        vertex_count: usize,
+    },
+    ZeroLengthEdge {
+        edge: usize,
+    },
+    DuplicateEdge {
+        first: usize,
+        second: usize,
+    },
+    NonManifoldEdge {
+        start: usize,
+        end: usize,
+        face_count: usize,
+    },
+    FaceTooSmall {
+        face: usize,
+        count: usize,
+    },
+    FaceIndexOutOfRange {
+        face: usize,
+        index: usize,
+        vertex_count: usize,
+    },
+    InconsistentWinding {
+        first: usize,
+        second: usize,
+    },
+    NonOrientableSurface {
+        face: usize,
+    },
+    DegenerateRing {
+        vertices: Vec<usize>,
+    },
+}
+
+impl fmt::Display for GeometryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GeometryError::TooFewVertices { count } => {
+                write!(f, "nanopolygon needs at least 3 vertices, got {}", count)
+            }
+            GeometryError::NonFiniteCoordinate { vertex } => {
+                write!(f, "vertex {} has a NaN or infinite coordinate", vertex)
+            }
+            GeometryError::DuplicateVertex { first, second } => {
+                write!(f, "vertices {} and {} coincide", first, second)
+            }
+            GeometryError::EdgeIndexOutOfRange {
+                edge,
+                index,
+                vertex_count,
+            } => write!(
+                f,
+                "edge {} references vertex {} but only {} vertices exist",
+                edge, index, vertex_count
+            ),
+            GeometryError::ZeroLengthEdge { edge } => {
+                write!(f, "edge {} has zero length", edge)
+            }
+            GeometryError::DuplicateEdge { first, second } => {
+                write!(f, "edges {} and {} join the same vertices", first, second)
+            }
+            GeometryError::NonManifoldEdge {
+                start,
+                end,
+                face_count,
+            } => write!(
+                f,
+                "edge ({}, {}) is shared by {} faces; a manifold allows at most 2",
+                start, end, face_count
+            ),
+            GeometryError::FaceTooSmall { face, count } => {
+                write!(
+                    f,
+                    "face {} has {} vertices; at least 3 are needed",
+                    face, count
+                )
+            }
+            GeometryError::FaceIndexOutOfRange {
+                face,
+                index,
+                vertex_count,
+            } => write!(
+                f,
+                "face {} references vertex {} but only {} vertices exist",
+                face, index, vertex_count
+            ),
+            GeometryError::InconsistentWinding { first, second } => write!(
+                f,
+                "faces {} and {} traverse a shared edge in the same direction",
+                first, second
+            ),
+            GeometryError::NonOrientableSurface { face } => {
+                write!(
+                    f,
+                    "surface cannot be consistently oriented at face {}",
+                    face
+                )
+            }
+            GeometryError::DegenerateRing { vertices } => write!(
+                f,
+                "vertex ring {:?} is collinear or self-touching and cannot be triangulated",
+                vertices
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GeometryError {}
+
+/// Collect every defect in a vertex/edge/face set; empty means the shape is usable.
+pub fn validate_geometry(
+    vertices: &[VertexNm],
+    edges: &[Edge],
+    faces: &[Face],
+) -> Vec<GeometryError> {
+    let mut errors = Vec::new();
+    let vertex_count = vertices.len();
+
+    if vertex_count < 3 {
+        errors.push(GeometryError::TooFewVertices {
+            count: vertex_count,
+        });
+    }
+
+    let mut finite = vec![true; vertex_count];
+    for (i, v) in vertices.iter().enumerate() {
+        if !(v.x_nm.is_finite() && v.y_nm.is_finite() && v.z_nm.is_finite()) {
+            finite[i] = false;
+            errors.push(GeometryError::NonFiniteCoordinate { vertex: i });
+        }
+    }
+
+    errors.extend(duplicate_vertices(vertices, &finite));
+
+    let mut seen: HashMap<(usize, usize), usize> = HashMap::new();
+    let mut well_formed = true;
+    for (i, e) in edges.iter().enumerate() {
+        let mut in_range = true;
+        for index in [e.start_index, e.end_index] {
+            if index >= vertex_count {
+                in_range = false;
+                errors.push(GeometryError::EdgeIndexOutOfRange {
+                    edge: i,
+                    index,
+                    vertex_count,
+                });
+            }
+        }
+        if !in_range {
+            well_formed = false;
+            continue;
+        }
+
+        let (a, b) = (e.start_index, e.end_index);
+        let degenerate = a == b
+            || (finite[a]
+                && finite[b]
+                && geometry::norm(geometry::sub(
+                    geometry::to_vec3(&vertices[a]),
+                    geometry::to_vec3(&vertices[b]),
+                )) <= DUPLICATE_VERTEX_TOLERANCE_NM);
+        if degenerate {
+            errors.push(GeometryError::ZeroLengthEdge { edge: i });
+        }
+
+        match seen.get(&geometry::edge_key(a, b)) {
+            Some(&first) => errors.push(GeometryError::DuplicateEdge { first, second: i }),
+            None => {
+                seen.insert(geometry::edge_key(a, b), i);
+            }
+        }
+    }
+
+    let points: Vec<geometry::Vec3> = vertices.iter().map(geometry::to_vec3).collect();
+    for (f, face) in faces.iter().enumerate() {
+        if face.vertex_indices.len() < 3 {
+            errors.push(GeometryError::FaceTooSmall {
+                face: f,
+                count: face.vertex_indices.len(),
+            });
+        }
+        let mut in_range = true;
+        for &index in &face.vertex_indices {
+            if index >= vertex_count {
+                in_range = false;
+                errors.push(GeometryError::FaceIndexOutOfRange {
+                    face: f,
+                    index,
+                    vertex_count,
+                });
+            }
+        }
+        if !in_range {
+            well_formed = false;
+        } else if face.vertex_indices.iter().all(|&i| finite[i]) {
+            if let Err(e) = topology::ear_clip(&points, &face.vertex_indices) {
+                errors.push(e);
+            }
+        }
+    }
+
+    // Face-based checks only make sense once the index lists themselves are sound.
+    if well_formed {
+        let face_count = if faces.is_empty() {
+            let triangles: Vec<Face> = geometry::triangles_from_edges(vertex_count, edges)
+                .into_iter()
+                .map(|t| Face {
+                    vertex_indices: t.to_vec(),
+                })
+                .collect();
+            topology::edge_face_counts(&triangles)
+        } else {
+            topology::edge_face_counts(faces)
+        };
+        let mut shared: Vec<_> = face_count.into_iter().filter(|(_, n)| *n > 2).collect();
+        shared.sort();
+        for ((start, end), face_count) in shared {
+            errors.push(GeometryError::NonManifoldEdge {
+                start,
+                end,
+                face_count,
+            });
+        }
+
+        for (first, second) in topology::winding_conflicts(faces) {
+            errors.push(GeometryError::InconsistentWinding { first, second });
+        }
+    }
+
+    errors
+}
+
+fn duplicate_vertices(vertices: &[VertexNm], finite: &[bool]) -> Vec<GeometryError> {
+    // Sweep along x so only near neighbours are compared.
+    let mut order: Vec<usize> = (0..vertices.len()).filter(|&i| finite[i]).collect();
+    order.sort_by(|&a, &b| vertices[a].x_nm.total_cmp(&vertices[b].x_nm));
+
+    let mut errors = Vec::new();
+    for (n, &i) in order.iter().enumerate() {
+        for &j in &order[n + 1..] {
+            if vertices[j].x_nm - vertices[i].x_nm > DUPLICATE_VERTEX_TOLERANCE_NM {
+                break;
+            }
+            let distance = geometry::norm(geometry::sub(
+                geometry::to_vec3(&vertices[i]),
+                geometry::to_vec3(&vertices[j]),
+            ));
+            if distance <= DUPLICATE_VERTEX_TOLERANCE_NM {
+                errors.push(GeometryError::DuplicateVertex {
+                    first: i.min(j),
+                    second: i.max(j),
+                });
+            }
+        }
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xr_lab_grid::nanopoly::core::nanopoly_object::{
+        BciInterface, EnergeticProfile, NanopolyObject,
+    };
+    use crate::xr_lab_grid::nanopoly::lifecycle::LifecycleError;
+    use crate::xr_lab_grid::nanopoly::nanopolygon::{
+        BioAffinityTarget, BiophysicalMetadata, Nanopolygon,
+    };
+    use crate::xr_lab_grid::nanopoly::nanoswarm::{Nanoswarm, NanoswarmMember};
+    use crate::xr_lab_grid::nanopoly::transform::RigidTransform;
+
+    fn vertices(points: &[[f64; 3]]) -> Vec<VertexNm> {
+        points
+            .iter()
+            .map(|p| VertexNm {
+                x_nm: p[0],
+                y_nm: p[1],
+                z_nm: p[2],
+            })
+            .collect()
+    }
+
+    fn edges(pairs: &[(usize, usize)]) -> Vec<Edge> {
+        pairs
+            .iter()
+            .map(|&(a, b)| Edge {
+                start_index: a,
+                end_index: b,
+            })
+            .collect()
+    }
+
+    fn faces(rings: &[&[usize]]) -> Vec<Face> {
+        rings
+            .iter()
+            .map(|r| Face {
+                vertex_indices: r.to_vec(),
+            })
+            .collect()
+    }
+
+    fn triangle() -> Vec<VertexNm> {
+        vertices(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]])
+    }
+
+    // Five points off a common plane, so no edge or face is degenerate.
+    fn spread() -> Vec<VertexNm> {
+        vertices(&[
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.5, 1.0, 0.0],
+            [0.5, -1.0, 0.3],
+            [0.5, 0.2, 1.0],
+        ])
+    }
+
+    #[test]
+    fn closed_tetrahedron_is_clean() {
+        let v = vertices(&[
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0],
+        ]);
+        let f = faces(&[&[0, 2, 1], &[0, 1, 3], &[0, 3, 2], &[1, 2, 3]]);
+        let e = topology::edges_from_faces(&f);
+        assert!(validate_geometry(&v, &e, &f).is_empty());
+    }
+
+    #[test]
+    fn too_few_vertices() {
+        let v = vertices(&[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0]]);
+        let errors = validate_geometry(&v, &edges(&[(0, 1)]), &[]);
+        assert_eq!(errors, vec![GeometryError::TooFewVertices { count: 2 }]);
+    }
+
+    #[test]
+    fn non_finite_coordinate() {
+        let mut v = triangle();
+        v[1].y_nm = f64::NAN;
+        let errors = validate_geometry(&v, &edges(&[(0, 1), (1, 2), (2, 0)]), &[]);
+        assert_eq!(
+            errors,
+            vec![GeometryError::NonFiniteCoordinate { vertex: 1 }]
+        );
+    }
+
+    #[test]
+    fn duplicate_vertex() {
+        let mut v = triangle();
+        v.push(VertexNm {
+            x_nm: 1.0,
+            y_nm: 0.0,
+            z_nm: DUPLICATE_VERTEX_TOLERANCE_NM / 2.0,
+        });
+        let errors = validate_geometry(&v, &edges(&[(0, 1), (1, 2), (2, 0)]), &[]);
+        assert_eq!(
+            errors,
+            vec![GeometryError::DuplicateVertex {
+                first: 1,
+                second: 3
+            }]
+        );
+    }
+
+    #[test]
+    fn edge_index_out_of_range() {
+        let errors = validate_geometry(&triangle(), &edges(&[(0, 1), (1, 5)]), &[]);
+        assert_eq!(
+            errors,
+            vec![GeometryError::EdgeIndexOutOfRange {
+                edge: 1,
+                index: 5,
+                vertex_count: 3
+            }]
+        );
+    }
+
+    #[test]
+    fn zero_length_edge() {
+        let errors = validate_geometry(&triangle(), &edges(&[(0, 1), (2, 2)]), &[]);
+        assert_eq!(errors, vec![GeometryError::ZeroLengthEdge { edge: 1 }]);
+    }
+
+    #[test]
+    fn duplicate_edge() {
+        let errors = validate_geometry(&triangle(), &edges(&[(0, 1), (1, 2), (1, 0)]), &[]);
+        assert_eq!(
+            errors,
+            vec![GeometryError::DuplicateEdge {
+                first: 0,
+                second: 2
+            }]
+        );
+    }
+
+    #[test]
+    fn non_manifold_edge() {
+        // Three fins hinged on edge (0, 1).
+        let f = faces(&[&[0, 1, 2], &[1, 0, 3], &[1, 0, 4]]);
+        let errors = validate_geometry(&spread(), &topology::edges_from_faces(&f), &f);
+        assert!(errors.contains(&GeometryError::NonManifoldEdge {
+            start: 0,
+            end: 1,
+            face_count: 3
+        }));
+    }
+
+    #[test]
+    fn face_too_small() {
+        let f = faces(&[&[0, 1]]);
+        let errors = validate_geometry(&triangle(), &topology::edges_from_faces(&f), &f);
+        assert_eq!(
+            errors,
+            vec![GeometryError::FaceTooSmall { face: 0, count: 2 }]
+        );
+    }
+
+    #[test]
+    fn face_index_out_of_range() {
+        let f = faces(&[&[0, 1, 7]]);
+        let e = edges(&[(0, 1)]);
+        let errors = validate_geometry(&triangle(), &e, &f);
+        assert_eq!(
+            errors,
+            vec![GeometryError::FaceIndexOutOfRange {
+                face: 0,
+                index: 7,
+                vertex_count: 3
+            }]
+        );
+    }
+
+    #[test]
+    fn degenerate_ring() {
+        let v = vertices(&[
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [2.0, 0.0, 0.0],
+            [3.0, 0.0, 0.0],
+        ]);
+        let f = faces(&[&[0, 1, 2, 3]]);
+        let errors = validate_geometry(&v, &topology::edges_from_faces(&f), &f);
+        assert_eq!(
+            errors,
+            vec![GeometryError::DegenerateRing {
+                vertices: vec![0, 1, 2, 3]
+            }]
+        );
+    }
+
+    #[test]
+    fn inconsistent_winding() {
+        let f = faces(&[&[0, 1, 2], &[0, 1, 3]]);
+        let errors = validate_geometry(&spread(), &topology::edges_from_faces(&f), &f);
+        assert_eq!(
+            errors,
+            vec![GeometryError::InconsistentWinding {
+                first: 0,
+                second: 1
+            }]
+        );
+    }
+
+    #[test]
+    fn non_orientable_surface() {
+        // Five-vertex Moebius strip: triangles {i, i+1, i+2} mod 5.
+        let mut f: Vec<Face> = (0..5)
+            .map(|i| Face {
+                vertex_indices: vec![i, (i + 1) % 5, (i + 2) % 5],
+            })
+            .collect();
+        assert!(matches!(
+            topology::orient_faces(&mut f),
+            Err(GeometryError::NonOrientableSurface { .. })
+        ));
+    }
+
+    #[test]
+    fn swarm_rejects_defective_member() {
+        let bio = BiophysicalMetadata {
+            target: BioAffinityTarget::GlialCell,
+            zeta_potential_mv: -15.0,
+            hydrophobicity_index: 0.2,
+            elastic_modulus_kpa: 1.0,
+            ligands: Vec::new(),
+        };
+        let bad = Nanopolygon::new("bad", triangle(), edges(&[(0, 1), (1, 1)]), bio);
+        let mut swarm = Nanoswarm::new("host");
+        let member = NanoswarmMember::new(
+            NanopolyObject::from_parts(
+                "bad",
+                bad,
+                EnergeticProfile::constant(100.0),
+                BciInterface::none(),
+                swarm.governance.clone(),
+            ),
+            RigidTransform::identity(),
+        );
+
+        let expected = LifecycleError::InvalidGeometry {
+            id: "bad".to_string(),
+            errors: vec![GeometryError::ZeroLengthEdge { edge: 1 }],
+        };
+        assert_eq!(swarm.add_member(member.clone()), Err(expected.clone()));
+        assert_eq!(swarm.admit_member(member, 10), Err(expected));
+        assert!(swarm.members.is_empty());
+        assert!(swarm.events.is_empty());
+    }
+}
@@ -1,6 +1,10 @@
-use crate::xrlabgrid::nanopoly::nanopolygon::{Nanopolygon, BiophysicalMetadata};
+use crate::xr_lab_grid::nanopoly::nanopolygon::Nanopolygon;
 use crate::store::metrics::ResponseMetric;
-use crate::store::upgradestore::UpgradeModule;
+use crate::store::upgrade_store::UpgradeModule;
+use crate::xr_lab_grid::nanopoly::gltf_export::{self, GltfDocument, GltfExportOptions, SceneMember};
+use crate::xr_lab_grid::nanopoly::transform::Quaternion;
+use crate::xr_lab_grid::nanopoly::charge;
+use safety_core::types::{SafetyState, SwarmMode};
 
 #[derive(Clone, Debug)]
 pub struct XrGridCell {
@@ -17,10 +21,12 @@ impl XrGridCell {
     ) -> ResponseMetric {
         // Delegate to UpgradeStore semantics: K/D/DW based on biophysics + module deltas
         let allowed_targets = &module.allowed_targets;
-        let charge_ok = module.max_allowed_charge >= self.poly.bio.surfacecharge;
-        let target_ok = allowed_targets.iter().any(|t| *t == self.poly.bio.target);
+        let charge_ok =
+            charge::zeta_in_range(self.poly.bio.zeta_potential_mv, module.allowed_zeta_mv);
+        let target_ok = allowed_targets.contains(&self.poly.bio.target);
+        let chemistry_ok = module.chemistry_violations(&self.poly.bio).is_empty();
 
-        let (k, d, dw, notes) = if charge_ok && target_ok {
+        let (k, d, dw, notes) = if charge_ok && target_ok && chemistry_ok {
             (0.90, module.delta_energy_d, module.delta_dw,
              "XR-grid cell upgrade within nanopolygon constraints")
         } else {
@@ -31,3 +37,107 @@ impl XrGridCell {
         ResponseMetric::new(k, d, dw, notes)
     }
 }
+
+impl XrGridCell {
+    /// glTF scene entry for this cell, named by cell id.
+    pub fn scene_member<'a>(&'a self, safety: Option<&'a SafetyState>) -> SceneMember<'a> {
+        SceneMember {
+            name: self.id.clone(),
+            poly: &self.poly,
+            translation_nm: [0.0, 0.0, 0.0],
+            rotation: Quaternion::identity(),
+            safety,
+        }
+    }
+}
+
+/// Export a set of grid cells as one glTF scene, laid out in a row.
+/// `safety` is matched to cells by index.
+pub fn export_cells_gltf(
+    scene_name: &str,
+    cells: &[XrGridCell],
+    safety: &[SafetyState],
+    swarm_mode: &SwarmMode,
+    options: &GltfExportOptions,
+) -> GltfDocument {
+    let mut members: Vec<SceneMember> = cells
+        .iter()
+        .enumerate()
+        .map(|(i, c)| c.scene_member(safety.get(i)))
+        .collect();
+    gltf_export::arrange_in_row(&mut members);
+    gltf_export::build_scene(scene_name, &members, swarm_mode, options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xr_lab_grid::nanopoly::generators::{self, PlatonicSolid};
+    use crate::xr_lab_grid::nanopoly::gltf_export::GltfExportOptions;
+    use crate::xr_lab_grid::nanopoly::nanopolygon::{
+        BioAffinityTarget, BiophysicalMetadata, SurfaceChemistry, SurfaceLigand,
+    };
+
+    fn cell(id: &str, zeta_potential_mv: f32) -> XrGridCell {
+        let bio = BiophysicalMetadata {
+            target: BioAffinityTarget::NeuralMembrane,
+            zeta_potential_mv,
+            hydrophobicity_index: 0.3,
+            elastic_modulus_kpa: 0.5,
+            ligands: vec![SurfaceLigand {
+                chemistry: SurfaceChemistry::Peg,
+                density_per_nm2: 0.5,
+            }],
+        };
+        XrGridCell {
+            id: id.to_string(),
+            poly: generators::platonic(id, PlatonicSolid::Octahedron, 10.0, bio).unwrap(),
+            shard_path: format!("/shards/{}", id),
+            service_endpoint: "http://localhost:8080".to_string(),
+        }
+    }
+
+    fn module() -> UpgradeModule {
+        UpgradeModule {
+            id: "mod_test".to_string(),
+            label: "Test module".to_string(),
+            required_citizen_stake: 1,
+            delta_energy_d: 0.05,
+            delta_dw: 0.1,
+            allowed_targets: vec![BioAffinityTarget::NeuralMembrane],
+            allowed_zeta_mv: (-60.0, 10.0),
+            required_chemistries: vec![SurfaceChemistry::Peg],
+            forbidden_chemistries: vec![SurfaceChemistry::Amine],
+        }
+    }
+
+    #[test]
+    fn upgrade_respects_charge_window() {
+        let ok = cell("a", -20.0).evaluate_upgrade(&module());
+        assert_eq!(ok.knowledge_factor_k, 0.90);
+        assert_eq!(ok.dracula_wave_dw, 0.1);
+
+        let cationic = cell("b", 35.0).evaluate_upgrade(&module());
+        assert_eq!(cationic.knowledge_factor_k, 0.70);
+        assert_eq!(cationic.dracula_wave_dw, 0.30);
+    }
+
+    #[test]
+    fn cells_export_side_by_side() {
+        let cells = [cell("a", -20.0), cell("b", -20.0)];
+        let doc = export_cells_gltf(
+            "grid",
+            &cells,
+            &[],
+            &SwarmMode::Normal,
+            &GltfExportOptions::default(),
+        );
+        let nodes = doc.json["nodes"].as_array().unwrap();
+        assert_eq!(nodes.len(), 3);
+        assert_eq!(nodes[1]["name"], "a");
+        assert_eq!(nodes[2]["name"], "b");
+        let x = |n: usize| nodes[n]["translation"][0].as_f64().unwrap();
+        // Octahedra of circumradius 10 nm, 10 nm apart.
+        assert!((x(2) - x(1) - 30.0).abs() < 1e-9);
+    }
+}
@@ -1,10 +1,10 @@
-use crate::xrlabgrid::nanopoly::nanoswarm::{NanoswarmMember, Nanoswarm};
+use crate::xr_lab_grid::nanopoly::nanoswarm::Nanoswarm;
 use crate::store::metrics::ResponseMetric;
 
 impl Nanoswarm {
     pub fn evaluate_planning_session(&self) -> ResponseMetric {
         // Re-use aggregate energy & DW, treat as planning-session envelope
-        let base = self.check_policy();
+        let base = self.aggregate_metric();
         ResponseMetric::new(
             base.knowledge_factor_k,
             base.demand_d,